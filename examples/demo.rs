@@ -1,9 +1,11 @@
 //! Demo of all three celebration types.
 //! Run: cargo run --example demo
 
+use cwinner_lib::animation::EpicStyle;
 use cwinner_lib::celebration::CelebrationLevel;
-use cwinner_lib::renderer::render;
+use cwinner_lib::renderer::{render, render_level_up};
 use cwinner_lib::state::State;
+use cwinner_lib::theme::Theme;
 use std::io::{self, Write};
 
 fn main() {
@@ -21,13 +23,16 @@ fn main() {
     println!("2) Medium — toast (1.5s)");
     println!("3) Medium — achievement toast (2.5s)");
     println!("4) Epic   — confetti + splash (6.5s)");
+    println!("5) Level up — block-letter banner (5s)");
     println!("q) Quit");
     println!();
 
     let tty = "/dev/tty".to_string();
+    let theme = Theme::from_config(&cwinner_lib::config::Config::load().visual);
+    let style = EpicStyle::default();
 
     loop {
-        print!("Choose [1-4/q]: ");
+        print!("Choose [1-5/q]: ");
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
@@ -36,7 +41,15 @@ fn main() {
         match input.trim() {
             "1" => {
                 println!("  -> Mini celebration...");
-                render(&tty, &CelebrationLevel::Mini, &state, None, None);
+                render(
+                    &tty,
+                    &CelebrationLevel::Mini,
+                    &state,
+                    &[],
+                    None,
+                    &theme,
+                    &style,
+                );
                 println!("  Done!");
             }
             "2" => {
@@ -45,8 +58,10 @@ fn main() {
                     &tty,
                     &CelebrationLevel::Medium,
                     &state,
-                    None,
+                    &[],
                     Some("✓ Task Completed"),
+                    &theme,
+                    &style,
                 );
                 println!("  Done!");
             }
@@ -56,8 +71,10 @@ fn main() {
                     &tty,
                     &CelebrationLevel::Medium,
                     &state,
-                    Some("First Commit — Made your first git commit"),
+                    &["First Commit — Made your first git commit".into()],
                     Some("📝 Git Commit"),
+                    &theme,
+                    &style,
                 );
                 println!("  Done!");
             }
@@ -67,16 +84,26 @@ fn main() {
                     &tty,
                     &CelebrationLevel::Epic,
                     &state,
-                    Some("Shipped It — First git push"),
+                    &[
+                        "Shipped It — First git push".into(),
+                        "Prompt Whisperer — Reached level 2".into(),
+                    ],
                     Some("🚀 Git Push"),
+                    &theme,
+                    &style,
                 );
                 println!("  Done!");
             }
+            "5" => {
+                println!("  -> Level up...");
+                let _ = render_level_up(&tty, &state, None, &[], &theme, &style);
+                println!("  Done!");
+            }
             "q" | "Q" => {
                 println!("Bye!");
                 break;
             }
-            _ => println!("  Invalid choice, try 1-4 or q"),
+            _ => println!("  Invalid choice, try 1-5 or q"),
         }
         println!();
     }
@@ -1,9 +1,12 @@
-//! Non-interactive demo for recording.
+//! Non-interactive demo for recording with a live screen recorder.
 //! Run: cargo run --example record
+//! (`cwinner render --export` records a single celebration without a terminal.)
 
+use cwinner_lib::animation::EpicStyle;
 use cwinner_lib::celebration::CelebrationLevel;
 use cwinner_lib::renderer::{render, render_progress_bar};
 use cwinner_lib::state::State;
+use cwinner_lib::theme::Theme;
 use std::thread;
 use std::time::Duration;
 
@@ -18,6 +21,8 @@ fn main() {
     };
 
     let tty = "/dev/tty".to_string();
+    let theme = Theme::from_config(&cwinner_lib::config::Config::load().visual);
+    let style = EpicStyle::default();
 
     println!("\x1b[1;36m  cwinner — gamification for Claude Code\x1b[0m");
     println!();
@@ -25,7 +30,7 @@ fn main() {
 
     println!("\x1b[33m  ▸ Mini — progress bar (bottom of screen)\x1b[0m");
     thread::sleep(Duration::from_millis(800));
-    let _ = render_progress_bar(&tty, &state);
+    let _ = render_progress_bar(&tty, &state, &theme);
     thread::sleep(Duration::from_millis(1000));
 
     println!("\x1b[33m  ▸ Medium — task completed\x1b[0m");
@@ -34,8 +39,10 @@ fn main() {
         &tty,
         &CelebrationLevel::Medium,
         &state,
-        None,
+        &[],
         Some("✓ Task Completed"),
+        &theme,
+        &style,
     );
     thread::sleep(Duration::from_millis(1000));
 
@@ -45,8 +52,10 @@ fn main() {
         &tty,
         &CelebrationLevel::Medium,
         &state,
-        Some("First Commit — Made your first git commit"),
+        &["First Commit — Made your first git commit".into()],
         Some("📝 Git Commit"),
+        &theme,
+        &style,
     );
     thread::sleep(Duration::from_millis(1000));
 
@@ -56,8 +65,10 @@ fn main() {
         &tty,
         &CelebrationLevel::Epic,
         &state,
-        Some("Shipped It — First git push"),
+        &["Shipped It — First git push".into()],
         Some("🚀 Git Push"),
+        &theme,
+        &style,
     );
     thread::sleep(Duration::from_millis(500));
 
@@ -1,5 +1,6 @@
 use crate::event::{Event, EventKind};
 use crate::state::State;
+use crate::streak::StreakKind;
 
 pub struct Achievement {
     pub id: &'static str,
@@ -29,7 +30,7 @@ pub static REGISTRY: &[Achievement] = &[
         name: "Centurion",
         description: "100 commits total",
     },
-    // Streaks (3)
+    // Streaks (7)
     Achievement {
         id: "streak_5",
         name: "On a Roll",
@@ -45,6 +46,26 @@ pub static REGISTRY: &[Achievement] = &[
         name: "Dedicated",
         description: "25-day commit streak",
     },
+    Achievement {
+        id: "session_streak_7",
+        name: "Regular",
+        description: "Coded with Claude 7 days in a row",
+    },
+    Achievement {
+        id: "test_streak_7",
+        name: "Green Week",
+        description: "Passing tests 7 days in a row",
+    },
+    Achievement {
+        id: "push_streak_7",
+        name: "Ship It Daily",
+        description: "Pushed 7 days in a row",
+    },
+    Achievement {
+        id: "tool_streak_100",
+        name: "Clean Run",
+        description: "100 tool calls in a row without a failure",
+    },
     // Push (1)
     Achievement {
         id: "first_push",
@@ -209,7 +230,7 @@ pub static REGISTRY: &[Achievement] = &[
 ];
 
 /// Check if a bash command looks like running a test suite.
-fn is_test_command(command: &str) -> bool {
+pub fn is_test_command(command: &str) -> bool {
     command
         .split("&&")
         .flat_map(|s| s.split(';'))
@@ -252,6 +273,10 @@ fn is_unlocked(a: &Achievement, state: &State, event: &Event) -> bool {
         "streak_5" => state.commit_streak_days >= 5,
         "streak_10" => state.commit_streak_days >= 10,
         "streak_25" => state.commit_streak_days >= 25,
+        "session_streak_7" => state.streak_count(StreakKind::Session) >= 7,
+        "test_streak_7" => state.streak_count(StreakKind::Test) >= 7,
+        "push_streak_7" => state.streak_count(StreakKind::Push) >= 7,
+        "tool_streak_100" => state.streak_count(StreakKind::Tool) >= 100,
         "first_push" => {
             event.event == EventKind::GitPush
                 || (event.event == EventKind::PostToolUse
@@ -305,7 +330,7 @@ mod tests {
 
     #[test]
     fn test_registry_has_expected_achievements() {
-        assert_eq!(REGISTRY.len(), 38);
+        assert_eq!(REGISTRY.len(), 42);
     }
 
     #[test]
@@ -433,6 +458,20 @@ mod tests {
         assert!(unlocked.iter().any(|a| a.id == "streak_25"));
     }
 
+    #[test]
+    fn test_other_streak_achievements_unlock() {
+        let mut s = State::default();
+        for kind in [StreakKind::Test, StreakKind::Push] {
+            s.streaks.entry(kind).or_default().count = 7;
+        }
+        s.streaks.entry(StreakKind::Tool).or_default().count = 99;
+        let unlocked = check_achievements(&s, &ev(EventKind::PostToolUse, None));
+        assert!(unlocked.iter().any(|a| a.id == "test_streak_7"));
+        assert!(unlocked.iter().any(|a| a.id == "push_streak_7"));
+        assert!(!unlocked.iter().any(|a| a.id == "session_streak_7"));
+        assert!(!unlocked.iter().any(|a| a.id == "tool_streak_100"));
+    }
+
     #[test]
     fn test_first_push_unlocks_on_git_push() {
         let s = State::default();
@@ -0,0 +1,717 @@
+//! Epic celebration animations. Each effect is a small simulation stepped
+//! once per frame from a seeded RNG, so the same seed and scene always give
+//! the same frames.
+
+use crate::celebration::Celebration;
+use crate::config::{Animation, AnimationConfig};
+use crate::event::EventKind;
+use crate::font;
+use crate::renderer::truncate_to_width;
+use crate::theme::{Theme, ThemeColor};
+use rand::rngs::Xoshiro256PlusPlus;
+use rand::{RngExt, SeedableRng};
+use std::collections::VecDeque;
+use unicode_width::UnicodeWidthStr;
+
+/// Time between frames.
+pub const FRAME_MS: u64 = 50;
+/// Frames of animation before the splash box appears (3s).
+pub const INTRO_FRAMES: u32 = 60;
+/// Frames the splash box stays up over the animation (3.5s).
+pub const SPLASH_FRAMES: u32 = 70;
+
+/// How to play an epic celebration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpicStyle {
+    pub animation: Animation,
+    pub seed: u64,
+}
+
+impl EpicStyle {
+    /// The configured animation for `c`, seeded from `seed` or at random.
+    pub fn for_celebration(cfg: &AnimationConfig, seed: Option<u64>, c: &Celebration) -> Self {
+        Self {
+            animation: animation_for(cfg, c),
+            seed: seed.unwrap_or_else(rand::random),
+        }
+    }
+}
+
+impl Default for EpicStyle {
+    fn default() -> Self {
+        Self {
+            animation: Animation::default(),
+            seed: rand::random(),
+        }
+    }
+}
+
+/// Pick the animation for a celebration: level-ups first, then streak
+/// milestones, then the event kind, then the default.
+pub fn animation_for(cfg: &AnimationConfig, c: &Celebration) -> Animation {
+    let by_event = match c.event {
+        EventKind::GitCommit => cfg.git_commit,
+        EventKind::GitPush => cfg.git_push,
+        EventKind::TaskCompleted => cfg.task_completed,
+        EventKind::SessionEnd => cfg.session_end,
+        _ => None,
+    };
+    c.leveled_up
+        .then_some(cfg.level_up)
+        .flatten()
+        .or(c.streak_milestone.is_some().then_some(cfg.streak).flatten())
+        .or(by_event)
+        .unwrap_or(cfg.default)
+}
+
+/// Something to draw at a screen position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sprite {
+    pub col: u16,
+    pub row: u16,
+    pub text: String,
+    pub color: ThemeColor,
+}
+
+/// What an effect is drawn for: the screen size and the splash content.
+#[derive(Debug, Clone)]
+pub struct Scene {
+    pub cols: u16,
+    pub rows: u16,
+    pub level: u32,
+    /// Splash lines (label, achievements, level) with their colors.
+    pub lines: Vec<(String, ThemeColor)>,
+}
+
+/// One animation. `frame` is called with t = 0, 1, 2, … and returns
+/// everything visible in that frame; the screen is cleared in between.
+pub trait Effect {
+    fn frame(&mut self, t: u32) -> Vec<Sprite>;
+}
+
+pub fn effect(kind: Animation, scene: &Scene, theme: &Theme, seed: u64) -> Box<dyn Effect> {
+    let rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+    let field = Field {
+        cols: scene.cols.max(1),
+        // Keep the bottom row free, as the cursor may sit there
+        rows: scene.rows.saturating_sub(1).max(1),
+    };
+    let colors = theme.palette.confetti.clone();
+    match kind {
+        Animation::Confetti => Box::new(Confetti {
+            rng,
+            field,
+            colors,
+            glyphs: theme.glyphs.confetti.clone(),
+            pieces: VecDeque::new(),
+        }),
+        Animation::Fireworks => Box::new(Fireworks {
+            rng,
+            field,
+            colors,
+            rockets: Vec::new(),
+            sparks: Vec::new(),
+        }),
+        Animation::Rain => Box::new(Rain {
+            rng,
+            field,
+            colors,
+            drops: Vec::new(),
+            splashes: Vec::new(),
+        }),
+        Animation::Starfield => Box::new(Starfield {
+            rng,
+            field,
+            colors,
+            stars: Vec::new(),
+        }),
+        Animation::Banner => Box::new(Banner {
+            rng,
+            field,
+            colors,
+            letters: font::render(&format!("Lvl {}", scene.level), theme.glyphs.bar_filled),
+            color: theme.palette.level,
+            glyphs: theme.glyphs.confetti.clone(),
+        }),
+        Animation::Typewriter => Box::new(Typewriter {
+            field,
+            lines: scene
+                .lines
+                .iter()
+                .map(|(text, color)| {
+                    let text = theme.text(text);
+                    (
+                        truncate_to_width(&text, field.cols as usize).to_string(),
+                        *color,
+                    )
+                })
+                .collect(),
+            cursor: theme.palette.level,
+        }),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Field {
+    cols: u16,
+    rows: u16,
+}
+
+impl Field {
+    /// Position for a point in simulation space, if it's on screen.
+    fn cell(&self, x: f32, y: f32) -> Option<(u16, u16)> {
+        let (col, row) = (x.round(), y.round());
+        (col >= 0.0 && row >= 0.0 && col < self.cols as f32 && row < self.rows as f32)
+            .then_some((col as u16, row as u16))
+    }
+}
+
+fn pick<T: Copy>(rng: &mut Xoshiro256PlusPlus, items: &[T]) -> T {
+    items[rng.random_range(0..items.len())]
+}
+
+/// Pieces pile up at random until the screen is a quarter full.
+struct Confetti {
+    rng: Xoshiro256PlusPlus,
+    field: Field,
+    colors: Vec<ThemeColor>,
+    glyphs: Vec<char>,
+    pieces: VecDeque<Sprite>,
+}
+
+impl Effect for Confetti {
+    fn frame(&mut self, _t: u32) -> Vec<Sprite> {
+        let Field { cols, rows } = self.field;
+        let max = (cols as usize * rows as usize / 4).max(1);
+        for _ in 0..(cols / 8).max(1) {
+            let piece = Sprite {
+                col: self.rng.random_range(0..cols),
+                row: self.rng.random_range(0..rows),
+                text: pick(&mut self.rng, &self.glyphs).to_string(),
+                color: pick(&mut self.rng, &self.colors),
+            };
+            self.pieces.push_back(piece);
+            if self.pieces.len() > max {
+                self.pieces.pop_front();
+            }
+        }
+        self.pieces.iter().cloned().collect()
+    }
+}
+
+const GRAVITY: f32 = 0.08;
+
+struct Rocket {
+    x: f32,
+    y: f32,
+    apex: f32,
+    color: ThemeColor,
+}
+
+struct Spark {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    age: u32,
+    color: ThemeColor,
+}
+
+/// Rockets climb from the bottom and burst into sparks that fall and fade.
+struct Fireworks {
+    rng: Xoshiro256PlusPlus,
+    field: Field,
+    colors: Vec<ThemeColor>,
+    rockets: Vec<Rocket>,
+    sparks: Vec<Spark>,
+}
+
+impl Fireworks {
+    const SPARKS: usize = 16;
+    const SPARK_LIFE: u32 = 18;
+
+    fn burst(&mut self, x: f32, y: f32, color: ThemeColor) {
+        for i in 0..Self::SPARKS {
+            let angle = i as f32 * std::f32::consts::TAU / Self::SPARKS as f32;
+            let speed = self.rng.random_range(0.6..1.2);
+            self.sparks.push(Spark {
+                x,
+                y,
+                // Cells are about twice as tall as wide
+                vx: angle.cos() * speed * 2.0,
+                vy: angle.sin() * speed,
+                age: 0,
+                color,
+            });
+        }
+    }
+}
+
+impl Effect for Fireworks {
+    fn frame(&mut self, t: u32) -> Vec<Sprite> {
+        let Field { cols, rows } = self.field;
+        if t % 10 == 0 || (self.rockets.is_empty() && self.rng.random_range(0..6) == 0) {
+            let (lo, hi) = (cols as f32 / 6.0, cols as f32 * 5.0 / 6.0);
+            self.rockets.push(Rocket {
+                x: self.rng.random_range(lo..hi.max(lo + 1.0)),
+                y: rows as f32 - 1.0,
+                apex: self
+                    .rng
+                    .random_range(rows as f32 / 6.0..(rows as f32 / 2.0).max(1.0)),
+                color: pick(&mut self.rng, &self.colors),
+            });
+        }
+
+        let mut bursts = Vec::new();
+        self.rockets.retain_mut(|r| {
+            r.y -= 1.0;
+            let done = r.y <= r.apex;
+            if done {
+                bursts.push((r.x, r.y, r.color));
+            }
+            !done
+        });
+        for (x, y, color) in bursts {
+            self.burst(x, y, color);
+        }
+        for s in &mut self.sparks {
+            s.x += s.vx;
+            s.y += s.vy;
+            s.vx *= 0.9;
+            s.vy += GRAVITY;
+            s.age += 1;
+        }
+        let field = self.field;
+        self.sparks
+            .retain(|s| s.age < Self::SPARK_LIFE && s.y < field.rows as f32);
+
+        let rockets = self.rockets.iter().filter_map(|r| {
+            let (col, row) = field.cell(r.x, r.y)?;
+            Some(Sprite {
+                col,
+                row,
+                text: "|".into(),
+                color: r.color,
+            })
+        });
+        let sparks = self.sparks.iter().filter_map(|s| {
+            let (col, row) = field.cell(s.x, s.y)?;
+            let glyph = match s.age * 3 / Self::SPARK_LIFE {
+                0 => "*",
+                1 => "+",
+                _ => ".",
+            };
+            Some(Sprite {
+                col,
+                row,
+                text: glyph.into(),
+                color: s.color,
+            })
+        });
+        rockets.chain(sparks).collect()
+    }
+}
+
+struct Drop {
+    col: u16,
+    y: f32,
+    vy: f32,
+    color: ThemeColor,
+}
+
+/// Drops fall under gravity, stretching as they speed up, and splash at the
+/// bottom.
+struct Rain {
+    rng: Xoshiro256PlusPlus,
+    field: Field,
+    colors: Vec<ThemeColor>,
+    drops: Vec<Drop>,
+    /// Column and frames left.
+    splashes: Vec<(u16, u32, ThemeColor)>,
+}
+
+impl Effect for Rain {
+    fn frame(&mut self, _t: u32) -> Vec<Sprite> {
+        let Field { cols, rows } = self.field;
+        for _ in 0..(cols / 10).max(1) {
+            self.drops.push(Drop {
+                col: self.rng.random_range(0..cols),
+                y: 0.0,
+                vy: self.rng.random_range(0.2..0.5),
+                color: pick(&mut self.rng, &self.colors),
+            });
+        }
+        for s in &mut self.splashes {
+            s.1 -= 1;
+        }
+        self.splashes.retain(|s| s.1 > 0);
+        let bottom = rows as f32 - 1.0;
+        let mut landed = Vec::new();
+        self.drops.retain_mut(|d| {
+            d.vy += GRAVITY * 2.0;
+            d.y += d.vy;
+            let hit = d.y >= bottom;
+            if hit {
+                landed.push((d.col, 3, d.color));
+            }
+            !hit
+        });
+        self.splashes.extend(landed);
+
+        let drops = self.drops.iter().map(|d| Sprite {
+            col: d.col,
+            row: d.y as u16,
+            text: if d.vy > 1.0 { "|" } else { "'" }.into(),
+            color: d.color,
+        });
+        let splashes = self.splashes.iter().map(|&(col, left, color)| Sprite {
+            col,
+            row: rows - 1,
+            text: if left > 1 { "o" } else { "." }.into(),
+            color,
+        });
+        drops.chain(splashes).collect()
+    }
+}
+
+struct Star {
+    x: f32,
+    y: f32,
+    vx: f32,
+    vy: f32,
+    age: u32,
+    color: ThemeColor,
+}
+
+/// Stars stream out from the center, speeding up as they approach.
+struct Starfield {
+    rng: Xoshiro256PlusPlus,
+    field: Field,
+    colors: Vec<ThemeColor>,
+    stars: Vec<Star>,
+}
+
+impl Effect for Starfield {
+    fn frame(&mut self, _t: u32) -> Vec<Sprite> {
+        let Field { cols, rows } = self.field;
+        let (cx, cy) = (cols as f32 / 2.0, rows as f32 / 2.0);
+        for _ in 0..(cols / 16).max(1) + 1 {
+            let angle = self.rng.random_range(0.0..std::f32::consts::TAU);
+            let speed = self.rng.random_range(0.1..0.4);
+            self.stars.push(Star {
+                x: cx,
+                y: cy,
+                vx: angle.cos() * speed * 2.0,
+                vy: angle.sin() * speed,
+                age: 0,
+                color: pick(&mut self.rng, &self.colors),
+            });
+        }
+        for s in &mut self.stars {
+            let boost = 1.0 + s.age as f32 * 0.15;
+            s.x += s.vx * boost;
+            s.y += s.vy * boost;
+            s.age += 1;
+        }
+        let field = self.field;
+        self.stars.retain(|s| field.cell(s.x, s.y).is_some());
+        self.stars
+            .iter()
+            .filter_map(|s| {
+                let (col, row) = field.cell(s.x, s.y)?;
+                let glyph = match s.age {
+                    0..6 => ".",
+                    6..12 => "+",
+                    _ => "*",
+                };
+                Some(Sprite {
+                    col,
+                    row,
+                    text: glyph.into(),
+                    color: s.color,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The level number in block letters rises from the bottom to sit above the
+/// splash box, then twinkles.
+struct Banner {
+    rng: Xoshiro256PlusPlus,
+    field: Field,
+    colors: Vec<ThemeColor>,
+    letters: Vec<String>,
+    color: ThemeColor,
+    glyphs: Vec<char>,
+}
+
+impl Banner {
+    /// Frames to reach the resting position.
+    const RISE: u32 = 30;
+}
+
+impl Effect for Banner {
+    fn frame(&mut self, t: u32) -> Vec<Sprite> {
+        let Field { cols, rows } = self.field;
+        let height = font::HEIGHT as f32;
+        // Rest just above the splash box (about seven rows around the middle)
+        let rest = (rows as f32 / 2.0 - 4.0 - height).max(0.0);
+        let start = rows as f32;
+        let p = (t as f32 / Self::RISE as f32).min(1.0);
+        let eased = 1.0 - (1.0 - p) * (1.0 - p);
+        let top = (start + (rest - start) * eased).round() as i32;
+
+        let width = self.letters.first().map_or(0, |l| l.width());
+        let visible = width.min(cols as usize);
+        let left = (cols as usize - visible) / 2;
+        let mut sprites: Vec<Sprite> = self
+            .letters
+            .iter()
+            .enumerate()
+            .filter_map(|(i, line)| {
+                let row = top + i as i32;
+                (row >= 0 && row < rows as i32).then(|| Sprite {
+                    col: left as u16,
+                    row: row as u16,
+                    text: line.chars().take(visible).collect(),
+                    color: self.color,
+                })
+            })
+            .collect();
+
+        if t >= Self::RISE {
+            for _ in 0..(cols / 12).max(1) {
+                sprites.push(Sprite {
+                    col: self.rng.random_range(0..cols),
+                    row: self.rng.random_range(0..rows),
+                    text: pick(&mut self.rng, &self.glyphs).to_string(),
+                    color: pick(&mut self.rng, &self.colors),
+                });
+            }
+        }
+        sprites
+    }
+}
+
+/// The splash text typed out a character at a time, centered where the
+/// splash box will show it. Clears once the box is up.
+struct Typewriter {
+    field: Field,
+    lines: Vec<(String, ThemeColor)>,
+    cursor: ThemeColor,
+}
+
+impl Effect for Typewriter {
+    fn frame(&mut self, t: u32) -> Vec<Sprite> {
+        if t >= INTRO_FRAMES {
+            return Vec::new();
+        }
+        let Field { cols, rows } = self.field;
+        let total: usize = self.lines.iter().map(|(l, _)| l.chars().count()).sum();
+        // Finish typing two-thirds of the way through the intro
+        let per_frame = total.div_ceil(INTRO_FRAMES as usize * 2 / 3).max(1);
+        let mut budget = (t as usize + 1) * per_frame;
+        let top = (rows / 2).saturating_sub(self.lines.len() as u16 / 2);
+
+        let mut sprites = Vec::new();
+        for (i, (line, color)) in self.lines.iter().enumerate() {
+            let row = top + i as u16;
+            if row >= rows {
+                break;
+            }
+            let len = line.chars().count();
+            let shown: String = line.chars().take(budget).collect();
+            let width = line.width().min(cols as usize);
+            let col = ((cols as usize - width) / 2) as u16;
+            let typing = budget < len;
+            let end = col as usize + shown.width();
+            budget = budget.saturating_sub(len);
+            sprites.push(Sprite {
+                col,
+                row,
+                text: shown,
+                color: *color,
+            });
+            let blink = !typing && budget == 0 && (t / 6) % 2 == 0;
+            if (typing || blink) && end < cols as usize {
+                sprites.push(Sprite {
+                    col: end as u16,
+                    row,
+                    text: "_".into(),
+                    color: self.cursor,
+                });
+            }
+            if typing {
+                break;
+            }
+        }
+        sprites
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::celebration::CelebrationLevel;
+    use crate::streak::StreakKind;
+    use crossterm::style::Color;
+
+    fn scene(cols: u16, rows: u16) -> Scene {
+        Scene {
+            cols,
+            rows,
+            level: 12,
+            lines: vec![
+                ("🚀 Git Push".into(), ThemeColor::Named(Color::Green)),
+                (
+                    "🏆 Shipped It — First git push".into(),
+                    ThemeColor::Named(Color::Yellow),
+                ),
+                (
+                    "Lvl 12 Vibe Architect ✦ 4200 XP".into(),
+                    ThemeColor::Named(Color::Cyan),
+                ),
+            ],
+        }
+    }
+
+    fn frames(kind: Animation, scene: &Scene, seed: u64) -> Vec<Vec<Sprite>> {
+        let mut e = effect(kind, scene, &Theme::default(), seed);
+        (0..INTRO_FRAMES + SPLASH_FRAMES)
+            .map(|t| e.frame(t))
+            .collect()
+    }
+
+    fn celebration(event: EventKind) -> Celebration {
+        Celebration {
+            level: CelebrationLevel::Epic,
+            event,
+            achievements: vec![],
+            label: None,
+            streak_milestone: None,
+            leveled_up: false,
+            previous_level: None,
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_frames() {
+        let s = scene(80, 24);
+        for kind in Animation::ALL {
+            assert_eq!(frames(kind, &s, 7), frames(kind, &s, 7), "{kind:?}");
+        }
+        assert_ne!(
+            frames(Animation::Confetti, &s, 7),
+            frames(Animation::Confetti, &s, 8)
+        );
+    }
+
+    #[test]
+    fn test_sprites_stay_on_screen() {
+        for (cols, rows) in [(80, 24), (20, 6), (1, 1)] {
+            let s = scene(cols, rows);
+            for kind in Animation::ALL {
+                for (t, frame) in frames(kind, &s, 3).iter().enumerate() {
+                    for sp in frame {
+                        assert!(sp.row < rows.max(1), "{kind:?} {cols}x{rows} t={t}: {sp:?}");
+                        assert!(
+                            sp.col as usize + sp.text.width() <= cols as usize,
+                            "{kind:?} {cols}x{rows} t={t}: {sp:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fireworks_burst_into_sparks() {
+        let all = frames(Animation::Fireworks, &scene(80, 24), 1);
+        assert!(all[0].iter().any(|s| s.text == "|"));
+        assert!(
+            all.iter()
+                .any(|f| f.iter().filter(|s| s.text == "*").count() >= 8)
+        );
+    }
+
+    #[test]
+    fn test_rain_accelerates() {
+        let mut rain = Rain {
+            rng: Xoshiro256PlusPlus::seed_from_u64(0),
+            field: Field { cols: 1, rows: 40 },
+            colors: vec![ThemeColor::Default],
+            drops: Vec::new(),
+            splashes: Vec::new(),
+        };
+        let ys: Vec<f32> = (0..6)
+            .map(|t| {
+                rain.frame(t);
+                rain.drops[0].y
+            })
+            .collect();
+        let steps: Vec<f32> = ys.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(steps.windows(2).all(|w| w[1] > w[0]), "{ys:?}");
+    }
+
+    #[test]
+    fn test_banner_rises_to_rest() {
+        let all = frames(Animation::Banner, &scene(80, 24), 1);
+        let top = |f: &Vec<Sprite>| f.iter().map(|s| s.row).min();
+        assert_eq!(top(&all[0]), None, "starts below the screen");
+        assert!(top(&all[10]) > top(&all[20]));
+        assert_eq!(top(&all[Banner::RISE as usize]), Some(3));
+        let rows: Vec<_> = all[Banner::RISE as usize]
+            .iter()
+            .take(font::HEIGHT)
+            .collect();
+        assert_eq!(rows[4].text, font::render("Lvl 12", '█')[4]);
+    }
+
+    #[test]
+    fn test_typewriter_reveals_text() {
+        let s = scene(80, 24);
+        let all = frames(Animation::Typewriter, &s, 0);
+        let text = |f: &Vec<Sprite>| {
+            f.iter()
+                .filter(|s| s.text != "_")
+                .map(|s| s.text.clone())
+                .collect::<String>()
+        };
+        assert!("🚀 Git Push".starts_with(&text(&all[0])));
+        assert!(!text(&all[0]).is_empty());
+        assert!(text(&all[10]).len() < text(&all[20]).len());
+        let full: String = s.lines.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(text(&all[INTRO_FRAMES as usize - 1]), full);
+        assert!(all[INTRO_FRAMES as usize].is_empty());
+    }
+
+    #[test]
+    fn test_animation_for() {
+        let cfg = AnimationConfig {
+            default: Animation::Rain,
+            level_up: Some(Animation::Banner),
+            git_push: Some(Animation::Fireworks),
+            ..Default::default()
+        };
+        assert_eq!(
+            animation_for(&cfg, &celebration(EventKind::GitPush)),
+            Animation::Fireworks
+        );
+        assert_eq!(
+            animation_for(&cfg, &celebration(EventKind::GitCommit)),
+            Animation::Rain
+        );
+        let c = Celebration {
+            leveled_up: true,
+            ..celebration(EventKind::GitPush)
+        };
+        assert_eq!(animation_for(&cfg, &c), Animation::Banner);
+        // Unset streak falls through to the event
+        let c = Celebration {
+            streak_milestone: Some(StreakKind::Commit),
+            ..celebration(EventKind::GitPush)
+        };
+        assert_eq!(animation_for(&cfg, &c), Animation::Fireworks);
+    }
+}
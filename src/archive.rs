@@ -0,0 +1,107 @@
+//! Portable progress archives for `cwinner state export` / `import`: the
+//! state as JSON, wrapped with a format version and a SHA-256 checksum.
+
+use crate::state::State;
+use anyhow::{Context, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Marks a file as a cwinner archive.
+pub const FORMAT: &str = "cwinner-progress";
+/// Archive layout version. The state inside carries its own
+/// `schema_version` and is migrated on import like `state.json`.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Archive {
+    format: String,
+    version: u32,
+    exported_at: DateTime<Utc>,
+    /// cwinner version that wrote the archive, for bug reports.
+    cwinner: String,
+    /// SHA-256 of `state` serialized compactly.
+    sha256: String,
+    state: Value,
+}
+
+fn digest(state: &Value) -> anyhow::Result<String> {
+    crate::update::sha256_of_bytes(serde_json::to_string(state)?.as_bytes())
+}
+
+/// `state` as an archive, without what only matters on this machine.
+pub fn export(state: &State) -> anyhow::Result<String> {
+    let mut state = state.clone();
+    state.keep_local(State::default());
+    let state = serde_json::to_value(&state)?;
+    let archive = Archive {
+        format: FORMAT.into(),
+        version: ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        cwinner: env!("CARGO_PKG_VERSION").into(),
+        sha256: digest(&state)?,
+        state,
+    };
+    Ok(serde_json::to_string_pretty(&archive)? + "\n")
+}
+
+/// The state in an archive, after checking its format and checksum.
+pub fn import(data: &str) -> anyhow::Result<State> {
+    let archive: Archive = serde_json::from_str(data).context("not a cwinner archive")?;
+    if archive.format != FORMAT {
+        bail!("not a cwinner archive (format {:?})", archive.format);
+    }
+    if archive.version > ARCHIVE_VERSION {
+        bail!(
+            "archive is v{}, newer than this cwinner understands (v{ARCHIVE_VERSION}); upgrade cwinner",
+            archive.version
+        );
+    }
+    if digest(&archive.state)? != archive.sha256.to_ascii_lowercase() {
+        bail!("archive checksum mismatch; the file was modified or damaged");
+    }
+    let (state, _) = State::from_json(&archive.state.to_string())?;
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let mut state = State::default();
+        state.add_xp(1200);
+        state.unlock_achievement("first_commit");
+        let data = export(&state).unwrap();
+        assert!(data.contains("\"format\": \"cwinner-progress\""));
+        let back = import(&data).unwrap();
+        assert_eq!((back.xp, back.level), (1200, state.level));
+        assert_eq!(back.achievements_unlocked, vec!["first_commit"]);
+    }
+
+    #[test]
+    fn test_export_leaves_out_local_fields() {
+        let mut state = State::default();
+        state.add_xp(100);
+        state.xp_ledger.total = 100;
+        let back = import(&export(&state).unwrap()).unwrap();
+        assert_eq!(back.xp, 100);
+        assert_eq!(back.xp_ledger, Default::default());
+    }
+
+    #[test]
+    fn test_import_rejects_tampering() {
+        let mut state = State::default();
+        state.add_xp(100);
+        let data = export(&state).unwrap();
+
+        let tampered = data.replace("\"xp\": 100", "\"xp\": 100000");
+        let err = import(&tampered).unwrap_err();
+        assert!(err.to_string().contains("checksum"), "{err}");
+
+        let future = data.replace("\"version\": 1", "\"version\": 99");
+        assert!(import(&future).unwrap_err().to_string().contains("newer"));
+        assert!(import("{}").is_err());
+    }
+}
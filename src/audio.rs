@@ -1,18 +1,46 @@
 use crate::celebration::CelebrationLevel;
 use crate::config::AudioConfig;
+use crate::event::EventKind;
+use crate::streak::StreakKind;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum SoundKind {
     Mini,
     Milestone,
     Epic,
     Fanfare,
     Streak,
+    SessionStreak,
+    TestStreak,
+    PushStreak,
+    ToolStreak,
+    LevelUp,
+    Achievement,
+    Quest,
+    SessionEnd,
+    Failure,
 }
 
 impl SoundKind {
+    pub const ALL: &'static [SoundKind] = &[
+        SoundKind::Mini,
+        SoundKind::Milestone,
+        SoundKind::Epic,
+        SoundKind::Fanfare,
+        SoundKind::Streak,
+        SoundKind::SessionStreak,
+        SoundKind::TestStreak,
+        SoundKind::PushStreak,
+        SoundKind::ToolStreak,
+        SoundKind::LevelUp,
+        SoundKind::Achievement,
+        SoundKind::Quest,
+        SoundKind::SessionEnd,
+        SoundKind::Failure,
+    ];
+
     pub fn name(&self) -> &'static str {
         match self {
             SoundKind::Mini => "mini",
@@ -20,59 +48,125 @@ impl SoundKind {
             SoundKind::Epic => "epic",
             SoundKind::Fanfare => "fanfare",
             SoundKind::Streak => "streak",
+            SoundKind::SessionStreak => "streak_session",
+            SoundKind::TestStreak => "streak_test",
+            SoundKind::PushStreak => "streak_push",
+            SoundKind::ToolStreak => "streak_tool",
+            SoundKind::LevelUp => "levelup",
+            SoundKind::Achievement => "achievement",
+            SoundKind::Quest => "quest",
+            SoundKind::SessionEnd => "session_end",
+            SoundKind::Failure => "failure",
+        }
+    }
+
+    /// The sound for a streak milestone.
+    pub fn for_streak(kind: StreakKind) -> SoundKind {
+        match kind {
+            StreakKind::Commit => SoundKind::Streak,
+            StreakKind::Session => SoundKind::SessionStreak,
+            StreakKind::Test => SoundKind::TestStreak,
+            StreakKind::Push => SoundKind::PushStreak,
+            StreakKind::Tool => SoundKind::ToolStreak,
         }
     }
 }
 
+/// Pick the main sound for a celebration. `event` is the effective event kind
+/// (a Bash `git push` counts as `GitPush`).
 pub fn celebration_to_sound(
     level: &CelebrationLevel,
+    event: &EventKind,
     has_achievement: bool,
-    is_streak_milestone: bool,
+    streak: Option<StreakKind>,
 ) -> Option<SoundKind> {
     match level {
         CelebrationLevel::Off => None,
         CelebrationLevel::Mini => None,
-        CelebrationLevel::Medium => {
-            if has_achievement {
-                Some(SoundKind::Epic)
-            } else {
-                Some(SoundKind::Milestone)
-            }
-        }
-        CelebrationLevel::Epic => {
-            if is_streak_milestone {
-                Some(SoundKind::Streak)
-            } else {
-                Some(SoundKind::Fanfare)
-            }
-        }
+        _ if streak.is_some() => streak.map(SoundKind::for_streak),
+        CelebrationLevel::Medium => Some(match event {
+            _ if has_achievement => SoundKind::Achievement,
+            EventKind::TaskCompleted => SoundKind::Quest,
+            EventKind::SessionEnd => SoundKind::SessionEnd,
+            _ => SoundKind::Milestone,
+        }),
+        CelebrationLevel::Epic => Some(match event {
+            EventKind::SessionEnd => SoundKind::SessionEnd,
+            EventKind::GitPush => SoundKind::Fanfare,
+            _ => SoundKind::Epic,
+        }),
     }
 }
 
-/// Play a sound file using the system audio player.
+/// All sounds for one celebration, in playback order: the main sound, then the
+/// achievement jingle (if the main sound isn't already it), then the level-up,
+/// which replaces the generic milestone sound.
+pub fn celebration_sounds(
+    level: &CelebrationLevel,
+    event: &EventKind,
+    has_achievement: bool,
+    streak: Option<StreakKind>,
+    leveled_up: bool,
+) -> Vec<SoundKind> {
+    let mut sounds: Vec<SoundKind> = celebration_to_sound(level, event, has_achievement, streak)
+        .into_iter()
+        .collect();
+    if has_achievement && !sounds.contains(&SoundKind::Achievement) {
+        sounds.push(SoundKind::Achievement);
+    }
+    if leveled_up {
+        // The level-up is what made a routine event worth a sound
+        sounds.retain(|s| *s != SoundKind::Milestone);
+        sounds.push(SoundKind::LevelUp);
+    }
+    sounds
+}
+
+fn sounds_dir() -> PathBuf {
+    dirs::config_dir()
+        .map(|d| d.join("cwinner").join("sounds"))
+        .unwrap_or_else(|| PathBuf::from("/tmp/cwinner/sounds"))
+}
+
+/// Build the system audio player command for a sound file.
 /// Linux: `aplay -q` (ALSA, works with PipeWire/PulseAudio).
 /// macOS: `afplay`.
-pub fn play_sound(kind: &SoundKind, audio_cfg: &AudioConfig) {
-    let sounds_dir = dirs::config_dir()
-        .map(|d| d.join("cwinner").join("sounds"))
-        .unwrap_or_else(|| PathBuf::from("/tmp/cwinner/sounds"));
+fn player_command(path: &Path) -> Command {
+    if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("afplay");
+        cmd.arg(path);
+        cmd
+    } else {
+        let mut cmd = Command::new("aplay");
+        cmd.arg("-q").arg(path);
+        cmd
+    }
+}
 
-    let Some(path) = find_sound_file(kind, audio_cfg, &sounds_dir) else {
+/// Play a sound file using the system audio player.
+pub fn play_sound(kind: &SoundKind, audio_cfg: &AudioConfig) {
+    let Some(path) = find_sound_file(kind, audio_cfg, &sounds_dir()) else {
         return;
     };
+    let _ = player_command(&path).spawn();
+}
 
-    let path_str = match path.to_str() {
-        Some(s) => s.to_string(),
-        None => return,
-    };
-
-    let (cmd, args) = if cfg!(target_os = "macos") {
-        ("afplay", vec![path_str])
-    } else {
-        ("aplay", vec!["-q".into(), path_str])
-    };
-
-    let _ = Command::new(cmd).args(&args).spawn();
+/// Play several sounds back-to-back on a background thread, waiting for each
+/// player to exit before starting the next so they never overlap.
+pub fn play_sequence(kinds: &[SoundKind], audio_cfg: &AudioConfig) {
+    let dir = sounds_dir();
+    let paths: Vec<PathBuf> = kinds
+        .iter()
+        .filter_map(|k| find_sound_file(k, audio_cfg, &dir))
+        .collect();
+    if paths.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for path in paths {
+            let _ = player_command(&path).status();
+        }
+    });
 }
 
 pub fn find_sound_file(kind: &SoundKind, cfg: &AudioConfig, sounds_dir: &Path) -> Option<PathBuf> {
@@ -84,6 +178,14 @@ pub fn find_sound_file(kind: &SoundKind, cfg: &AudioConfig, sounds_dir: &Path) -
             return Some(p);
         }
     }
+    // Synthesized from a note-sequence spec (see `sounds::SoundSpec`)
+    let spec = pack_dir.join(format!("{name}.toml"));
+    if spec.exists() {
+        match crate::sounds::ensure_spec_sound_file(&spec, &cfg.sound_pack) {
+            Ok(p) => return Some(p),
+            Err(e) => eprintln!("[cwinnerd] sound spec {}: {e:#}", spec.display()),
+        }
+    }
     // Fallback: generate WAV to /tmp/cwinner/
     crate::sounds::ensure_sound_file(kind).ok()
 }
@@ -99,26 +201,126 @@ mod tests {
         assert_eq!(SoundKind::Epic.name(), "epic");
         assert_eq!(SoundKind::Fanfare.name(), "fanfare");
         assert_eq!(SoundKind::Streak.name(), "streak");
+        assert_eq!(SoundKind::TestStreak.name(), "streak_test");
+        assert_eq!(SoundKind::LevelUp.name(), "levelup");
+        assert_eq!(SoundKind::Achievement.name(), "achievement");
+        assert_eq!(SoundKind::Quest.name(), "quest");
+        assert_eq!(SoundKind::SessionEnd.name(), "session_end");
+        assert_eq!(SoundKind::Failure.name(), "failure");
     }
 
     #[test]
     fn test_streak_milestone_uses_streak_sound() {
-        let sound = celebration_to_sound(&CelebrationLevel::Epic, false, true);
+        let sound = celebration_to_sound(
+            &CelebrationLevel::Epic,
+            &EventKind::GitCommit,
+            false,
+            Some(StreakKind::Commit),
+        );
         assert!(matches!(sound, Some(SoundKind::Streak)));
+        let sound = celebration_to_sound(
+            &CelebrationLevel::Medium,
+            &EventKind::PostToolUse,
+            true,
+            Some(StreakKind::Tool),
+        );
+        assert!(matches!(sound, Some(SoundKind::ToolStreak)));
     }
 
     #[test]
-    fn test_non_streak_epic_still_uses_fanfare() {
-        let sound = celebration_to_sound(&CelebrationLevel::Epic, false, false);
+    fn test_non_streak_epic_push_still_uses_fanfare() {
+        let sound = celebration_to_sound(&CelebrationLevel::Epic, &EventKind::GitPush, false, None);
         assert!(matches!(sound, Some(SoundKind::Fanfare)));
     }
 
     #[test]
-    fn test_achievement_medium_still_uses_epic_sound() {
-        let sound = celebration_to_sound(&CelebrationLevel::Medium, true, false);
+    fn test_other_epic_uses_epic_sound() {
+        let sound = celebration_to_sound(
+            &CelebrationLevel::Epic,
+            &EventKind::TaskCompleted,
+            false,
+            None,
+        );
         assert!(matches!(sound, Some(SoundKind::Epic)));
     }
 
+    #[test]
+    fn test_achievement_medium_uses_achievement_sound() {
+        let sound =
+            celebration_to_sound(&CelebrationLevel::Medium, &EventKind::GitCommit, true, None);
+        assert!(matches!(sound, Some(SoundKind::Achievement)));
+    }
+
+    #[test]
+    fn test_task_and_session_sounds() {
+        let quest = celebration_to_sound(
+            &CelebrationLevel::Medium,
+            &EventKind::TaskCompleted,
+            false,
+            None,
+        );
+        assert!(matches!(quest, Some(SoundKind::Quest)));
+        for level in [CelebrationLevel::Medium, CelebrationLevel::Epic] {
+            let end = celebration_to_sound(&level, &EventKind::SessionEnd, false, None);
+            assert!(matches!(end, Some(SoundKind::SessionEnd)));
+        }
+    }
+
+    #[test]
+    fn test_celebration_sounds_sequence_order() {
+        let seq = celebration_sounds(
+            &CelebrationLevel::Epic,
+            &EventKind::GitPush,
+            true,
+            None,
+            true,
+        );
+        assert_eq!(
+            seq,
+            vec![
+                SoundKind::Fanfare,
+                SoundKind::Achievement,
+                SoundKind::LevelUp
+            ]
+        );
+    }
+
+    #[test]
+    fn test_celebration_sounds_no_duplicate_achievement() {
+        let seq = celebration_sounds(
+            &CelebrationLevel::Medium,
+            &EventKind::PostToolUse,
+            true,
+            None,
+            false,
+        );
+        assert_eq!(seq, vec![SoundKind::Achievement]);
+    }
+
+    #[test]
+    fn test_mini_level_up_plays_only_level_up() {
+        let seq = celebration_sounds(
+            &CelebrationLevel::Mini,
+            &EventKind::PostToolUse,
+            false,
+            None,
+            true,
+        );
+        assert_eq!(seq, vec![SoundKind::LevelUp]);
+    }
+
+    #[test]
+    fn test_level_up_replaces_milestone_sound() {
+        let seq = celebration_sounds(
+            &CelebrationLevel::Medium,
+            &EventKind::PostToolUse,
+            false,
+            None,
+            true,
+        );
+        assert_eq!(seq, vec![SoundKind::LevelUp]);
+    }
+
     #[test]
     fn test_play_sound_generates_wav_when_no_pack() {
         let tmp = tempfile::tempdir().unwrap();
@@ -130,4 +332,21 @@ mod tests {
         let result = find_sound_file(&SoundKind::Mini, &cfg, tmp.path());
         assert!(result.is_some(), "should fall back to generated WAV");
     }
+
+    #[test]
+    fn test_find_sound_file_renders_toml_spec() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pack = format!("spec-pack-{}", std::process::id());
+        let pack_dir = tmp.path().join(&pack);
+        std::fs::create_dir_all(&pack_dir).unwrap();
+        std::fs::write(pack_dir.join("epic.toml"), "sequence = \"C4 E4 G4\"").unwrap();
+        let cfg = AudioConfig {
+            enabled: true,
+            sound_pack: pack,
+            volume: 0.8,
+        };
+        let path = find_sound_file(&SoundKind::Epic, &cfg, tmp.path()).unwrap();
+        assert!(path.starts_with(std::env::temp_dir().join("cwinner").join(&cfg.sound_pack)));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
 }
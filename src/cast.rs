@@ -0,0 +1,68 @@
+//! asciinema v2 recordings of rendered celebrations: a JSON header line, then
+//! one `[seconds, "o", data]` line per chunk of terminal output.
+//! See <https://docs.asciinema.org/manual/asciicast/v2/>.
+
+use crate::screen::{Recorder, Target};
+use serde_json::{Value, json};
+use std::io::{self, Write};
+
+/// Write a recording as an asciicast. The optional header timestamp is left
+/// out so the same render always produces the same file.
+pub fn write(out: &mut impl Write, rec: &Recorder, title: Option<&str>) -> io::Result<()> {
+    let (cols, rows) = rec.size();
+    let mut header = json!({
+        "version": 2,
+        "width": cols,
+        "height": rows,
+        "env": { "TERM": "xterm-256color" },
+    });
+    if let Some(title) = title {
+        header["title"] = Value::from(title);
+    }
+    writeln!(out, "{header}")?;
+    for (at, data) in rec.output() {
+        writeln!(out, "{}", json!([at.as_secs_f64(), "o", data]))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::render_progress_bar_to;
+    use crate::screen::VirtualScreen;
+    use crate::state::State;
+    use crate::theme::Theme;
+
+    #[test]
+    fn test_cast_replays_the_render() {
+        let mut rec = Recorder::new(40, 6);
+        render_progress_bar_to(&mut rec, &State::default(), &Theme::default()).unwrap();
+        let mut out = Vec::new();
+        write(&mut out, &rec, Some("progress")).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+
+        let header: Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 40);
+        assert_eq!(header["height"], 6);
+        assert_eq!(header["title"], "progress");
+
+        let events: Vec<Value> = lines.map(|l| serde_json::from_str(l).unwrap()).collect();
+        let times: Vec<f64> = events.iter().map(|e| e[0].as_f64().unwrap()).collect();
+        assert_eq!(times.first(), Some(&0.0));
+        assert_eq!(times.last(), Some(&3.0));
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+
+        // Playing the events back in a fresh terminal draws the same frames
+        let mut screen = VirtualScreen::new(40, 6);
+        assert_eq!(events.len(), rec.frames().len());
+        for (e, frame) in events.iter().zip(rec.frames()) {
+            assert_eq!(e[1], "o");
+            screen.feed(e[2].as_str().unwrap().as_bytes());
+            assert_eq!(screen.lines(), frame.lines);
+        }
+        assert!(!screen.alternate);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::config::{Config, Intensity};
 use crate::event::{Event, EventKind};
 use crate::state::State;
+use crate::streak::StreakKind;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CelebrationLevel {
@@ -21,6 +22,46 @@ impl From<&Intensity> for CelebrationLevel {
     }
 }
 
+/// One celebration ready to present: what to render and which sounds to play.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Celebration {
+    pub level: CelebrationLevel,
+    /// Effective event kind (a Bash `git push` counts as `GitPush`), used to pick sounds.
+    pub event: EventKind,
+    /// Newly unlocked achievements as "Name — description".
+    pub achievements: Vec<String>,
+    pub label: Option<String>,
+    /// The streak that reached a milestone, if any.
+    pub streak_milestone: Option<StreakKind>,
+    pub leveled_up: bool,
+    /// The player's level before leveling up (the lowest of any merged in),
+    /// so the level-up screen can show the real old title.
+    pub previous_level: Option<u32>,
+}
+
+impl Celebration {
+    /// Fold a later celebration into this one: the higher level wins (with its
+    /// event and label), achievements are merged, and flags are combined.
+    pub fn merge(&mut self, other: Celebration) {
+        if other.level > self.level {
+            self.level = other.level;
+            self.event = other.event;
+            self.label = other.label;
+        }
+        for a in other.achievements {
+            if !self.achievements.contains(&a) {
+                self.achievements.push(a);
+            }
+        }
+        self.streak_milestone = self.streak_milestone.or(other.streak_milestone);
+        self.leveled_up |= other.leveled_up;
+        self.previous_level = match (self.previous_level, other.previous_level) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
 /// Check if a bash command matches any custom trigger pattern (substring match).
 /// Returns the intensity of the first matching trigger, or None.
 pub fn check_custom_triggers(command: &str, cfg: &Config) -> Option<CelebrationLevel> {
@@ -64,6 +105,22 @@ pub fn detect_git_command(command: &str) -> Option<EventKind> {
     found
 }
 
+/// The event kind as celebrated: a successful Bash `git commit`/`git push`
+/// counts as `GitCommit`/`GitPush`.
+pub fn effective_event_kind(event: &Event) -> EventKind {
+    if event.event == EventKind::PostToolUse && event.tool.as_deref() == Some("Bash") {
+        if let Some(git_kind) = event
+            .metadata
+            .get("command")
+            .and_then(|v| v.as_str())
+            .and_then(detect_git_command)
+        {
+            return git_kind;
+        }
+    }
+    event.event.clone()
+}
+
 pub fn decide(event: &Event, _state: &State, cfg: &Config) -> CelebrationLevel {
     if event.event == EventKind::PostToolUse {
         if let Some(tool) = &event.tool {
@@ -106,23 +163,17 @@ pub fn decide(event: &Event, _state: &State, cfg: &Config) -> CelebrationLevel {
     }
 }
 
-pub fn xp_for_level(level: &CelebrationLevel) -> u32 {
-    match level {
-        CelebrationLevel::Off => 0,
-        CelebrationLevel::Mini => 5,
-        CelebrationLevel::Medium => 25,
-        CelebrationLevel::Epic => 100,
-    }
-}
-
-/// XP with 2× streak bonus when commit_streak_days >= 5
-pub fn xp_for_event(level: &CelebrationLevel, state: &State) -> u32 {
-    let base = xp_for_level(level);
-    if base > 0 && state.commit_streak_days >= 5 {
-        base * 2
-    } else {
-        base
+/// Whether a failed tool call is one we would have celebrated at Medium or
+/// above had it succeeded (e.g. a failed `git push`), so it deserves a cue.
+pub fn is_notable_failure(event: &Event, cfg: &Config) -> bool {
+    if event.event != EventKind::PostToolUseFailure {
+        return false;
     }
+    let as_success = Event {
+        event: EventKind::PostToolUse,
+        ..event.clone()
+    };
+    decide(&as_success, &State::default(), cfg) >= CelebrationLevel::Medium
 }
 
 #[cfg(test)]
@@ -170,21 +221,6 @@ mod tests {
         assert_eq!(result, CelebrationLevel::Epic);
     }
 
-    #[test]
-    fn test_streak_bonus_doubles_xp() {
-        let state = State {
-            commit_streak_days: 5,
-            ..Default::default()
-        };
-        assert_eq!(xp_for_event(&CelebrationLevel::Medium, &state), 50); // 25 * 2
-    }
-
-    #[test]
-    fn test_no_streak_bonus_below_5_days() {
-        let state = State::default(); // streak = 0
-        assert_eq!(xp_for_event(&CelebrationLevel::Medium, &state), 25);
-    }
-
     // --- Custom trigger tests ---
 
     fn make_bash_event_with_command(command: &str, exit_code: i64) -> Event {
@@ -380,4 +416,65 @@ mod tests {
         let result = decide(&event, &state, &cfg);
         assert_eq!(result, CelebrationLevel::Mini);
     }
+
+    #[test]
+    fn test_failed_git_push_is_notable_failure() {
+        let cfg = Config::default();
+        let mut event = make_bash_event_with_command("git push origin main", 1);
+        event.event = EventKind::PostToolUseFailure;
+        assert!(is_notable_failure(&event, &cfg));
+    }
+
+    #[test]
+    fn test_failed_routine_command_is_not_notable() {
+        let cfg = Config::default();
+        let mut event = make_bash_event_with_command("ls -la", 1);
+        event.event = EventKind::PostToolUseFailure;
+        assert!(!is_notable_failure(&event, &cfg));
+        // Successful events are never failures
+        let ok = make_bash_event_with_command("git push origin main", 0);
+        assert!(!is_notable_failure(&ok, &cfg));
+    }
+
+    fn celebration(level: CelebrationLevel, label: &str, achievements: &[&str]) -> Celebration {
+        Celebration {
+            level,
+            event: EventKind::PostToolUse,
+            achievements: achievements.iter().map(|a| a.to_string()).collect(),
+            label: Some(label.into()),
+            streak_milestone: None,
+            leveled_up: false,
+            previous_level: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_highest_level_and_all_achievements() {
+        let mut c = celebration(CelebrationLevel::Medium, "commit", &["A — first"]);
+        c.merge(Celebration {
+            leveled_up: true,
+            ..celebration(CelebrationLevel::Epic, "push", &["B — second", "A — first"])
+        });
+        c.merge(celebration(CelebrationLevel::Mini, "edit", &[]));
+        assert_eq!(c.level, CelebrationLevel::Epic);
+        assert_eq!(c.label.as_deref(), Some("push"));
+        assert_eq!(c.achievements, vec!["A — first", "B — second"]);
+        assert!(c.leveled_up);
+    }
+
+    #[test]
+    fn test_merge_keeps_lowest_previous_level() {
+        let level_up = |from| Celebration {
+            leveled_up: true,
+            previous_level: Some(from),
+            ..celebration(CelebrationLevel::Medium, "level up", &[])
+        };
+        let mut c = celebration(CelebrationLevel::Mini, "edit", &[]);
+        c.merge(level_up(3));
+        assert_eq!(c.previous_level, Some(3));
+        c.merge(level_up(4));
+        let mut earlier = level_up(2);
+        earlier.merge(c);
+        assert_eq!(earlier.previous_level, Some(2));
+    }
 }
@@ -1,4 +1,6 @@
+use chrono::{NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -48,6 +50,119 @@ impl Default for AudioConfig {
     }
 }
 
+/// Colors a terminal can show. `Auto` picks from `COLORTERM`/`TERM`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorDepth {
+    #[default]
+    Auto,
+    Truecolor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+/// Animation behind an epic splash.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Animation {
+    #[default]
+    Confetti,
+    Fireworks,
+    Rain,
+    Starfield,
+    /// Big block-letter level number rising into view.
+    Banner,
+    /// The splash text typed out one character at a time.
+    Typewriter,
+}
+
+impl Animation {
+    pub const ALL: [Animation; 6] = [
+        Animation::Confetti,
+        Animation::Fireworks,
+        Animation::Rain,
+        Animation::Starfield,
+        Animation::Banner,
+        Animation::Typewriter,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Animation::Confetti => "confetti",
+            Animation::Fireworks => "fireworks",
+            Animation::Rain => "rain",
+            Animation::Starfield => "starfield",
+            Animation::Banner => "banner",
+            Animation::Typewriter => "typewriter",
+        }
+    }
+}
+
+impl std::str::FromStr for Animation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Animation::ALL
+            .into_iter()
+            .find(|a| a.name() == s)
+            .ok_or_else(|| anyhow::anyhow!("unknown animation: {s}"))
+    }
+}
+
+/// How Medium and Epic celebrations are shown.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayMode {
+    /// Take over the terminal on the alternate screen.
+    #[default]
+    Fullscreen,
+    /// A one-line notification (terminal title or tmux status line) that
+    /// leaves the main screen untouched.
+    Inline,
+}
+
+/// Desktop notification escape sent for epic inline celebrations.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InlineNotify {
+    /// `OSC 9` (iTerm2, WezTerm, Windows Terminal, kitty).
+    #[default]
+    Osc9,
+    /// `OSC 777;notify` (rxvt-unicode, foot, Ghostty, VTE terminals).
+    Osc777,
+    Off,
+}
+
+/// Whether a key press on the terminal cuts a celebration short. Claude Code
+/// reads the same terminal, so detection is best effort.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SkipPolicy {
+    /// Notice the key without reading it, so it still reaches Claude Code.
+    #[default]
+    Peek,
+    /// Read the key, so it only dismisses the celebration.
+    Consume,
+    Off,
+}
+
+/// Which animation plays for each kind of epic celebration. Kinds left unset
+/// use `default`. A level-up or streak milestone takes precedence over the
+/// event that caused it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct AnimationConfig {
+    pub default: Animation,
+    pub level_up: Option<Animation>,
+    pub streak: Option<Animation>,
+    pub git_commit: Option<Animation>,
+    pub git_push: Option<Animation>,
+    pub task_completed: Option<Animation>,
+    pub session_end: Option<Animation>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct VisualConfig {
@@ -56,6 +171,15 @@ pub struct VisualConfig {
     pub progress_bar: bool,
     pub confetti_duration_ms: u64,
     pub splash_duration_ms: u64,
+    /// Built-in theme name or a file in `~/.config/cwinner/themes/`.
+    pub theme: String,
+    pub color_depth: ColorDepth,
+    pub animations: AnimationConfig,
+    /// Fixed random seed for animations, for reproducible output.
+    pub seed: Option<u64>,
+    pub overlay_mode: OverlayMode,
+    pub inline_notify: InlineNotify,
+    pub skip_on_key: SkipPolicy,
 }
 
 impl Default for VisualConfig {
@@ -66,6 +190,13 @@ impl Default for VisualConfig {
             progress_bar: true,
             confetti_duration_ms: 1500,
             splash_duration_ms: 2000,
+            theme: "default".into(),
+            color_depth: ColorDepth::Auto,
+            animations: AnimationConfig::default(),
+            seed: None,
+            overlay_mode: OverlayMode::Fullscreen,
+            inline_notify: InlineNotify::Osc9,
+            skip_on_key: SkipPolicy::Peek,
         }
     }
 }
@@ -77,12 +208,162 @@ pub struct CustomTrigger {
     pub intensity: Intensity,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreakConfig {
+    /// IANA timezone streak days are counted in, e.g. "America/Los_Angeles".
+    /// The system timezone when unset.
+    pub timezone: Option<String>,
+    /// Saturdays and Sundays without a commit don't break a streak.
+    pub skip_weekends: bool,
+    /// Most streak freezes held at once; one is earned per 7 streak days and
+    /// spent to cover a missed day. 0 turns freezes off.
+    pub max_freezes: u32,
+}
+
+impl Default for StreakConfig {
+    fn default() -> Self {
+        Self {
+            timezone: None,
+            skip_weekends: false,
+            max_freezes: 2,
+        }
+    }
+}
+
+/// XP for each celebration level before overrides and multipliers.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct XpBase {
+    pub mini: u32,
+    pub medium: u32,
+    pub epic: u32,
+}
+
+impl Default for XpBase {
+    fn default() -> Self {
+        Self {
+            mini: 5,
+            medium: 25,
+            epic: 100,
+        }
+    }
+}
+
+/// XP multiplier from a commit streak of at least `days`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StreakTier {
+    pub days: u32,
+    pub multiplier: f64,
+}
+
+/// Keeps routine tool calls from farming XP.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RoutineXp {
+    /// Identical calls within this many seconds earn less each time.
+    pub repeat_window_secs: u64,
+    /// XP factor for each earlier identical call in the window.
+    pub repeat_decay: f64,
+    /// Most XP per hour from routine tool calls, 0 for no cap.
+    pub hourly_cap: u32,
+    /// Reading what was already read, with no change since, earns nothing.
+    pub ignore_noop_reads: bool,
+}
+
+impl Default for RoutineXp {
+    fn default() -> Self {
+        Self {
+            repeat_window_secs: 600,
+            repeat_decay: 0.5,
+            hourly_cap: 100,
+            ignore_noop_reads: true,
+        }
+    }
+}
+
+/// How much XP events earn.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct XpConfig {
+    pub base: XpBase,
+    /// XP for an event, replacing its level's base. Keys are event names
+    /// (`git_commit`, `git_push`, `task_completed`, `session_end`,
+    /// `post_tool_use`), or `post_tool_use.<Tool>` for one tool.
+    pub events: BTreeMap<String, u32>,
+    /// The highest tier the commit streak reaches multiplies the XP.
+    pub streak_tiers: Vec<StreakTier>,
+    /// Most XP earned per day, 0 for no cap.
+    pub daily_cap: u32,
+    /// Most XP per day from one event, keyed like `events`.
+    pub daily_caps: BTreeMap<String, u32>,
+    pub routine: RoutineXp,
+}
+
+impl Default for XpConfig {
+    fn default() -> Self {
+        Self {
+            base: XpBase::default(),
+            events: BTreeMap::new(),
+            streak_tiers: vec![StreakTier {
+                days: 5,
+                multiplier: 2.0,
+            }],
+            daily_cap: 0,
+            daily_caps: BTreeMap::new(),
+            routine: RoutineXp::default(),
+        }
+    }
+}
+
+/// Progress shared between machines through a synced folder.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// Folder kept in sync by Dropbox, Syncthing, git, ...; `~/` is expanded.
+    /// Sync is off when unset.
+    pub dir: Option<String>,
+    /// This machine's shard name; the hostname when unset.
+    pub machine: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TriggersConfig {
     #[serde(default)]
     pub custom: Vec<CustomTrigger>,
 }
 
+/// A recurring do-not-disturb window in local time. Overnight windows
+/// (`start` after `end`) run into the next morning; `start == end` covers the
+/// whole day. `days` lists the weekdays a window starts on (empty = every day).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuietWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleConfig {
+    pub quiet_hours: Vec<QuietWindow>,
+    /// Silence sounds while do-not-disturb is active.
+    pub mute_audio: bool,
+    /// Show toasts and splashes only as status line progress while do-not-disturb is active.
+    pub downgrade_visuals: bool,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            quiet_hours: vec![],
+            mute_audio: true,
+            downgrade_visuals: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
     #[serde(default)]
@@ -93,6 +374,14 @@ pub struct Config {
     pub visual: VisualConfig,
     #[serde(default)]
     pub triggers: TriggersConfig,
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    #[serde(default)]
+    pub streak: StreakConfig,
+    #[serde(default)]
+    pub sync: SyncConfig,
+    #[serde(default)]
+    pub xp: XpConfig,
 }
 
 impl Config {
@@ -152,6 +441,86 @@ splash_duration_ms = 2000
         assert_eq!(cfg.visual.confetti_duration_ms, 1500);
     }
 
+    #[test]
+    fn test_parse_visual_theme() {
+        let cfg: Config =
+            toml::from_str("[visual]\ntheme = \"holiday\"\ncolor_depth = \"256\"\n").unwrap();
+        assert_eq!(cfg.visual.theme, "holiday");
+        assert_eq!(cfg.visual.color_depth, ColorDepth::Ansi256);
+        assert!(cfg.visual.confetti);
+
+        let cfg = Config::default();
+        assert_eq!(cfg.visual.theme, "default");
+        assert_eq!(cfg.visual.color_depth, ColorDepth::Auto);
+    }
+
+    #[test]
+    fn test_parse_visual_animations() {
+        let toml = "[visual]\nseed = 42\n\n[visual.animations]\ndefault = \"rain\"\ngit_push = \"fireworks\"\n";
+        let cfg: Config = toml::from_str(toml).unwrap();
+        assert_eq!(cfg.visual.seed, Some(42));
+        assert_eq!(cfg.visual.animations.default, Animation::Rain);
+        assert_eq!(cfg.visual.animations.git_push, Some(Animation::Fireworks));
+        assert_eq!(cfg.visual.animations.level_up, None);
+        assert_eq!(
+            "starfield".parse::<Animation>().unwrap(),
+            Animation::Starfield
+        );
+        assert!("sparkles".parse::<Animation>().is_err());
+
+        let cfg = Config::default();
+        assert_eq!(cfg.visual.animations.default, Animation::Confetti);
+        assert_eq!(cfg.visual.seed, None);
+    }
+
+    #[test]
+    fn test_parse_overlay_mode() {
+        let toml = "[visual]\noverlay_mode = \"inline\"\ninline_notify = \"osc777\"\nskip_on_key = \"off\"\n";
+        let cfg: Config = toml::from_str(toml).unwrap();
+        assert_eq!(cfg.visual.overlay_mode, OverlayMode::Inline);
+        assert_eq!(cfg.visual.inline_notify, InlineNotify::Osc777);
+        assert_eq!(cfg.visual.skip_on_key, SkipPolicy::Off);
+
+        let cfg = Config::default();
+        assert_eq!(cfg.visual.overlay_mode, OverlayMode::Fullscreen);
+        assert_eq!(cfg.visual.inline_notify, InlineNotify::Osc9);
+        assert_eq!(cfg.visual.skip_on_key, SkipPolicy::Peek);
+    }
+
+    #[test]
+    fn test_parse_sync() {
+        let cfg: Config = toml::from_str("[sync]\ndir = \"~/Dropbox/cwinner\"\n").unwrap();
+        assert_eq!(cfg.sync.dir.as_deref(), Some("~/Dropbox/cwinner"));
+        assert_eq!(cfg.sync.machine, None);
+        assert_eq!(Config::default().sync.dir, None);
+    }
+
+    #[test]
+    fn test_parse_xp() {
+        let cfg: Config = toml::from_str(
+            r#"
+[xp]
+base = { mini = 1 }
+events = { git_push = 150, "post_tool_use.Read" = 0 }
+streak_tiers = [{ days = 3, multiplier = 1.5 }, { days = 30, multiplier = 3.0 }]
+daily_caps = { post_tool_use = 200 }
+"#,
+        )
+        .unwrap();
+        assert_eq!((cfg.xp.base.mini, cfg.xp.base.epic), (1, 100));
+        assert_eq!(cfg.xp.events["post_tool_use.Read"], 0);
+        assert_eq!(cfg.xp.streak_tiers.len(), 2);
+        assert_eq!(cfg.xp.daily_cap, 0);
+        assert_eq!(Config::default().xp.streak_tiers[0].days, 5);
+        assert_eq!(cfg.xp.routine, RoutineXp::default());
+
+        let cfg: Config =
+            toml::from_str("[xp.routine]\nhourly_cap = 0\nignore_noop_reads = false\n").unwrap();
+        assert_eq!(cfg.xp.routine.hourly_cap, 0);
+        assert!(!cfg.xp.routine.ignore_noop_reads);
+        assert_eq!(cfg.xp.routine.repeat_window_secs, 600);
+    }
+
     #[test]
     fn test_default_config_has_no_custom_triggers() {
         let cfg = Config::default();
@@ -190,4 +559,40 @@ routine = "mini"
         let cfg: Config = toml::from_str(toml_str).unwrap();
         assert!(cfg.triggers.custom.is_empty());
     }
+
+    #[test]
+    fn test_parse_toml_with_schedule() {
+        let toml_str = r#"
+[schedule]
+mute_audio = false
+
+[[schedule.quiet_hours]]
+start = "22:00"
+end = "08:00"
+
+[[schedule.quiet_hours]]
+start = "00:00"
+end = "00:00"
+days = ["sat", "sun"]
+"#;
+        let cfg: Config = toml::from_str(toml_str).unwrap();
+        assert!(!cfg.schedule.mute_audio);
+        assert!(cfg.schedule.downgrade_visuals);
+        assert_eq!(cfg.schedule.quiet_hours.len(), 2);
+        assert_eq!(
+            cfg.schedule.quiet_hours[0].start,
+            NaiveTime::from_hms_opt(22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            cfg.schedule.quiet_hours[1].days,
+            vec![Weekday::Sat, Weekday::Sun]
+        );
+    }
+
+    #[test]
+    fn test_default_config_has_no_quiet_hours() {
+        let cfg = Config::default();
+        assert!(cfg.schedule.quiet_hours.is_empty());
+        assert!(cfg.schedule.mute_audio);
+    }
 }
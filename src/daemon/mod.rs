@@ -1,2 +1,3 @@
+pub mod queue;
 pub mod server;
 pub use server::run;
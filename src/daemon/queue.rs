@@ -0,0 +1,113 @@
+use crate::celebration::Celebration;
+use std::collections::HashMap;
+
+/// A celebration waiting for the render cooldown on its terminal to expire.
+#[derive(Debug, Clone)]
+pub struct Queued {
+    pub celebration: Celebration,
+    /// Only muted if every coalesced celebration was muted (e.g. by DND).
+    pub mute_audio: bool,
+}
+
+/// Celebrations that hit the render cooldown, one pending entry per tty.
+///
+/// Rather than a list that would replay a burst of toasts back to back,
+/// everything queued for a terminal is coalesced into a single entry: the
+/// highest level wins and achievement lists are merged, so the one render
+/// that follows the cooldown carries everything that was skipped.
+#[derive(Debug, Default)]
+pub struct CelebrationQueue {
+    pending: HashMap<String, Queued>,
+}
+
+impl CelebrationQueue {
+    /// Queue a celebration for `tty_path`. Returns true if nothing was pending
+    /// for that tty yet, i.e. the caller should start draining it.
+    pub fn push(&mut self, tty_path: &str, celebration: Celebration, mute_audio: bool) -> bool {
+        match self.pending.get_mut(tty_path) {
+            Some(q) => {
+                q.celebration.merge(celebration);
+                q.mute_audio &= mute_audio;
+                false
+            }
+            None => {
+                self.pending.insert(
+                    tty_path.to_string(),
+                    Queued {
+                        celebration,
+                        mute_audio,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Remove and return what is pending for `tty_path`.
+    pub fn take(&mut self, tty_path: &str) -> Option<Queued> {
+        self.pending.remove(tty_path)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::celebration::CelebrationLevel;
+    use crate::event::EventKind;
+
+    fn celebration(level: CelebrationLevel, achievements: &[&str]) -> Celebration {
+        Celebration {
+            level,
+            event: EventKind::GitCommit,
+            achievements: achievements.iter().map(|a| a.to_string()).collect(),
+            label: None,
+            streak_milestone: None,
+            leveled_up: false,
+            previous_level: None,
+        }
+    }
+
+    #[test]
+    fn test_push_coalesces_per_tty() {
+        let mut q = CelebrationQueue::default();
+        let (a, b) = (&["A — a"], &["B — b"]);
+        assert!(q.push(
+            "/dev/pts/1",
+            celebration(CelebrationLevel::Medium, a),
+            false
+        ));
+        assert!(!q.push("/dev/pts/1", celebration(CelebrationLevel::Epic, b), false));
+        assert!(!q.push("/dev/pts/1", celebration(CelebrationLevel::Mini, a), false));
+        // A different terminal gets its own entry
+        assert!(q.push(
+            "/dev/pts/2",
+            celebration(CelebrationLevel::Mini, &[]),
+            false
+        ));
+
+        let queued = q.take("/dev/pts/1").unwrap();
+        assert_eq!(queued.celebration.level, CelebrationLevel::Epic);
+        assert_eq!(queued.celebration.achievements, vec!["A — a", "B — b"]);
+        assert!(q.take("/dev/pts/1").is_none());
+        assert!(!q.is_empty());
+        q.take("/dev/pts/2").unwrap();
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn test_mute_only_if_all_muted() {
+        let mut q = CelebrationQueue::default();
+        let medium = || celebration(CelebrationLevel::Medium, &[]);
+        q.push("/dev/pts/1", medium(), true);
+        q.push("/dev/pts/1", medium(), false);
+        assert!(!q.take("/dev/pts/1").unwrap().mute_audio);
+
+        q.push("/dev/pts/1", medium(), true);
+        q.push("/dev/pts/1", medium(), true);
+        assert!(q.take("/dev/pts/1").unwrap().mute_audio);
+    }
+}
@@ -1,12 +1,23 @@
-use crate::achievements::check_achievements;
-use crate::audio::{celebration_to_sound, play_sound};
+use super::queue::CelebrationQueue;
+use crate::achievements::{check_achievements, is_test_command};
+use crate::animation::EpicStyle;
+use crate::audio::{SoundKind, celebration_sounds, play_sequence};
 use crate::celebration::{
-    CelebrationLevel, decide, detect_git_command, has_git_commit, xp_for_event,
+    Celebration, CelebrationLevel, decide, effective_event_kind, has_git_commit, is_notable_failure,
 };
-use crate::config::Config;
+use crate::config::{Config, OverlayMode};
 use crate::event::{Event, EventKind};
-use crate::renderer::render;
+use crate::focus::{self, FocusBatches};
+use crate::renderer::{render_level_up_to, render_to};
+use crate::schedule::{DndDigest, DndToggle, dnd_active};
+use crate::screen::Tty;
 use crate::state::State;
+use crate::streak::{StreakKind, StreakPolicy};
+use crate::sync::Shards;
+use crate::terminal::{TermCaps, TermEnv};
+use crate::theme::Theme;
+use crate::xp;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -114,9 +125,16 @@ pub async fn run() -> anyhow::Result<()> {
     let _ = std::fs::remove_file(&path);
 
     let listener = UnixListener::bind(&path)?;
-    let state = Arc::new(Mutex::new(State::load()));
-    let cfg = Arc::new(Config::load());
     let sessions: Arc<Mutex<SessionMap>> = Arc::new(Mutex::new(HashMap::new()));
+    let dnd: Arc<Mutex<DndDigest>> = Arc::new(Mutex::new(DndDigest::load()));
+    let focus: Arc<Mutex<FocusBatches>> = Arc::new(Mutex::new(FocusBatches::default()));
+    let shared = Shared {
+        state: Arc::new(Mutex::new(State::load())),
+        cfg: Arc::new(Config::load()),
+        queue: Arc::new(Mutex::new(CelebrationQueue::default())),
+        terminals: Arc::new(Mutex::new(HashMap::new())),
+    };
+    let cfg = &shared.cfg;
 
     eprintln!("cwinnerd listening on {}", path.display());
     eprintln!(
@@ -127,6 +145,22 @@ pub async fn run() -> anyhow::Result<()> {
         cfg.intensity.breakthrough
     );
 
+    // DND can end with no event to carry its summary (a timer, quiet hours,
+    // `cwinner dnd off`), so check for that on a timer too
+    {
+        let dnd = Arc::clone(&dnd);
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(DND_CHECK_EVERY);
+            loop {
+                tick.tick().await;
+                let (dnd, shared) = (Arc::clone(&dnd), shared.clone());
+                let _ =
+                    tokio::task::spawn_blocking(move || flush_dnd_digest(None, &dnd, shared)).await;
+            }
+        });
+    }
+
     loop {
         let (stream, _) = match listener.accept().await {
             Ok(conn) => conn,
@@ -137,11 +171,12 @@ pub async fn run() -> anyhow::Result<()> {
                 continue;
             }
         };
-        let state = Arc::clone(&state);
-        let cfg = Arc::clone(&cfg);
+        let shared = shared.clone();
         let sessions = Arc::clone(&sessions);
+        let dnd = Arc::clone(&dnd);
+        let focus = Arc::clone(&focus);
         tokio::spawn(async move {
-            if let Err(e) = handle_connection(stream, state, cfg, sessions).await {
+            if let Err(e) = handle_connection(stream, shared, sessions, dnd, focus).await {
                 eprintln!("connection error: {e}");
             }
         });
@@ -164,10 +199,13 @@ fn is_valid_tty(path: &str) -> bool {
 
 async fn handle_connection(
     mut stream: UnixStream,
-    state: Arc<Mutex<State>>,
-    cfg: Arc<Config>,
+    shared: Shared,
     sessions: Arc<Mutex<SessionMap>>,
+    dnd: Arc<Mutex<DndDigest>>,
+    focus: Arc<Mutex<FocusBatches>>,
 ) -> anyhow::Result<()> {
+    let state = Arc::clone(&shared.state);
+    let cfg = Arc::clone(&shared.cfg);
     const MAX_MSG_LEN: usize = 65536;
     let mut buf = Vec::new();
     let mut tmp = [0u8; 4096];
@@ -196,6 +234,15 @@ async fn handle_connection(
             return Ok(());
         }
 
+        // Remember what this terminal can display, for this and later renders
+        if let Some(env) = TermEnv::from_metadata(&event.metadata) {
+            shared
+                .terminals
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(tty_path.clone(), env);
+        }
+
         // Track session info (commits + duration) for SessionEnd epic logic
         let (session_commit_count, duration_milestone_level) = {
             let mut sm = sessions.lock().unwrap_or_else(|e| e.into_inner());
@@ -220,173 +267,544 @@ async fn handle_connection(
             }
         };
 
-        // Process event under a single mutex lock, then clone state for rendering
-        let (level, achievement_name, is_streak_milestone, leveled_up, event_label, state_snapshot) = {
-            let mut s = state.lock().unwrap_or_else(|e| e.into_inner());
-            let (mut level, achievement_name, is_streak_milestone, leveled_up) =
-                process_event_with_state(&event, &mut s, &cfg);
+        // Process the event against the state on disk, under the state file
+        // lock so CLI writes in between aren't lost, then keep a copy for
+        // rendering
+        let (
+            level,
+            achievements,
+            streak_milestone,
+            leveled_up,
+            previous_level,
+            xp_gained,
+            event_label,
+            state_snapshot,
+        ) = {
+            // Blocking file work (lock, read, fsync'd write) stays off the
+            // async workers
+            let event = event.clone();
+            let cfg = Arc::clone(&cfg);
+            let result = tokio::task::spawn_blocking(move || {
+                let process = |s: &mut State| {
+                    let (old_xp, old_level) = (s.xp, s.level);
+                    let (mut level, achievements, streak_milestone, leveled_up) =
+                        process_event_with_state(&event, s, &cfg);
+
+                    // SessionEnd with >=1 commit in this session → upgrade to Epic
+                    if event.event == EventKind::SessionEnd && session_commit_count >= 1 {
+                        level = CelebrationLevel::Epic;
+                    }
 
-            // SessionEnd with >=1 commit in this session → upgrade to Epic
-            if event.event == EventKind::SessionEnd && session_commit_count >= 1 {
-                level = CelebrationLevel::Epic;
-            }
+                    // Duration milestone can upgrade celebration level
+                    if let Some(dur_level) = duration_milestone_level {
+                        level = level.max(dur_level);
+                    }
 
-            // Duration milestone can upgrade celebration level
-            if let Some(dur_level) = duration_milestone_level {
-                level = level.max(dur_level);
-            }
+                    // Achievement unlock → upgrade to at least Medium so toast is shown
+                    if !achievements.is_empty() {
+                        level = level.max(CelebrationLevel::Medium);
+                    }
 
-            // Achievement unlock → upgrade to at least Medium so toast is shown
-            if achievement_name.is_some() {
-                level = level.max(CelebrationLevel::Medium);
-            }
+                    // Level-up → at least Medium so the level-up screen is shown
+                    if leveled_up {
+                        level = level.max(CelebrationLevel::Medium);
+                    }
 
-            let label = make_event_label(&event);
-
-            s.save();
-            let snapshot = s.clone();
-            (
-                level,
-                achievement_name,
-                is_streak_milestone,
-                leveled_up,
-                label,
-                snapshot,
-            )
+                    // Other streaks name themselves; the commit streak shows in the
+                    // splash of the commit that extended it
+                    let label = match streak_milestone {
+                        Some(kind) if kind != StreakKind::Commit => {
+                            Some(kind.label(s.streak_count(kind)))
+                        }
+                        _ => make_event_label(&event),
+                    };
+
+                    (
+                        level,
+                        achievements,
+                        streak_milestone,
+                        leveled_up,
+                        leveled_up.then_some(old_level),
+                        s.xp - old_xp,
+                        label,
+                        s.clone(),
+                    )
+                };
+                // With sync on, the event applies to every machine's progress
+                State::update(|s| match Shards::from_config(&cfg.sync) {
+                    Some(shards) => shards.apply(s, process),
+                    None => process(s),
+                })
+            })
+            .await?;
+            *state.lock().unwrap_or_else(|e| e.into_inner()) = result.7.clone();
+            result
+        };
+
+        let mut celebration = Celebration {
+            level,
+            event: effective_event_kind(&event),
+            achievements,
+            label: event_label,
+            streak_milestone,
+            leveled_up,
+            previous_level,
+        };
+
+        // Do-not-disturb: XP is already awarded above; here we only decide how
+        // loudly to celebrate. What was held back is shown once DND ends.
+        let dnd_on = dnd_active(
+            &cfg.schedule,
+            DndToggle::load().as_ref(),
+            chrono::Utc::now(),
+        );
+        let (mute_audio, replay_dnd) = {
+            let mut digest = dnd.lock().unwrap_or_else(|e| e.into_inner());
+            if dnd_on {
+                let mute = digest.suppress(&cfg.schedule, &mut celebration, xp_gained, &tty_path);
+                if let Err(e) = digest.save() {
+                    eprintln!("[cwinnerd] saving DND digest: {e:#}");
+                }
+                (mute, false)
+            } else {
+                (false, !digest.is_empty())
+            }
         };
 
+        // Focus mode: queue interrupting celebrations and flush them as one
+        // summary at this terminal's next break point (or as soon as focus is
+        // switched off).
+        let focus_on = focus::is_on();
+        {
+            let mut batches = focus.lock().unwrap_or_else(|e| e.into_inner());
+            let batch = batches.get_mut(&tty_path);
+            if focus_on {
+                batch.add_xp(xp_gained);
+            }
+            if focus_on && !focus::is_flush_point(&event.event) {
+                if focus::interrupts(&celebration) {
+                    batch.push(celebration.clone());
+                    celebration.level = celebration.level.min(CelebrationLevel::Mini);
+                    celebration.leveled_up = false;
+                }
+            } else if !batch.is_empty() {
+                batch.push(celebration.clone());
+                let flushed = std::mem::take(batch);
+                celebration = flushed.combined().unwrap_or(celebration);
+            }
+        }
+
         eprintln!(
-            "[cwinnerd] event={:?} tool={:?} level={:?} achievement={:?} streak_milestone={:?} leveled_up={} tty={:?}",
+            "[cwinnerd] event={:?} tool={:?} level={:?} achievements={:?} streak_milestone={:?} leveled_up={} dnd={} focus={} tty={:?}",
             event.event,
             event.tool,
-            level,
-            achievement_name,
-            is_streak_milestone,
-            leveled_up,
+            celebration.level,
+            celebration.achievements,
+            celebration.streak_milestone,
+            celebration.leveled_up,
+            dnd_on,
+            focus_on,
             tty_path
         );
 
-        if level != CelebrationLevel::Off {
-            let cfg2 = Arc::clone(&cfg);
+        // A failed command we would have celebrated gets an audio-only cue
+        if celebration.level == CelebrationLevel::Off
+            && cfg.audio.enabled
+            && !(dnd_on && cfg.schedule.mute_audio)
+            && !focus_on
+            && is_notable_failure(&event, &cfg)
+        {
+            play_sequence(&[SoundKind::Failure], &cfg.audio);
+        }
+
+        if celebration.level != CelebrationLevel::Off || replay_dnd {
             let _ = tokio::task::spawn_blocking(move || {
                 std::thread::sleep(std::time::Duration::from_millis(200));
-                let Some(guard) = crate::renderer::acquire_render_slot(&level) else {
-                    eprintln!("[cwinnerd] SKIPPED (cooldown)");
-                    return;
-                };
-                eprintln!("[cwinnerd] RENDERING level={:?}", level);
-                if cfg2.audio.enabled {
-                    if leveled_up {
-                        play_sound(&crate::audio::SoundKind::Mini, &cfg2.audio);
-                    }
-                    if let Some(sound) = celebration_to_sound(
-                        &level,
-                        achievement_name.is_some(),
-                        is_streak_milestone,
-                    ) {
-                        play_sound(&sound, &cfg2.audio);
-                    }
+                // The DND summary first, as its own toast
+                if replay_dnd {
+                    flush_dnd_digest(Some(&tty_path), &dnd, shared.clone());
+                }
+                if celebration.level != CelebrationLevel::Off {
+                    present_or_queue(&tty_path, celebration, &state_snapshot, mute_audio, shared);
                 }
-                render(
-                    &tty_path,
-                    &level,
-                    &state_snapshot,
-                    achievement_name.as_deref(),
-                    event_label.as_deref(),
-                );
-                crate::renderer::finish_render(guard, &level);
             })
             .await;
         }
 
         // Signal hook process that rendering is done — unblocks Claude Code
         let _ = stream.write_all(b"ok\n").await;
+    } else if let Ok(control) = serde_json::from_str::<Control>(line) {
+        let reply = handle_control(control, focus, shared).await;
+        let _ = stream.write_all(reply.as_bytes()).await;
     }
 
     Ok(())
 }
 
+/// Requests from the CLI (as opposed to hook events).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Control {
+    /// Show every focus batch now, each on the terminal it was earned on.
+    /// `tty_path` is the terminal asking.
+    FlushFocus { tty_path: String },
+}
+
+/// Run a control request and return the one-line reply: `ok`, `empty`
+/// (nothing to do) or `queued` (shown once the render cooldown expires).
+async fn handle_control(
+    control: Control,
+    focus: Arc<Mutex<FocusBatches>>,
+    shared: Shared,
+) -> String {
+    match control {
+        Control::FlushFocus { tty_path } => {
+            if !is_valid_tty(&tty_path) {
+                eprintln!("[cwinnerd] rejected invalid tty_path: {tty_path:?}");
+                return "error\n".into();
+            }
+            let flushed = focus.lock().unwrap_or_else(|e| e.into_inner()).take_all();
+            if flushed.is_empty() {
+                return "empty\n".into();
+            }
+            let snapshot = shared
+                .state
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            let shown = tokio::task::spawn_blocking(move || {
+                let mut all_shown = true;
+                for (tty, batch) in flushed {
+                    let Some(celebration) = batch.combined() else {
+                        continue;
+                    };
+                    if !is_valid_tty(&tty) {
+                        continue;
+                    }
+                    let shared = shared.clone();
+                    all_shown &= present_or_queue(&tty, celebration, &snapshot, false, shared);
+                }
+                all_shown
+            })
+            .await
+            .unwrap_or(false);
+            if shown { "ok\n" } else { "queued\n" }.into()
+        }
+    }
+}
+
+/// Daemon-wide handles needed to render (now or after the cooldown).
+#[derive(Clone)]
+struct Shared {
+    /// The state as of the last event; `state.json` is the source of truth.
+    state: Arc<Mutex<State>>,
+    cfg: Arc<Config>,
+    queue: Arc<Mutex<CelebrationQueue>>,
+    /// Each terminal's environment (capabilities, tmux), as last reported by
+    /// its hooks.
+    terminals: Arc<Mutex<HashMap<String, TermEnv>>>,
+}
+
+impl Shared {
+    /// The environment last reported by hooks running on `tty_path`.
+    fn term_env(&self, tty_path: &str) -> Option<TermEnv> {
+        self.terminals
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(tty_path)
+            .cloned()
+    }
+
+    /// The theme to render with on `tty_path`.
+    fn theme_for(&self, tty_path: &str) -> Theme {
+        let caps = self
+            .term_env(tty_path)
+            .map(|env| TermCaps::detect(&env))
+            .unwrap_or_else(TermCaps::current);
+        Theme::for_terminal(&self.cfg.visual, &caps)
+    }
+}
+
+/// How often the daemon checks whether DND has ended.
+const DND_CHECK_EVERY: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Once DND is off, show what it held back on `tty_path` (by default the
+/// terminal it was held back on) and forget it. Blocks while rendering.
+fn flush_dnd_digest(tty_path: Option<&str>, dnd: &Mutex<DndDigest>, shared: Shared) {
+    let toggle = DndToggle::load();
+    if dnd_active(&shared.cfg.schedule, toggle.as_ref(), chrono::Utc::now()) {
+        return;
+    }
+    let (tty, digest) = {
+        let mut digest = dnd.lock().unwrap_or_else(|e| e.into_inner());
+        let tty = tty_path
+            .map(String::from)
+            .or_else(|| digest.tty_path.clone())
+            .filter(|t| is_valid_tty(t));
+        // Nothing held back, or nowhere to show it until the next event
+        if *digest == DndDigest::default() || (tty.is_none() && !digest.is_empty()) {
+            return;
+        }
+        let taken = std::mem::take(&mut *digest);
+        if let Err(e) = digest.save() {
+            eprintln!("[cwinnerd] saving DND digest: {e:#}");
+        }
+        (tty, taken)
+    };
+    // XP alone never interrupted anyone, so it isn't worth a toast either
+    let Some(tty) = tty.filter(|_| !digest.is_empty()) else {
+        return;
+    };
+    let snapshot = shared
+        .state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    present_or_queue(&tty, digest.into_celebration(), &snapshot, true, shared);
+}
+
+/// Present a celebration now, or queue it for its tty if the render cooldown
+/// is active. Returns false if it was queued. Blocks while rendering.
+fn present_or_queue(
+    tty_path: &str,
+    celebration: Celebration,
+    state: &State,
+    mute_audio: bool,
+    shared: Shared,
+) -> bool {
+    if present(tty_path, &celebration, state, mute_audio, &shared) {
+        return true;
+    }
+    eprintln!("[cwinnerd] QUEUED (cooldown)");
+    let first = shared.queue.lock().unwrap_or_else(|e| e.into_inner()).push(
+        tty_path,
+        celebration,
+        mute_audio,
+    );
+    if first {
+        let tty_path = tty_path.to_string();
+        std::thread::spawn(move || drain_queue(&tty_path, shared));
+    }
+    false
+}
+
+/// Wait out the render cooldown and render whatever is queued for `tty_path`,
+/// with the state as it is by then. Exits once the queue for this tty is empty.
+fn drain_queue(tty_path: &str, shared: Shared) {
+    loop {
+        std::thread::sleep(crate::renderer::cooldown_remaining(tty_path));
+        let Some(queued) = shared
+            .queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take(tty_path)
+        else {
+            return;
+        };
+        let snapshot = shared
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        if present(
+            tty_path,
+            &queued.celebration,
+            &snapshot,
+            queued.mute_audio,
+            &shared,
+        ) {
+            return;
+        }
+        // Another render took the slot first; wait again unless a newer
+        // drainer has already picked up this tty.
+        let first = shared.queue.lock().unwrap_or_else(|e| e.into_inner()).push(
+            tty_path,
+            queued.celebration,
+            queued.mute_audio,
+        );
+        if !first {
+            return;
+        }
+    }
+}
+
+/// Play the sounds for and render one celebration, honouring the render
+/// cooldown. Returns false if the cooldown held it back. Blocks while rendering.
+fn present(
+    tty_path: &str,
+    celebration: &Celebration,
+    state: &State,
+    mute_audio: bool,
+    shared: &Shared,
+) -> bool {
+    let cfg = &shared.cfg;
+    let level = &celebration.level;
+    let Some(guard) = crate::renderer::acquire_render_slot(tty_path, level) else {
+        return false;
+    };
+    eprintln!("[cwinnerd] RENDERING level={:?}", level);
+    if cfg.audio.enabled && !mute_audio {
+        let sounds = celebration_sounds(
+            level,
+            &celebration.event,
+            !celebration.achievements.is_empty(),
+            celebration.streak_milestone,
+            celebration.leveled_up,
+        );
+        play_sequence(&sounds, &cfg.audio);
+    }
+    let theme = shared.theme_for(tty_path);
+    let style = EpicStyle::for_celebration(&cfg.visual.animations, cfg.visual.seed, celebration);
+    if cfg.visual.overlay_mode == OverlayMode::Inline {
+        let env = shared.term_env(tty_path);
+        let _ = crate::inline::notify(
+            tty_path,
+            celebration,
+            state,
+            &theme,
+            env.as_ref(),
+            &cfg.visual,
+        );
+    } else if *level > CelebrationLevel::Mini {
+        if let Ok(mut tty) = Tty::open_with(tty_path, cfg.visual.skip_on_key) {
+            let _ = if celebration.leveled_up {
+                render_level_up_to(
+                    &mut tty,
+                    state,
+                    celebration.previous_level,
+                    &celebration.achievements,
+                    &theme,
+                    &style,
+                )
+            } else {
+                render_to(
+                    &mut tty,
+                    level,
+                    state,
+                    &celebration.achievements,
+                    celebration.label.as_deref(),
+                    &theme,
+                    &style,
+                )
+            };
+        }
+    }
+    crate::renderer::finish_render(guard, level);
+    true
+}
+
 /// Generate a human-readable label for the event (shown in toast/splash).
 fn make_event_label(event: &Event) -> Option<String> {
-    match event.event {
+    match effective_event_kind(event) {
         EventKind::TaskCompleted => Some("✓ Task Completed".into()),
         EventKind::SessionEnd => Some("👋 Session Complete".into()),
         EventKind::GitCommit => Some("📝 Git Commit".into()),
         EventKind::GitPush => Some("🚀 Git Push".into()),
-        EventKind::PostToolUse => {
-            let tool = event.tool.as_deref().unwrap_or("");
-            if tool == "Bash" {
-                if let Some(cmd) = event.metadata.get("command").and_then(|v| v.as_str()) {
-                    if let Some(git_kind) = detect_git_command(cmd) {
-                        return match git_kind {
-                            EventKind::GitPush => Some("🚀 Git Push".into()),
-                            EventKind::GitCommit => Some("📝 Git Commit".into()),
-                            _ => None,
-                        };
-                    }
-                }
-                None
-            } else {
-                None
-            }
-        }
         _ => None,
     }
 }
 
 /// Process an event against the given state, returning the celebration level,
-/// optionally the name of a newly unlocked achievement, whether a streak
-/// milestone was hit, and whether the player leveled up.
+/// every newly unlocked achievement (as "Name — description"), the streak
+/// that hit a milestone, if any, and whether the player leveled up.
 ///
 /// The caller is responsible for saving state and rendering visuals.
 pub fn process_event_with_state(
     event: &Event,
     state: &mut State,
     cfg: &Config,
-) -> (CelebrationLevel, Option<String>, bool, bool) {
+) -> (CelebrationLevel, Vec<String>, Option<StreakKind>, bool) {
     let mut level = decide(event, state, cfg);
-    let xp = xp_for_event(&level, state);
+    let policy = StreakPolicy::from_config(&cfg.streak);
+    let now = chrono::Utc::now();
+    let today = policy.zone.date(now);
+    let award = xp::award(event, &level, state, &cfg.xp, now, today);
+    let xp = award.xp;
     let old_level = state.level;
+    state.xp_ledger.record(now, today, event, &award);
     if xp > 0 {
         state.add_xp(xp);
     }
     let leveled_up = state.level > old_level;
-    let mut is_streak_milestone = false;
+    let bash_ok = event.event == EventKind::PostToolUse
+        && event.tool.as_deref() == Some("Bash")
+        && event.metadata.get("exit_code").and_then(|v| v.as_i64()) == Some(0);
+    let command = event.metadata.get("command").and_then(|v| v.as_str());
+    // Milestones hit by this event, most notable first
+    let mut milestones = Vec::new();
     // Record commit from GitCommit event or from Bash "git commit" command
     // (has_git_commit checks for commit even in chained commands like "git commit && git push")
-    let is_git_commit = event.event == EventKind::GitCommit
-        || (event.event == EventKind::PostToolUse
-            && event.tool.as_deref() == Some("Bash")
-            && event.metadata.get("exit_code").and_then(|v| v.as_i64()) == Some(0)
-            && event
-                .metadata
-                .get("command")
-                .and_then(|v| v.as_str())
-                .is_some_and(has_git_commit));
+    let is_git_commit =
+        event.event == EventKind::GitCommit || (bash_ok && command.is_some_and(has_git_commit));
     if is_git_commit {
-        let commit_result = state.record_commit();
+        let commit_result = state.record_commit(&policy);
+        if commit_result.freezes_used > 0 {
+            eprintln!(
+                "[cwinnerd] streak freeze used for {} missed day(s)",
+                commit_result.freezes_used
+            );
+        }
         if commit_result.streak_milestone.is_some() {
-            is_streak_milestone = true;
-            level = CelebrationLevel::Epic;
+            milestones.push(StreakKind::Commit);
         }
     }
-    if let Some(tool) = &event.tool {
-        state.record_tool_use(tool);
+    if effective_event_kind(event) == EventKind::GitPush
+        && state
+            .record_streak_day(StreakKind::Push, now, &policy)
+            .is_some()
+    {
+        milestones.push(StreakKind::Push);
+    }
+    if bash_ok
+        && command.is_some_and(is_test_command)
+        && state
+            .record_streak_day(StreakKind::Test, now, &policy)
+            .is_some()
+    {
+        milestones.push(StreakKind::Test);
+    }
+    if state
+        .record_streak_day(StreakKind::Session, now, &policy)
+        .is_some()
+    {
+        milestones.push(StreakKind::Session);
+    }
+    let tool_call = match event.event {
+        EventKind::PostToolUse => Some(true),
+        EventKind::PostToolUseFailure => Some(false),
+        _ => None,
+    };
+    if tool_call.is_some_and(|ok| state.record_tool_result(ok).is_some()) {
+        milestones.push(StreakKind::Tool);
+    }
+    let streak_milestone = milestones.first().copied();
+    match streak_milestone {
+        Some(StreakKind::Tool) => level = level.max(CelebrationLevel::Medium),
+        Some(_) => level = CelebrationLevel::Epic,
+        None => {}
+    }
+    // Only successful calls count towards tool-usage achievements
+    if event.event != EventKind::PostToolUseFailure {
+        if let Some(tool) = &event.tool {
+            state.record_tool_use(tool);
+        }
     }
     let newly_unlocked = check_achievements(state, event);
-    let achievement_name = newly_unlocked
-        .first()
-        .map(|a| format!("{} — {}", a.name, a.description));
+    let achievements = newly_unlocked
+        .iter()
+        .map(|a| format!("{} — {}", a.name, a.description))
+        .collect();
     for a in &newly_unlocked {
         state.unlock_achievement(a.id);
     }
-    (level, achievement_name, is_streak_milestone, leveled_up)
+    (level, achievements, streak_milestone, leveled_up)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::event::{Event, EventKind};
+    use crate::streak::DayZone;
     use std::collections::HashMap;
 
     #[test]
@@ -416,6 +834,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_effective_event_kind_detects_bash_git() {
+        let mut event = make_event(EventKind::PostToolUse);
+        event.tool = Some("Bash".into());
+        event
+            .metadata
+            .insert("command".into(), serde_json::json!("git push origin main"));
+        assert_eq!(effective_event_kind(&event), EventKind::GitPush);
+        assert_eq!(make_event_label(&event).as_deref(), Some("🚀 Git Push"));
+
+        event
+            .metadata
+            .insert("command".into(), serde_json::json!("cargo build"));
+        assert_eq!(effective_event_kind(&event), EventKind::PostToolUse);
+        assert_eq!(make_event_label(&event), None);
+    }
+
+    #[test]
+    fn test_failed_tool_use_is_not_recorded() {
+        let mut state = crate::state::State::default();
+        let cfg = crate::config::Config::default();
+        let mut event = make_event(EventKind::PostToolUseFailure);
+        event.tool = Some("WebFetch".into());
+        let (level, achievements, _, _) = process_event_with_state(&event, &mut state, &cfg);
+        assert_eq!(level, CelebrationLevel::Off);
+        assert!(achievements.is_empty());
+        assert!(state.tools_used.is_empty());
+    }
+
     #[test]
     fn test_process_event_task_completed_gives_xp_by_default() {
         let mut state = crate::state::State::default();
@@ -425,6 +872,22 @@ mod tests {
         assert_eq!(state.xp, 25); // task_completed defaults to "medium" (25 XP)
     }
 
+    #[test]
+    fn test_looping_ls_does_not_farm_xp() {
+        let mut state = crate::state::State::default();
+        let cfg = crate::config::Config::default();
+        let mut event = make_event(EventKind::PostToolUse);
+        event.tool = Some("Bash".into());
+        event
+            .metadata
+            .insert("command".into(), serde_json::json!("ls"));
+        for _ in 0..20 {
+            process_event_with_state(&event, &mut state, &cfg);
+        }
+        assert_eq!(state.xp, 5); // only the first ls earns
+        assert_eq!(state.xp_ledger.recent.len(), 20);
+    }
+
     #[test]
     fn test_process_event_git_commit_increments_commits() {
         let mut state = crate::state::State::default();
@@ -465,6 +928,26 @@ mod tests {
         assert!(leveled_up);
     }
 
+    #[test]
+    fn test_all_achievements_from_one_event_are_returned() {
+        let mut state = crate::state::State {
+            xp: 95,
+            ..Default::default()
+        };
+        let cfg = crate::config::Config::default();
+        let event = make_event(EventKind::GitCommit); // first commit and level 2 at once
+
+        let (_, achievements, _, _) = process_event_with_state(&event, &mut state, &cfg);
+
+        assert_eq!(achievements.len(), 2);
+        assert!(achievements.iter().any(|a| a.starts_with("First Commit")));
+        assert!(
+            achievements
+                .iter()
+                .any(|a| a.starts_with("Prompt Whisperer"))
+        );
+    }
+
     #[test]
     fn test_no_level_up_returns_false() {
         let mut state = crate::state::State {
@@ -481,10 +964,12 @@ mod tests {
 
     #[test]
     fn test_streak_bonus_applied_in_process_event() {
-        let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
+        let zone = DayZone::resolve(None);
+        let yesterday = zone.today().pred_opt().unwrap();
         let mut state = crate::state::State {
             commit_streak_days: 5,
             last_commit_date: Some(yesterday),
+            streak_timezone: Some(zone.name()),
             ..Default::default()
         };
         let cfg = crate::config::Config::default();
@@ -500,42 +985,104 @@ mod tests {
     fn test_streak_milestone_upgrades_to_epic() {
         let mut state = crate::state::State::default();
         // Set up: streak at 4, yesterday was last commit
-        let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
+        let zone = DayZone::resolve(None);
+        let yesterday = zone.today().pred_opt().unwrap();
         state.last_commit_date = Some(yesterday);
+        state.streak_timezone = Some(zone.name());
         state.commit_streak_days = 4;
         let cfg = crate::config::Config::default();
         let event = make_event(EventKind::GitCommit);
 
-        let (level, _, is_streak, _) = process_event_with_state(&event, &mut state, &cfg);
+        let (level, _, streak, _) = process_event_with_state(&event, &mut state, &cfg);
 
         assert_eq!(level, CelebrationLevel::Epic);
-        assert!(is_streak);
+        assert_eq!(streak, Some(StreakKind::Commit));
         assert_eq!(state.commit_streak_days, 5);
     }
 
     #[test]
     fn test_no_streak_milestone_at_non_milestone() {
         let mut state = crate::state::State::default();
-        let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
+        let zone = DayZone::resolve(None);
+        let yesterday = zone.today().pred_opt().unwrap();
         state.last_commit_date = Some(yesterday);
+        state.streak_timezone = Some(zone.name());
         state.commit_streak_days = 5; // going to 6, not a milestone
         let cfg = crate::config::Config::default();
         let event = make_event(EventKind::GitCommit);
 
-        let (_, _, is_streak, _) = process_event_with_state(&event, &mut state, &cfg);
+        let (_, _, streak, _) = process_event_with_state(&event, &mut state, &cfg);
 
-        assert!(!is_streak);
+        assert_eq!(streak, None);
     }
 
     #[test]
-    fn test_process_event_returns_is_streak_false_for_non_commit() {
+    fn test_process_event_returns_no_streak_for_non_commit() {
         let mut state = crate::state::State::default();
         let cfg = crate::config::Config::default();
         let event = make_event(EventKind::TaskCompleted);
 
-        let (_, _, is_streak, _) = process_event_with_state(&event, &mut state, &cfg);
+        let (_, _, streak, _) = process_event_with_state(&event, &mut state, &cfg);
 
-        assert!(!is_streak);
+        assert_eq!(streak, None);
+    }
+
+    #[test]
+    fn test_test_run_extends_test_streak() {
+        let zone = DayZone::resolve(None);
+        let mut state = crate::state::State {
+            streak_timezone: Some(zone.name()),
+            ..Default::default()
+        };
+        state.streaks.insert(
+            StreakKind::Test,
+            crate::streak::Streak {
+                count: 6,
+                best: 6,
+                last_date: zone.today().pred_opt(),
+            },
+        );
+        let cfg = crate::config::Config::default();
+        let mut event = make_event(EventKind::PostToolUse);
+        event.tool = Some("Bash".into());
+        event
+            .metadata
+            .insert("command".into(), serde_json::json!("cargo test"));
+        event
+            .metadata
+            .insert("exit_code".into(), serde_json::json!(1));
+
+        // A failing run doesn't count
+        let (_, _, streak, _) = process_event_with_state(&event, &mut state, &cfg);
+        assert_eq!(streak, None);
+        assert_eq!(state.streak_count(StreakKind::Test), 6);
+
+        event
+            .metadata
+            .insert("exit_code".into(), serde_json::json!(0));
+        let (level, _, streak, _) = process_event_with_state(&event, &mut state, &cfg);
+        assert_eq!(streak, Some(StreakKind::Test));
+        assert_eq!(level, CelebrationLevel::Epic);
+        assert_eq!(state.streak_count(StreakKind::Test), 7);
+        assert_eq!(state.streak_count(StreakKind::Session), 1);
+    }
+
+    #[test]
+    fn test_tool_failure_ends_tool_streak() {
+        let mut state = crate::state::State::default();
+        let cfg = crate::config::Config::default();
+        let mut event = make_event(EventKind::PostToolUse);
+        event.tool = Some("Read".into());
+        let mut hit = None;
+        for _ in 0..25 {
+            hit = process_event_with_state(&event, &mut state, &cfg).2;
+        }
+        assert_eq!(hit, Some(StreakKind::Tool));
+        assert_eq!(state.streak_count(StreakKind::Tool), 25);
+
+        event.event = EventKind::PostToolUseFailure;
+        process_event_with_state(&event, &mut state, &cfg);
+        assert_eq!(state.streak_count(StreakKind::Tool), 0);
     }
 
     // --- Session duration milestone tests ---
@@ -676,4 +1223,13 @@ mod tests {
         info.commits += 1;
         assert_eq!(info.commits, 2);
     }
+
+    #[test]
+    fn test_control_flush_focus_wire_format() {
+        let json = r#"{"command":"flush_focus","tty_path":"/dev/pts/3"}"#;
+        let Control::FlushFocus { tty_path } = serde_json::from_str::<Control>(json).unwrap();
+        assert_eq!(tty_path, "/dev/pts/3");
+        // Control messages must never be mistaken for hook events
+        assert!(serde_json::from_str::<Event>(json).is_err());
+    }
 }
@@ -0,0 +1,196 @@
+use crate::celebration::{Celebration, CelebrationLevel};
+use crate::event::EventKind;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Focus mode is on while this flag file exists next to `state.json`.
+pub fn flag_path() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("cwinner").join("focus"))
+}
+
+pub fn is_on_at(path: &Path) -> bool {
+    path.exists()
+}
+
+pub fn is_on() -> bool {
+    flag_path().is_some_and(|p| is_on_at(&p))
+}
+
+pub fn set_at(path: &Path, on: bool) -> anyhow::Result<()> {
+    if on {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, b"")?;
+    } else {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+pub fn set(on: bool) -> anyhow::Result<()> {
+    let path = flag_path().ok_or_else(|| anyhow::anyhow!("no data directory"))?;
+    set_at(&path, on)
+}
+
+/// Natural break points where a focus batch is flushed on its own.
+pub fn is_flush_point(kind: &EventKind) -> bool {
+    matches!(kind, EventKind::TaskCompleted | EventKind::SessionEnd)
+}
+
+/// Whether a celebration would interrupt (toast, splash or sound) and so
+/// belongs in the batch rather than on screen while focused.
+pub fn interrupts(c: &Celebration) -> bool {
+    c.level >= CelebrationLevel::Medium || c.leveled_up
+}
+
+/// Celebrations held back while focused, coalesced into one.
+#[derive(Debug, Clone, Default)]
+pub struct FocusBatch {
+    pending: Option<Celebration>,
+    /// How many celebrations were folded into `pending`.
+    pub count: u32,
+    /// XP earned while focused.
+    pub xp: u32,
+}
+
+impl FocusBatch {
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_none()
+    }
+
+    pub fn push(&mut self, c: Celebration) {
+        self.count += 1;
+        match &mut self.pending {
+            Some(p) => p.merge(c),
+            None => self.pending = Some(c),
+        }
+    }
+
+    pub fn add_xp(&mut self, xp: u32) {
+        self.xp = self.xp.saturating_add(xp);
+    }
+
+    /// The whole batch as a single celebration, labelled with a summary.
+    pub fn combined(&self) -> Option<Celebration> {
+        let mut c = self.pending.clone()?;
+        c.level = c.level.max(CelebrationLevel::Medium);
+        c.label = Some(format!(
+            "🎯 While you were focused: {} celebration{} · +{} XP",
+            self.count,
+            if self.count == 1 { "" } else { "s" },
+            self.xp
+        ));
+        Some(c)
+    }
+}
+
+/// Focus batches by tty path, so each terminal's celebrations are shown
+/// on that terminal.
+#[derive(Debug, Default)]
+pub struct FocusBatches {
+    batches: HashMap<String, FocusBatch>,
+}
+
+impl FocusBatches {
+    /// The batch for `tty_path`.
+    pub fn get_mut(&mut self, tty_path: &str) -> &mut FocusBatch {
+        self.batches.entry(tty_path.to_string()).or_default()
+    }
+
+    /// Take every non-empty batch, with the terminal it belongs to.
+    pub fn take_all(&mut self) -> Vec<(String, FocusBatch)> {
+        self.batches
+            .drain()
+            .filter(|(_, b)| !b.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn celebration(level: CelebrationLevel, achievements: &[&str]) -> Celebration {
+        Celebration {
+            level,
+            event: EventKind::GitCommit,
+            achievements: achievements.iter().map(|a| a.to_string()).collect(),
+            label: Some("📝 Git Commit".into()),
+            streak_milestone: None,
+            leveled_up: false,
+            previous_level: None,
+        }
+    }
+
+    #[test]
+    fn test_flag_toggle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cwinner").join("focus");
+        assert!(!is_on_at(&path));
+        set_at(&path, true).unwrap();
+        assert!(is_on_at(&path));
+        set_at(&path, false).unwrap();
+        assert!(!is_on_at(&path));
+        // Turning off twice is fine
+        set_at(&path, false).unwrap();
+    }
+
+    #[test]
+    fn test_flush_points() {
+        assert!(is_flush_point(&EventKind::TaskCompleted));
+        assert!(is_flush_point(&EventKind::SessionEnd));
+        assert!(!is_flush_point(&EventKind::PostToolUse));
+        assert!(!is_flush_point(&EventKind::GitCommit));
+    }
+
+    #[test]
+    fn test_batch_combines_into_summary() {
+        let mut batch = FocusBatch::default();
+        assert!(batch.combined().is_none());
+        batch.push(celebration(CelebrationLevel::Medium, &["A — a"]));
+        batch.push(celebration(CelebrationLevel::Epic, &["B — b"]));
+        batch.add_xp(150);
+        let c = batch.combined().unwrap();
+        assert_eq!(c.level, CelebrationLevel::Epic);
+        assert_eq!(c.achievements, vec!["A — a", "B — b"]);
+        assert_eq!(
+            c.label.as_deref(),
+            Some("🎯 While you were focused: 2 celebrations · +150 XP")
+        );
+    }
+
+    #[test]
+    fn test_level_up_alone_is_shown_as_toast() {
+        let mut batch = FocusBatch::default();
+        let c = Celebration {
+            leveled_up: true,
+            ..celebration(CelebrationLevel::Mini, &[])
+        };
+        assert!(interrupts(&c));
+        batch.push(c);
+        assert_eq!(batch.combined().unwrap().level, CelebrationLevel::Medium);
+    }
+
+    #[test]
+    fn test_batches_are_kept_per_terminal() {
+        let mut batches = FocusBatches::default();
+        batches
+            .get_mut("/dev/pts/1")
+            .push(celebration(CelebrationLevel::Medium, &["A — a"]));
+        batches
+            .get_mut("/dev/pts/2")
+            .push(celebration(CelebrationLevel::Epic, &["B — b"]));
+        let first = std::mem::take(batches.get_mut("/dev/pts/1"));
+        assert_eq!(first.combined().unwrap().achievements, vec!["A — a"]);
+        assert!(batches.get_mut("/dev/pts/1").is_empty());
+        let rest = batches.take_all();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].0, "/dev/pts/2");
+        assert_eq!(rest[0].1.combined().unwrap().achievements, vec!["B — b"]);
+        assert!(batches.take_all().is_empty());
+    }
+}
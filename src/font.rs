@@ -0,0 +1,110 @@
+//! A small FIGlet-style block font for banners: digits, A–Z and a little
+//! punctuation, five rows tall. `#` marks a filled cell.
+
+/// Rows per glyph.
+pub const HEIGHT: usize = 5;
+
+/// Columns left blank between glyphs.
+const SPACING: usize = 1;
+
+fn glyph(c: char) -> Option<[&'static str; HEIGHT]> {
+    let g = match c.to_ascii_uppercase() {
+        'A' => [" ### ", "#   #", "#####", "#   #", "#   #"],
+        'B' => ["#### ", "#   #", "#### ", "#   #", "#### "],
+        'C' => [" ####", "#    ", "#    ", "#    ", " ####"],
+        'D' => ["#### ", "#   #", "#   #", "#   #", "#### "],
+        'E' => ["#####", "#    ", "#### ", "#    ", "#####"],
+        'F' => ["#####", "#    ", "#### ", "#    ", "#    "],
+        'G' => [" ####", "#    ", "#  ##", "#   #", " ####"],
+        'H' => ["#   #", "#   #", "#####", "#   #", "#   #"],
+        'I' => ["###", " # ", " # ", " # ", "###"],
+        'J' => ["#####", "   # ", "   # ", "#  # ", " ##  "],
+        'K' => ["#   #", "#  # ", "###  ", "#  # ", "#   #"],
+        'L' => ["#    ", "#    ", "#    ", "#    ", "#####"],
+        'M' => ["#   #", "## ##", "# # #", "#   #", "#   #"],
+        'N' => ["#   #", "##  #", "# # #", "#  ##", "#   #"],
+        'O' => [" ### ", "#   #", "#   #", "#   #", " ### "],
+        'P' => ["#### ", "#   #", "#### ", "#    ", "#    "],
+        'Q' => [" ### ", "#   #", "# # #", "#  # ", " ## #"],
+        'R' => ["#### ", "#   #", "#### ", "#  # ", "#   #"],
+        'S' => [" ####", "#    ", " ### ", "    #", "#### "],
+        'T' => ["#####", "  #  ", "  #  ", "  #  ", "  #  "],
+        'U' => ["#   #", "#   #", "#   #", "#   #", " ### "],
+        'V' => ["#   #", "#   #", "#   #", " # # ", "  #  "],
+        'W' => ["#   #", "#   #", "# # #", "## ##", "#   #"],
+        'X' => ["#   #", " # # ", "  #  ", " # # ", "#   #"],
+        'Y' => ["#   #", " # # ", "  #  ", "  #  ", "  #  "],
+        'Z' => ["#####", "   # ", "  #  ", " #   ", "#####"],
+        '0' => [" ### ", "#  ##", "# # #", "##  #", " ### "],
+        '1' => ["  #  ", " ##  ", "  #  ", "  #  ", " ### "],
+        '2' => [" ### ", "#   #", "  ## ", " #   ", "#####"],
+        '3' => ["#### ", "    #", " ### ", "    #", "#### "],
+        '4' => ["#   #", "#   #", "#####", "    #", "    #"],
+        '5' => ["#####", "#    ", "#### ", "    #", "#### "],
+        '6' => [" ### ", "#    ", "#### ", "#   #", " ### "],
+        '7' => ["#####", "    #", "   # ", "  #  ", "  #  "],
+        '8' => [" ### ", "#   #", " ### ", "#   #", " ### "],
+        '9' => [" ### ", "#   #", " ####", "    #", " ### "],
+        ' ' => ["   ", "   ", "   ", "   ", "   "],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        '+' => ["   ", " # ", "###", " # ", "   "],
+        '.' => [" ", " ", " ", " ", "#"],
+        ':' => [" ", "#", " ", "#", " "],
+        '!' => ["#", "#", "#", " ", "#"],
+        '\'' => ["#", "#", " ", " ", " "],
+        _ => return None,
+    };
+    Some(g)
+}
+
+/// Render `text` as `HEIGHT` rows of equal width, drawing filled cells with
+/// `fill`. Characters the font doesn't have are skipped.
+pub fn render(text: &str, fill: char) -> Vec<String> {
+    let glyphs: Vec<_> = text.chars().filter_map(glyph).collect();
+    (0..HEIGHT)
+        .map(|row| {
+            glyphs
+                .iter()
+                .map(|g| g[row].replace('#', &fill.to_string()))
+                .collect::<Vec<_>>()
+                .join(&" ".repeat(SPACING))
+        })
+        .collect()
+}
+
+/// Width in columns of `text` rendered in the block font.
+pub fn width(text: &str) -> usize {
+    let glyphs: Vec<_> = text.chars().filter_map(glyph).collect();
+    let letters: usize = glyphs.iter().map(|g| g[0].len()).sum();
+    letters + SPACING * glyphs.len().saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glyphs_are_rectangular() {
+        let all = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 -+.:!'";
+        for c in all.chars() {
+            let g = glyph(c).unwrap();
+            assert!(g.iter().all(|row| row.len() == g[0].len()), "{c:?}");
+        }
+    }
+
+    #[test]
+    fn test_render_level_banner() {
+        let rows = render("Lvl 7", '█');
+        assert_eq!(rows.len(), HEIGHT);
+        let w = width("Lvl 7");
+        assert!(rows.iter().all(|r| r.chars().count() == w));
+        assert_eq!(rows[4], "█████   █   █████       █  ");
+    }
+
+    #[test]
+    fn test_unknown_characters_are_skipped() {
+        assert_eq!(render("A€", '#'), render("A", '#'));
+        assert_eq!(width(""), 0);
+        assert!(render("", '#').iter().all(String::is_empty));
+    }
+}
@@ -0,0 +1,263 @@
+//! Inline celebrations (`overlay_mode = "inline"`): a one-line notice in the
+//! terminal title, a desktop notification, or the tmux status line, leaving
+//! the main screen untouched.
+
+use crate::celebration::{Celebration, CelebrationLevel};
+use crate::config::{InlineNotify, VisualConfig};
+use crate::renderer::{format_toast_msg, toast_duration_ms};
+use crate::screen::{Target, Tty};
+use crate::state::State;
+use crate::terminal::TermEnv;
+use crate::theme::Theme;
+use std::io;
+use std::process::Command;
+use std::time::Duration;
+
+/// Save the window and icon titles on the xterm title stack.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+/// Restore the titles saved by `PUSH_TITLE`.
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// Epic celebrations and level-ups also get a desktop notification.
+fn is_big(c: &Celebration) -> bool {
+    c.level == CelebrationLevel::Epic || c.leveled_up
+}
+
+/// The notice for a celebration, on one line.
+pub fn message(c: &Celebration, state: &State, theme: &Theme) -> String {
+    let names: Vec<&str> = c
+        .achievements
+        .iter()
+        .map(|a| a.split(" — ").next().unwrap_or(a))
+        .collect();
+    let msg = if c.leveled_up {
+        let div = &theme.glyphs.divider;
+        let mut msg = format!(
+            "{} LEVEL UP {div} Lvl {} {}",
+            theme.glyphs.sparkle, state.level, state.level_name
+        );
+        if !names.is_empty() {
+            msg += &format!(" {div} {} {}", theme.glyphs.trophy, names.join(", "));
+        }
+        theme.text(&msg).into_owned()
+    } else if names.is_empty() {
+        format_toast_msg(state, None, c.label.as_deref(), theme).0
+    } else {
+        format_toast_msg(state, Some(&names.join(", ")), c.label.as_deref(), theme).0
+    };
+    // Keep labels and names from ending the escape sequence early
+    msg.chars().filter(|c| !c.is_control()).collect()
+}
+
+/// How long the notice stays up: as long as the toast would, and a little
+/// longer for epic celebrations.
+pub fn hold_duration(c: &Celebration) -> Duration {
+    let ms = toast_duration_ms(c.achievements.len());
+    Duration::from_millis(if is_big(c) { ms.max(4000) } else { ms })
+}
+
+/// The desktop notification escape for `msg`, if enabled.
+pub fn desktop_notification(kind: InlineNotify, msg: &str) -> Option<String> {
+    match kind {
+        InlineNotify::Osc9 => Some(format!("\x1b]9;{msg}\x07")),
+        InlineNotify::Osc777 => Some(format!("\x1b]777;notify;cwinner;{msg}\x07")),
+        InlineNotify::Off => None,
+    }
+}
+
+/// Show `msg` as the terminal title for `hold`, then put the old title back.
+pub fn notify_to(
+    target: &mut impl Target,
+    msg: &str,
+    hold: Duration,
+    notify: InlineNotify,
+) -> io::Result<()> {
+    write!(target, "{PUSH_TITLE}\x1b]0;{msg}\x07")?;
+    if let Some(seq) = desktop_notification(notify, msg) {
+        target.write_all(seq.as_bytes())?;
+    }
+    target.flush()?;
+    target.sleep(hold);
+    write!(target, "{POP_TITLE}")?;
+    target.flush()
+}
+
+/// Arguments for `tmux display-message` on the hook's server and pane, when
+/// it ran inside tmux. `hold` needs tmux 3.2 (`-d`).
+pub fn tmux_args(env: &TermEnv, msg: &str, hold: Option<Duration>) -> Option<Vec<String>> {
+    // TMUX is "socket,pid,session"
+    let socket = env.tmux.as_deref()?.split(',').next()?;
+    let mut args = vec!["-S".into(), socket.into(), "display-message".into()];
+    if let Some(hold) = hold {
+        args.extend(["-d".into(), hold.as_millis().to_string()]);
+    }
+    if let Some(pane) = &env.tmux_pane {
+        args.extend(["-t".into(), pane.clone()]);
+    }
+    // The message is a format string; `#` has to be doubled
+    args.push(msg.replace('#', "##"));
+    Some(args)
+}
+
+fn run_tmux(args: &[String]) -> bool {
+    Command::new("tmux")
+        .args(args)
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Show a celebration inline on `tty_path`. Blocks for as long as the notice
+/// is up, so the render cooldown applies as it does to full-screen renders.
+pub fn notify(
+    tty_path: &str,
+    c: &Celebration,
+    state: &State,
+    theme: &Theme,
+    env: Option<&TermEnv>,
+    cfg: &VisualConfig,
+) -> io::Result<()> {
+    if c.level <= CelebrationLevel::Mini {
+        return Ok(());
+    }
+    let msg = message(c, state, theme);
+    let hold = hold_duration(c);
+    if let Some(env) = env.filter(|e| e.tmux.is_some()) {
+        let shown = tmux_args(env, &msg, Some(hold)).is_some_and(|a| run_tmux(&a))
+            || tmux_args(env, &msg, None).is_some_and(|a| run_tmux(&a));
+        if shown {
+            std::thread::sleep(hold);
+            return Ok(());
+        }
+    }
+    let notify = if is_big(c) {
+        cfg.inline_notify
+    } else {
+        InlineNotify::Off
+    };
+    notify_to(&mut Tty::open(tty_path)?, &msg, hold, notify)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventKind;
+    use crate::screen::{Clock, Recorder};
+    use std::io::Write;
+
+    fn celebration(level: CelebrationLevel) -> Celebration {
+        Celebration {
+            level,
+            event: EventKind::GitCommit,
+            achievements: vec![],
+            label: Some("📝 Git Commit".into()),
+            streak_milestone: None,
+            leveled_up: false,
+            previous_level: None,
+        }
+    }
+
+    fn state() -> State {
+        State {
+            xp: 1325,
+            level: 3,
+            level_name: "Vibe Architect".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_message_variants() {
+        let theme = Theme::default();
+        let mut c = celebration(CelebrationLevel::Medium);
+        let plain = message(&c, &state(), &theme);
+        assert!(
+            plain.starts_with("📝 Git Commit │ Vibe Architect │ "),
+            "{plain}"
+        );
+
+        c.achievements = vec![
+            "First Commit — Made your first commit".into(),
+            "Night Owl — Coded after midnight".into(),
+        ];
+        let ach = message(&c, &state(), &theme);
+        assert!(ach.starts_with("🏆 First Commit, Night Owl │ "), "{ach}");
+        assert!(!ach.contains("midnight"));
+
+        c.leveled_up = true;
+        assert_eq!(
+            message(&c, &state(), &theme),
+            "✦ LEVEL UP │ Lvl 3 Vibe Architect │ 🏆 First Commit, Night Owl"
+        );
+    }
+
+    #[test]
+    fn test_message_strips_control_characters() {
+        let mut c = celebration(CelebrationLevel::Medium);
+        c.label = Some("deploy\x07\x1b]0;pwned".into());
+        let msg = message(&c, &state(), &Theme::default());
+        assert!(msg.starts_with("deploy]0;pwned │ "), "{msg}");
+    }
+
+    #[test]
+    fn test_hold_duration() {
+        let mut c = celebration(CelebrationLevel::Medium);
+        assert_eq!(hold_duration(&c), Duration::from_millis(1500));
+        c.leveled_up = true;
+        assert_eq!(hold_duration(&c), Duration::from_millis(4000));
+        c.achievements = vec!["a".into(); 4];
+        assert_eq!(hold_duration(&c), Duration::from_millis(6000));
+    }
+
+    #[test]
+    fn test_notify_sets_and_restores_title() {
+        let mut rec = Recorder::new(20, 3);
+        write!(rec, "\x1b]2;claude\x07$ ").unwrap();
+        let hold = Duration::from_millis(1500);
+        notify_to(&mut rec, "🚀 shipped", hold, InlineNotify::Osc9).unwrap();
+
+        let frames = rec.frames();
+        assert_eq!(frames.len(), 2);
+        assert!(frames.iter().all(|f| !f.alternate));
+        assert!(frames.iter().all(|f| f.lines[0] == "$"));
+        assert_eq!(rec.elapsed(), hold);
+        let (_, shown) = &rec.output()[0];
+        assert!(shown.contains("\x1b]0;🚀 shipped\x07"));
+        assert!(shown.contains("\x1b]9;🚀 shipped\x07"));
+        assert_eq!(rec.screen().title, "claude");
+    }
+
+    #[test]
+    fn test_desktop_notification_escapes() {
+        assert_eq!(
+            desktop_notification(InlineNotify::Osc777, "hi").as_deref(),
+            Some("\x1b]777;notify;cwinner;hi\x07")
+        );
+        assert_eq!(desktop_notification(InlineNotify::Off, "hi"), None);
+    }
+
+    #[test]
+    fn test_tmux_args() {
+        let env = TermEnv {
+            tmux: Some("/tmp/tmux-1000/default,4242,0".into()),
+            tmux_pane: Some("%3".into()),
+            ..Default::default()
+        };
+        let args = tmux_args(&env, "100% #1", Some(Duration::from_secs(2))).unwrap();
+        assert_eq!(
+            args,
+            [
+                "-S",
+                "/tmp/tmux-1000/default",
+                "display-message",
+                "-d",
+                "2000",
+                "-t",
+                "%3",
+                "100% ##1"
+            ]
+        );
+        let args = tmux_args(&env, "hi", None).unwrap();
+        assert!(!args.contains(&"-d".to_string()));
+        assert_eq!(tmux_args(&TermEnv::default(), "hi", None), None);
+    }
+}
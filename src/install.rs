@@ -100,6 +100,10 @@ pub fn add_claude_hooks(settings_path: &Path, binary: &str) -> Result<()> {
 
     let hooks_to_add = [
         ("PostToolUse", format!("{} hook post-tool-use", binary)),
+        (
+            "PostToolUseFailure",
+            format!("{} hook post-tool-use-failure", binary),
+        ),
         ("TaskCompleted", format!("{} hook task-completed", binary)),
         ("Stop", format!("{} hook session-end", binary)),
     ];
@@ -575,6 +579,26 @@ splash_screen = true
 progress_bar = true
 confetti_duration_ms = 1500
 splash_duration_ms = 2000
+# Built-in: default, monochrome, ascii, high-contrast, holiday — or a file in
+# ~/.config/cwinner/themes/. Preview with `cwinner theme preview <name>`.
+theme = "default"
+color_depth = "auto"   # auto | truecolor | 256 | 16
+# seed = 42            # fixed animation seed for reproducible output
+# "inline" shows a one-line notice in the terminal title (or tmux status line)
+# instead of the full-screen overlay; epics also send a desktop notification.
+overlay_mode = "fullscreen"   # fullscreen | inline
+inline_notify = "osc9"        # osc9 | osc777 | off
+# A key press cuts a celebration short. "peek" leaves the key for Claude Code,
+# "consume" swallows it. Best effort: Claude Code reads the same terminal.
+skip_on_key = "peek"          # peek | consume | off
+
+# Epic splash animation per event: confetti | fireworks | rain | starfield |
+# banner | typewriter. Level-ups and streak milestones win over the event.
+# [visual.animations]
+# default = "confetti"
+# level_up = "fireworks"  # behind the level-up screen
+# streak = "starfield"
+# git_push = "fireworks"
 
 # [triggers]
 # Custom triggers — celebrate specific bash commands (substring match).
@@ -585,6 +609,56 @@ splash_duration_ms = 2000
 # name = "deploy"
 # pattern = "git push"
 # intensity = "epic"
+
+# [schedule]
+# Do-not-disturb — XP is still awarded, sounds are muted and toasts/splashes
+# drop to the status line. Toggle by hand with `cwinner dnd on|off|for 30m`.
+# mute_audio = true
+# downgrade_visuals = true
+#
+# [[schedule.quiet_hours]]
+# start = "22:00"
+# end = "08:00"
+# days = ["mon", "tue", "wed", "thu", "fri"]  # optional, days the window starts on
+
+# [streak]
+# IANA timezone that streak days are counted in; the system timezone if unset.
+# timezone = "America/Los_Angeles"
+# Weekends without a commit don't break the streak.
+# skip_weekends = false
+# A freeze is earned every 7 streak days and covers one missed day; this many
+# can be held at once (0 turns freezes off). See `cwinner streak`.
+# max_freezes = 2
+
+# [sync]
+# Folder synced between machines (Dropbox, Syncthing, git). Each machine keeps
+# its own journal there and XP from all of them is added up.
+# dir = "~/Dropbox/cwinner"
+# This machine's journal name; the hostname if unset.
+# machine = "laptop"
+
+# [xp]
+# XP per celebration level.
+# base = { mini = 5, medium = 25, epic = 100 }
+# Replace the base for an event (git_commit, git_push, task_completed,
+# session_end, post_tool_use) or one tool (post_tool_use.<Tool>).
+# events = { git_push = 150, "post_tool_use.Read" = 1 }
+# The highest tier the commit streak reaches multiplies the XP.
+# streak_tiers = [{ days = 5, multiplier = 2.0 }]
+# Most XP per day (0 = no cap), and per event, keyed like `events`.
+# daily_cap = 0
+# daily_caps = { post_tool_use = 200 }
+
+# [xp.routine]
+# Identical tool calls within this many seconds earn repeat_decay times
+# the one before.
+# repeat_window_secs = 600
+# repeat_decay = 0.5
+# Most XP per hour from routine tool calls (0 = no cap).
+# hourly_cap = 100
+# Reading something again with nothing changed since earns nothing.
+# ignore_noop_reads = true
+# See how an award is computed with `cwinner xp explain '{"event":"GitCommit"}'`.
 "#;
 
 #[cfg(test)]
@@ -592,6 +666,12 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_default_config_parses() {
+        let cfg: crate::config::Config = toml::from_str(DEFAULT_CONFIG).unwrap();
+        assert_eq!(cfg.visual.theme, "default");
+    }
+
     #[test]
     fn test_merge_claude_settings_empty() {
         let dir = tempdir().unwrap();
@@ -604,6 +684,10 @@ mod tests {
         let v: serde_json::Value = serde_json::from_str(&content).unwrap();
         assert!(v["hooks"].is_object());
         assert!(v["hooks"]["PostToolUse"].is_array());
+        let failure = v["hooks"]["PostToolUseFailure"][0]["hooks"][0]["command"]
+            .as_str()
+            .unwrap();
+        assert_eq!(failure, "/usr/local/bin/cwinner hook post-tool-use-failure");
     }
 
     #[test]
@@ -777,7 +861,7 @@ mod tests {
         let content = std::fs::read_to_string(&settings_path).unwrap();
         let v: serde_json::Value = serde_json::from_str(&content).unwrap();
         // All hook arrays should be empty
-        for hook_name in &["PostToolUse", "TaskCompleted", "Stop"] {
+        for hook_name in &["PostToolUse", "PostToolUseFailure", "TaskCompleted", "Stop"] {
             let arr = v["hooks"][hook_name].as_array().unwrap();
             assert!(arr.is_empty(), "{hook_name} should be empty after remove");
         }
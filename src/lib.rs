@@ -1,11 +1,24 @@
 pub mod achievements;
+pub mod animation;
+pub mod archive;
 pub mod audio;
+pub mod cast;
 pub mod celebration;
 pub mod config;
 pub mod daemon;
 pub mod event;
+pub mod focus;
+pub mod font;
+pub mod inline;
 pub mod install;
 pub mod renderer;
+pub mod schedule;
+pub mod screen;
 pub mod sounds;
 pub mod state;
+pub mod streak;
+pub mod sync;
+pub mod terminal;
+pub mod theme;
 pub mod update;
+pub mod xp;
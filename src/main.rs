@@ -1,5 +1,12 @@
 use clap::{Parser, Subcommand};
-use cwinner_lib::{install, state::State};
+use cwinner_lib::animation::EpicStyle;
+use cwinner_lib::config::{Animation, ColorDepth, VisualConfig};
+use cwinner_lib::screen::{Clock, Recorder, Target, Tty};
+use cwinner_lib::state::Merge;
+use cwinner_lib::streak::{self, StreakPolicy};
+use cwinner_lib::sync::Shards;
+use cwinner_lib::terminal::TermCaps;
+use cwinner_lib::{install, state::State, theme::Theme};
 #[cfg(unix)]
 use std::os::unix::process::CommandExt;
 use std::path::PathBuf;
@@ -21,6 +28,8 @@ enum Commands {
     Status,
     /// Show overall statistics and achievements
     Stats,
+    /// Show the commit streak (recent days, freezes held, when it breaks) and the other streaks
+    Streak,
     /// Internal: send event to daemon (called by hook scripts)
     Hook {
         #[arg(value_enum)]
@@ -37,22 +46,161 @@ enum Commands {
         #[command(subcommand)]
         cmd: SoundsCommands,
     },
+    /// Do-not-disturb: keep earning XP but hold back sounds and splashes
+    Dnd {
+        #[command(subcommand)]
+        cmd: DndCommands,
+    },
+    /// Focus mode: batch celebrations and show them at the next break point
+    Focus {
+        #[command(subcommand)]
+        cmd: FocusCommands,
+    },
+    /// Render a sample celebration offline into an asciinema recording
+    Render {
+        /// Celebration to render
+        #[arg(long, value_enum, default_value = "epic")]
+        level: PreviewLevel,
+        /// Write an asciinema v2 cast file here
+        #[arg(long)]
+        export: PathBuf,
+        /// Theme to render in (defaults to the configured one)
+        #[arg(long)]
+        theme: Option<String>,
+        /// Epic animation: confetti, fireworks, rain, starfield, banner or typewriter
+        #[arg(long)]
+        animation: Option<Animation>,
+        /// Animation seed (defaults to `visual.seed`, else random)
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Terminal size as COLSxROWS
+        #[arg(long, default_value = "80x24", value_parser = parse_size)]
+        size: (u16, u16),
+    },
+    /// List and preview visual themes
+    Theme {
+        #[command(subcommand)]
+        cmd: ThemeCommands,
+    },
+    /// Look after the saved progress file
+    State {
+        #[command(subcommand)]
+        cmd: StateCommands,
+    },
+    /// Inspect the XP economy configured in `[xp]`
+    Xp {
+        #[command(subcommand)]
+        cmd: XpCommands,
+    },
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
 enum HookEvent {
     #[value(name = "post-tool-use")]
     PostToolUse,
+    #[value(name = "post-tool-use-failure")]
+    PostToolUseFailure,
     #[value(name = "task-completed")]
     TaskCompleted,
     #[value(name = "session-end")]
     SessionEnd,
 }
 
+#[derive(Subcommand)]
+enum DndCommands {
+    /// Turn do-not-disturb on until turned off
+    On,
+    /// Turn do-not-disturb off
+    Off,
+    /// Turn do-not-disturb on for a while (e.g. 30m, 2h, 1h30m)
+    For { duration: String },
+    /// Show whether do-not-disturb is active
+    Status,
+}
+
+#[derive(Subcommand)]
+enum FocusCommands {
+    /// Start batching celebrations
+    On,
+    /// Stop batching and show what was held back
+    Off,
+    /// Show what was held back now, staying in focus mode
+    Flush,
+    /// Show whether focus mode is on
+    Status,
+}
+
+#[derive(Subcommand)]
+enum StateCommands {
+    /// Restore progress after a corrupt state file was quarantined
+    Repair {
+        /// Start over if no readable state or backup is left
+        #[arg(long)]
+        fresh: bool,
+    },
+    /// Write XP, level, achievements and streaks to a portable archive
+    Export {
+        /// Archive to write (`-` for stdout)
+        path: PathBuf,
+    },
+    /// Bring in progress from an archive made by `cwinner state export`
+    Import {
+        path: PathBuf,
+        /// replace: take the archive as is; max: keep the higher of each
+        /// counter; sum: add counters made on separate machines
+        #[arg(long, default_value = "max")]
+        merge: Merge,
+    },
+}
+
+#[derive(Subcommand)]
+enum XpCommands {
+    /// Show how much XP an event would earn now, step by step
+    Explain {
+        /// Event as hooks send it, e.g. '{"event":"GitCommit"}' (`-` for stdin)
+        event: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ThemeCommands {
+    /// List built-in and custom themes
+    List,
+    /// Show a sample celebration in a theme (defaults to the configured one)
+    Preview {
+        name: Option<String>,
+        /// Only preview this celebration level
+        #[arg(long, value_enum)]
+        level: Option<PreviewLevel>,
+        /// Epic animation: confetti, fireworks, rain, starfield, banner or typewriter
+        #[arg(long)]
+        animation: Option<Animation>,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum PreviewLevel {
+    /// Progress bar
+    Mini,
+    /// Toast
+    Medium,
+    /// Animation and splash box
+    Epic,
+    LevelUp,
+}
+
 #[derive(Subcommand)]
 enum SoundsCommands {
     /// List available sound packs
     List,
+    /// Render a TOML note-sequence spec to a WAV file
+    Render {
+        /// Path to the `.toml` spec
+        spec: PathBuf,
+        /// Output WAV path (defaults to the spec path with a `.wav` extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
 }
 
 fn main() {
@@ -77,6 +225,15 @@ fn main() {
             println!("  XP:     {}", s.xp);
             println!("  Streak: {} days", s.commit_streak_days);
             println!("  Total commits: {}", s.commits_total);
+            let cfg = cwinner_lib::config::Config::load();
+            if let Some(shards) = Shards::from_config(&cfg.sync) {
+                let machines = cwinner_lib::sync::machines(shards.dir());
+                println!(
+                    "  Sync:   {} ({})",
+                    shards.dir().display(),
+                    machines.join(", ")
+                );
+            }
         }
         Commands::Stats => {
             let s = State::load();
@@ -135,6 +292,13 @@ fn main() {
                 }
             }
         }
+        Commands::Streak => {
+            let cfg = cwinner_lib::config::Config::load();
+            let policy = StreakPolicy::from_config(&cfg.streak);
+            let mut s = State::load();
+            s.set_day_zone(&policy.zone);
+            print!("{}", streak::view(&s, &policy, policy.zone.today()));
+        }
         Commands::Statusline => {
             let s = State::load();
             let (xp_in_level, xp_needed) = cwinner_lib::renderer::xp_progress(s.level, s.xp);
@@ -165,6 +329,166 @@ fn main() {
                 }
             });
         }
+        Commands::Dnd { cmd } => {
+            use cwinner_lib::schedule::{DndToggle, dnd_active, in_quiet_hours, parse_duration};
+            let save = |toggle: DndToggle| {
+                let result = DndToggle::path()
+                    .ok_or_else(|| anyhow::anyhow!("no data directory"))
+                    .and_then(|p| toggle.save_to(&p));
+                if let Err(e) = result {
+                    eprintln!("DND error: {e}");
+                    std::process::exit(1);
+                }
+            };
+            match cmd {
+                DndCommands::On => {
+                    save(DndToggle { until: None });
+                    println!("🔕 Do-not-disturb on (until `cwinner dnd off`)");
+                }
+                DndCommands::For { duration } => {
+                    let until = parse_duration(&duration)
+                        .and_then(|d| chrono::Utc::now().checked_add_signed(d));
+                    let Some(until) = until else {
+                        eprintln!("Invalid duration {duration:?} (try 30m, 2h, 1h30m)");
+                        std::process::exit(1);
+                    };
+                    save(DndToggle { until: Some(until) });
+                    println!(
+                        "🔕 Do-not-disturb on until {}",
+                        until.with_timezone(&chrono::Local).format("%H:%M")
+                    );
+                }
+                DndCommands::Off => {
+                    if let Err(e) = DndToggle::clear() {
+                        eprintln!("DND error: {e}");
+                        std::process::exit(1);
+                    }
+                    println!("🔔 Do-not-disturb off");
+                    if in_quiet_hours(
+                        &cwinner_lib::config::Config::load().schedule,
+                        chrono::Local::now().naive_local(),
+                    ) {
+                        println!("  (quiet hours from config are still in effect)");
+                    }
+                }
+                DndCommands::Status => {
+                    let cfg = cwinner_lib::config::Config::load();
+                    let now = chrono::Utc::now();
+                    let toggle = DndToggle::load().filter(|t| t.is_active(now));
+                    if !dnd_active(&cfg.schedule, toggle.as_ref(), now) {
+                        println!("🔔 Do-not-disturb off");
+                    } else if let Some(until) = toggle.as_ref().and_then(|t| t.until) {
+                        println!(
+                            "🔕 Do-not-disturb on until {}",
+                            until.with_timezone(&chrono::Local).format("%H:%M")
+                        );
+                    } else if toggle.is_some() {
+                        println!("🔕 Do-not-disturb on (until `cwinner dnd off`)");
+                    } else {
+                        println!("🔕 Quiet hours (from config)");
+                    }
+                }
+            }
+        }
+        Commands::Focus { cmd } => match cmd {
+            FocusCommands::On => {
+                if let Err(e) = cwinner_lib::focus::set(true) {
+                    eprintln!("Focus error: {e}");
+                    std::process::exit(1);
+                }
+                println!("🎯 Focus mode on — celebrations will wait for the next break");
+            }
+            FocusCommands::Off => {
+                if let Err(e) = cwinner_lib::focus::set(false) {
+                    eprintln!("Focus error: {e}");
+                    std::process::exit(1);
+                }
+                println!("🎯 Focus mode off");
+                flush_focus(&get_tty());
+            }
+            FocusCommands::Flush => {
+                if !flush_focus(&get_tty()) {
+                    println!("Nothing held back");
+                }
+            }
+            FocusCommands::Status => {
+                if cwinner_lib::focus::is_on() {
+                    println!("🎯 Focus mode on");
+                } else {
+                    println!("🎯 Focus mode off");
+                }
+            }
+        },
+        Commands::Render {
+            level,
+            export,
+            theme,
+            animation,
+            seed,
+            size: (cols, rows),
+        } => {
+            let visual = visual_with_theme(theme);
+            // A cast is played back elsewhere, so don't limit it to this terminal
+            let caps = TermCaps {
+                unicode: true,
+                color: true,
+                depth: ColorDepth::Truecolor,
+            };
+            let theme = Theme::for_terminal(&visual, &caps);
+            let style = EpicStyle {
+                animation: animation.unwrap_or(visual.animations.default),
+                seed: seed.or(visual.seed).unwrap_or_else(rand::random),
+            };
+            let mut rec = Recorder::new(cols, rows);
+            let written = render_sample(&mut rec, &level, &theme, &style).and_then(|()| {
+                let mut file = std::io::BufWriter::new(std::fs::File::create(&export)?);
+                cwinner_lib::cast::write(&mut file, &rec, Some("cwinner"))?;
+                std::io::Write::flush(&mut file)
+            });
+            match written {
+                Ok(()) => println!(
+                    "Wrote {} ({:.1}s)",
+                    export.display(),
+                    rec.elapsed().as_secs_f64()
+                ),
+                Err(e) => {
+                    eprintln!("Export error: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Theme { cmd } => match cmd {
+            ThemeCommands::List => {
+                let current = cwinner_lib::config::Config::load().visual.theme;
+                for name in Theme::available() {
+                    let marker = if name == current { "*" } else { " " };
+                    println!("{marker} {name}");
+                }
+            }
+            ThemeCommands::Preview {
+                name,
+                level,
+                animation,
+            } => {
+                let visual = visual_with_theme(name);
+                let theme = Theme::from_config(&visual);
+                let style = EpicStyle {
+                    animation: animation.unwrap_or(visual.animations.default),
+                    seed: visual.seed.unwrap_or_else(rand::random),
+                };
+                let levels = match level {
+                    Some(level) => vec![level],
+                    None => vec![PreviewLevel::Medium, PreviewLevel::Epic],
+                };
+                let Ok(mut tty) = Tty::open(&get_tty()) else {
+                    eprintln!("No terminal to preview in");
+                    std::process::exit(1);
+                };
+                for level in levels {
+                    let _ = render_sample(&mut tty, &level, &theme, &style);
+                }
+            }
+        },
         Commands::Sounds { cmd } => match cmd {
             SoundsCommands::List => {
                 let sounds_dir = dirs::config_dir()
@@ -179,10 +503,178 @@ fn main() {
                     println!("No sound packs in {}", sounds_dir.display());
                 }
             }
+            SoundsCommands::Render { spec, output } => {
+                let output = output.unwrap_or_else(|| spec.with_extension("wav"));
+                let result = cwinner_lib::sounds::SoundSpec::load(&spec)
+                    .and_then(|s| s.to_wav())
+                    .and_then(|wav| Ok(std::fs::write(&output, wav)?));
+                match result {
+                    Ok(()) => println!("✓ Rendered {}", output.display()),
+                    Err(e) => {
+                        eprintln!("Render error: {e:#}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+        Commands::State { cmd } => match cmd {
+            StateCommands::Repair { fresh } => {
+                use cwinner_lib::state::{Repair, StateLock};
+                let Some(path) = State::state_path() else {
+                    eprintln!("Repair error: no data directory");
+                    std::process::exit(1);
+                };
+                let _lock = StateLock::acquire_or_warn(&path);
+                match State::repair(&path, fresh) {
+                    Ok(Repair::Healthy) => println!("✓ {} is fine", path.display()),
+                    Ok(Repair::Restored { from, xp }) => {
+                        println!("✓ Restored {xp} XP from {}", from.display())
+                    }
+                    Ok(Repair::Fresh) => println!("✓ Started over with fresh progress"),
+                    Err(e) => {
+                        eprintln!("Repair error: {e:#}");
+                        std::process::exit(1);
+                    }
+                }
+            }
+            StateCommands::Export { path } => {
+                let result = cwinner_lib::archive::export(&State::load()).and_then(|data| {
+                    if path.as_os_str() == "-" {
+                        print!("{data}");
+                    } else {
+                        std::fs::write(&path, data)?;
+                        println!("✓ Exported progress to {}", path.display());
+                    }
+                    Ok(())
+                });
+                if let Err(e) = result {
+                    eprintln!("Export error: {e:#}");
+                    std::process::exit(1);
+                }
+            }
+            StateCommands::Import { path, merge } => {
+                if State::state_path().is_some_and(|p| State::needs_repair(&p).is_some()) {
+                    eprintln!("Import error: run `cwinner state repair` first");
+                    std::process::exit(1);
+                }
+                let cfg = cwinner_lib::config::Config::load();
+                // Shards only record progress, so a replace that lowers
+                // anything would be undone by the next event
+                if merge == Merge::Replace && cfg.sync.dir.is_some() {
+                    eprintln!(
+                        "Import error: --merge replace can't be used while [sync] is on; use max or sum"
+                    );
+                    std::process::exit(1);
+                }
+                let imported = std::fs::read_to_string(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|data| cwinner_lib::archive::import(&data));
+                let imported = match imported {
+                    Ok(state) => state,
+                    Err(e) => {
+                        eprintln!("Import error: {}: {e:#}", path.display());
+                        std::process::exit(1);
+                    }
+                };
+                let import = |s: &mut State| {
+                    let before = s.xp;
+                    s.merge(imported, merge);
+                    (before, s.clone())
+                };
+                // With sync on, the imported progress goes into this machine's shard
+                let (before, after) = State::update(|s| match Shards::from_config(&cfg.sync) {
+                    Some(shards) => shards.apply(s, import),
+                    None => import(s),
+                });
+                println!(
+                    "✓ Imported ({}): Lvl {} {} — {} XP (was {})",
+                    merge.name(),
+                    after.level,
+                    after.level_name,
+                    after.xp,
+                    before
+                );
+            }
+        },
+        Commands::Xp { cmd } => match cmd {
+            XpCommands::Explain { event } => {
+                use cwinner_lib::{celebration::decide, xp};
+                let data = if event == "-" {
+                    std::io::read_to_string(std::io::stdin()).map_err(anyhow::Error::from)
+                } else {
+                    Ok(event)
+                };
+                let event = match data.and_then(|data| xp::parse_event(&data)) {
+                    Ok(event) => event,
+                    Err(e) => {
+                        eprintln!("Explain error: {e:#}");
+                        std::process::exit(1);
+                    }
+                };
+                let cfg = cwinner_lib::config::Config::load();
+                let state = State::load();
+                let level = decide(&event, &state, &cfg);
+                let now = chrono::Utc::now();
+                let today = StreakPolicy::from_config(&cfg.streak).zone.date(now);
+                let award = xp::award(&event, &level, &state, &cfg.xp, now, today);
+                println!("{} ({level:?} celebration)", xp::event_key(&event));
+                print!("{}", award.explain());
+            }
         },
     }
 }
 
+/// The configured visual settings, switched to theme `name` if given. Exits
+/// if that theme can't be loaded.
+fn visual_with_theme(name: Option<String>) -> VisualConfig {
+    let mut visual = cwinner_lib::config::Config::load().visual;
+    if let Some(name) = name {
+        if let Err(e) = Theme::load_named(&name) {
+            eprintln!("Theme error: {e:#}");
+            std::process::exit(1);
+        }
+        visual.theme = name;
+    }
+    visual
+}
+
+/// Render a sample celebration at `level` with the user's current state.
+fn render_sample(
+    target: &mut impl Target,
+    level: &PreviewLevel,
+    theme: &Theme,
+    style: &EpicStyle,
+) -> std::io::Result<()> {
+    use cwinner_lib::celebration::CelebrationLevel;
+    use cwinner_lib::renderer;
+    let state = State::load();
+    let achievements = ["First Commit — Made your first git commit".to_string()];
+    let label = Some("📝 Git Commit");
+    match level {
+        PreviewLevel::Mini => renderer::render_progress_bar_to(target, &state, theme),
+        PreviewLevel::Medium | PreviewLevel::Epic => {
+            let level = match level {
+                PreviewLevel::Medium => CelebrationLevel::Medium,
+                _ => CelebrationLevel::Epic,
+            };
+            renderer::render_to(target, &level, &state, &achievements, label, theme, style)
+        }
+        PreviewLevel::LevelUp => {
+            renderer::render_level_up_to(target, &state, None, &achievements, theme, style)
+        }
+    }
+}
+
+/// Parse a terminal size like `80x24`.
+fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let invalid = || format!("expected COLSxROWS like 80x24, got {s:?}");
+    let (cols, rows) = s.split_once('x').ok_or_else(invalid)?;
+    match (cols.parse(), rows.parse()) {
+        (Ok(cols), Ok(rows)) if cols > 0 && rows > 0 => Ok((cols, rows)),
+        _ => Err(invalid()),
+    }
+}
+
 fn get_tty() -> String {
     #[cfg(target_os = "linux")]
     {
@@ -288,6 +780,7 @@ fn send_hook_event(event: HookEvent, tty_path: &str) {
 
     let event_kind = match event {
         HookEvent::PostToolUse => EventKind::PostToolUse,
+        HookEvent::PostToolUseFailure => EventKind::PostToolUseFailure,
         HookEvent::TaskCompleted => EventKind::TaskCompleted,
         HookEvent::SessionEnd => EventKind::SessionEnd,
     };
@@ -297,11 +790,17 @@ fn send_hook_event(event: HookEvent, tty_path: &str) {
         .and_then(|v| v.as_str())
         .map(String::from);
     // Claude Code sends PostToolUse only on success (failures go to PostToolUseFailure),
-    // and doesn't include exit_code in tool_response. Default to 0 for PostToolUse.
+    // and doesn't include exit_code in tool_response. Default to 0 for PostToolUse
+    // and 1 for PostToolUseFailure.
+    let default_exit = if event_kind == EventKind::PostToolUseFailure {
+        1
+    } else {
+        0
+    };
     let exit_code = meta
         .pointer("/tool_response/exit_code")
         .and_then(|v| v.as_i64())
-        .unwrap_or(0);
+        .unwrap_or(default_exit);
     let mut metadata = HashMap::new();
     metadata.insert("exit_code".into(), serde_json::json!(exit_code));
     // Pass bash command text for custom trigger matching
@@ -309,7 +808,12 @@ fn send_hook_event(event: HookEvent, tty_path: &str) {
         if let Some(cmd) = input.get("command").and_then(|v| v.as_str()) {
             metadata.insert("command".into(), serde_json::json!(cmd));
         }
+        // Tells repeated calls apart without sending what they were
+        let hash = cwinner_lib::xp::fingerprint(&input.to_string());
+        metadata.insert("tool_input_hash".into(), serde_json::json!(hash));
     }
+    // Let the daemon draw for this terminal (fonts, locale, NO_COLOR)
+    cwinner_lib::terminal::TermEnv::from_env().to_metadata(&mut metadata);
 
     let e = Event {
         event: event_kind,
@@ -347,6 +851,36 @@ fn send_hook_event(event: HookEvent, tty_path: &str) {
     let _ = stream.read(&mut ack);
 }
 
+/// Ask a running daemon to show the focus batch on `tty_path`. Returns
+/// false if there was nothing to show or the daemon isn't running.
+fn flush_focus(tty_path: &str) -> bool {
+    use cwinner_lib::daemon::server::{Control, socket_path};
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    let Ok(mut stream) = UnixStream::connect(socket_path()) else {
+        return false;
+    };
+    let control = Control::FlushFocus {
+        tty_path: tty_path.to_string(),
+    };
+    let json = serde_json::to_string(&control).unwrap_or_default();
+    let _ = stream.write_all(format!("{}\n", json).as_bytes());
+    stream
+        .set_read_timeout(Some(std::time::Duration::from_secs(10)))
+        .ok();
+    let mut reply = String::new();
+    let _ = stream.read_to_string(&mut reply);
+    match reply.trim() {
+        "ok" => true,
+        "queued" => {
+            println!("Another celebration is on screen; the batch will follow it");
+            true
+        }
+        _ => false,
+    }
+}
+
 /// Start the daemon as a detached background process so it inherits the
 /// current session's audio context (PipeWire/PulseAudio).  Systemd user
 /// services run in an isolated cgroup that cannot reach the audio server
@@ -409,4 +943,12 @@ mod tests {
             "get_tty() should return a /dev/ path, got: {tty}"
         );
     }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("80x24"), Ok((80, 24)));
+        assert!(parse_size("80").is_err());
+        assert!(parse_size("0x24").is_err());
+        assert!(parse_size("80x-1").is_err());
+    }
 }
@@ -1,20 +1,86 @@
+use crate::animation::{
+    self, Effect, EpicStyle, FRAME_MS, INTRO_FRAMES, SPLASH_FRAMES, Scene, Sprite,
+};
 use crate::celebration::CelebrationLevel;
+use crate::config::Animation;
+use crate::font;
+use crate::screen::{Target, Tty};
 use crate::state::{LEVELS, State};
+use crate::theme::{Theme, ThemeColor};
 use crossterm::{
     cursor, execute, queue,
     style::{Color, Print, ResetColor, SetForegroundColor},
     terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use rand::RngExt;
-use std::fs::OpenOptions;
+use std::collections::HashMap;
 use std::io::{self, Write};
-use std::sync::Mutex;
-use std::thread;
+use std::sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard};
 use std::time::{Duration, Instant};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// When a terminal last finished rendering, and at what level.
+type LastRender = Option<(Instant, CelebrationLevel)>;
+
+/// One terminal's render lock: held (`busy`) while rendering to it.
+#[derive(Default)]
+struct Slot {
+    state: Mutex<SlotState>,
+    idle: Condvar,
+}
+
+#[derive(Default)]
+struct SlotState {
+    busy: bool,
+    last: LastRender,
+}
+
+impl Slot {
+    /// The slot's state once no render to the terminal is in progress.
+    fn wait_idle(&self) -> MutexGuard<'_, SlotState> {
+        let guard = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.idle
+            .wait_while(guard, |s| s.busy)
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
 
-/// Global render lock — prevents concurrent alternate screen switches
-/// which confuse Claude Code's differential renderer.
-static RENDER_LOCK: Mutex<Option<(Instant, CelebrationLevel)>> = Mutex::new(None);
+/// Held while rendering to one terminal; pass it to `finish_render`.
+pub struct RenderSlot {
+    slot: Arc<Slot>,
+}
+
+impl Drop for RenderSlot {
+    fn drop(&mut self) {
+        self.slot
+            .state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .busy = false;
+        self.slot.idle.notify_all();
+    }
+}
+
+/// Per-tty render locks — prevent concurrent alternate screen switches within
+/// one terminal (which confuse Claude Code's differential renderer) while
+/// letting celebrations in different terminals render in parallel.
+static RENDER_SLOTS: LazyLock<Mutex<HashMap<String, Arc<Slot>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn render_slot(tty_path: &str) -> Arc<Slot> {
+    let mut slots = RENDER_SLOTS.lock().unwrap_or_else(|e| e.into_inner());
+    // Forget terminals nobody is rendering to whose cooldown has expired
+    slots.retain(|tty, slot| {
+        tty == tty_path
+            || Arc::strong_count(slot) > 1
+            || slot.state.try_lock().map_or(true, |s| {
+                s.busy
+                    || s.last
+                        .as_ref()
+                        .is_some_and(|(at, _)| at.elapsed() < RENDER_COOLDOWN)
+            })
+    });
+    Arc::clone(slots.entry(tty_path.to_string()).or_default())
+}
 
 /// Minimum gap between renders to let Claude Code's renderer recover.
 const RENDER_COOLDOWN: Duration = Duration::from_millis(3500);
@@ -24,18 +90,19 @@ const RENDER_COOLDOWN: Duration = Duration::from_millis(3500);
 pub fn level_threshold(index: usize) -> u32 {
     LEVELS.get(index).map(|&(t, _)| t).unwrap_or(u32::MAX)
 }
-const CONFETTI_CHARS: &[char] = &['✦', '★', '♦', '●', '*', '+', '#', '✿', '❋'];
-const CONFETTI_COLORS: &[Color] = &[
-    Color::Red,
-    Color::Green,
-    Color::Yellow,
-    Color::Blue,
-    Color::Magenta,
-    Color::Cyan,
-    Color::White,
-];
 
 pub fn xp_bar_string(current_xp: u32, next_xp: u32, width: usize) -> String {
+    xp_bar_with(current_xp, next_xp, width, '█', '░')
+}
+
+/// An XP bar drawn with the given filled and empty glyphs.
+pub fn xp_bar_with(
+    current_xp: u32,
+    next_xp: u32,
+    width: usize,
+    filled_glyph: char,
+    empty_glyph: char,
+) -> String {
     let ratio = if next_xp == 0 {
         1.0
     } else {
@@ -44,10 +111,10 @@ pub fn xp_bar_string(current_xp: u32, next_xp: u32, width: usize) -> String {
     let filled = ((ratio * width as f64).round() as usize).min(width);
     let mut s = String::new();
     for _ in 0..filled {
-        s.push('█');
+        s.push(filled_glyph);
     }
     for _ in filled..width {
-        s.push('░');
+        s.push(empty_glyph);
     }
     s
 }
@@ -69,31 +136,41 @@ pub fn xp_progress(level: u32, xp: u32) -> (u32, u32) {
     (xp_in_level, xp_needed)
 }
 
-/// Acquire the render lock and check cooldown. Returns the guard if rendering
-/// is allowed, or None if we should skip (too recent). The caller MUST call
-/// `finish_render` with the guard when done.
-pub fn acquire_render_slot(
-    level: &CelebrationLevel,
-) -> Option<std::sync::MutexGuard<'static, Option<(Instant, CelebrationLevel)>>> {
-    let guard = match RENDER_LOCK.lock() {
-        Ok(g) => g,
-        Err(e) => e.into_inner(),
-    };
-    if let Some((last_time, ref last_level)) = *guard {
-        if last_time.elapsed() < RENDER_COOLDOWN && level <= last_level {
-            // Skip cooldown only for lower/equal level; higher level always renders
-            return None;
+/// Acquire the render lock for `tty_path` and check its cooldown. Returns the
+/// guard if rendering is allowed, or None if we should skip (too recent). The
+/// caller MUST call `finish_render` with the guard when done.
+pub fn acquire_render_slot(tty_path: &str, level: &CelebrationLevel) -> Option<RenderSlot> {
+    let slot = render_slot(tty_path);
+    {
+        let mut state = slot.wait_idle();
+        if let Some((last_time, ref last_level)) = state.last {
+            if last_time.elapsed() < RENDER_COOLDOWN && level <= last_level {
+                // Skip cooldown only for lower/equal level; higher level always renders
+                return None;
+            }
         }
+        state.busy = true;
+    }
+    Some(RenderSlot { slot })
+}
+
+/// How long until the render cooldown for `tty_path` has fully expired (zero
+/// if it has). Blocks while a render to that terminal is in progress.
+pub fn cooldown_remaining(tty_path: &str) -> Duration {
+    match render_slot(tty_path).wait_idle().last {
+        Some((last_time, _)) => RENDER_COOLDOWN.saturating_sub(last_time.elapsed()),
+        None => Duration::ZERO,
     }
-    Some(guard)
 }
 
 /// Mark render as finished — sets the cooldown timestamp and level.
-pub fn finish_render(
-    mut guard: std::sync::MutexGuard<'static, Option<(Instant, CelebrationLevel)>>,
-    level: &CelebrationLevel,
-) {
-    *guard = Some((Instant::now(), level.clone()));
+pub fn finish_render(guard: RenderSlot, level: &CelebrationLevel) {
+    guard
+        .slot
+        .state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .last = Some((Instant::now(), level.clone()));
 }
 
 /// RAII guard that restores terminal state (leave alternate screen) on drop,
@@ -102,11 +179,11 @@ pub fn finish_render(
 /// Note: we do NOT touch cursor visibility here. Claude Code's renderer owns
 /// the cursor on the main screen and immediately overrides any Show/Hide we
 /// send. Attempting to hide the cursor just creates a race condition.
-struct TermGuard<'a> {
-    tty: &'a mut std::fs::File,
+struct TermGuard<'a, W: Write> {
+    tty: &'a mut W,
 }
 
-impl<'a> Drop for TermGuard<'a> {
+impl<W: Write> Drop for TermGuard<'_, W> {
     fn drop(&mut self) {
         let _ = execute!(self.tty, LeaveAlternateScreen);
     }
@@ -116,314 +193,706 @@ pub fn render(
     tty_path: &str,
     level: &CelebrationLevel,
     state: &State,
-    achievement: Option<&str>,
+    achievements: &[String],
     label: Option<&str>,
+    theme: &Theme,
+    style: &EpicStyle,
 ) {
+    if *level <= CelebrationLevel::Mini {
+        return;
+    }
+    if let Ok(mut tty) = Tty::open(tty_path) {
+        let _ = render_to(&mut tty, level, state, achievements, label, theme, style);
+    }
+}
+
+/// Render a celebration to any target, e.g. a `Recorder`.
+pub fn render_to(
+    target: &mut impl Target,
+    level: &CelebrationLevel,
+    state: &State,
+    achievements: &[String],
+    label: Option<&str>,
+    theme: &Theme,
+    style: &EpicStyle,
+) -> io::Result<()> {
     match level {
-        CelebrationLevel::Off => {}
+        CelebrationLevel::Off => Ok(()),
         CelebrationLevel::Mini => {
             // Mini progress bar is now shown via Claude Code's status line
             // (cwinner statusline command). No alternate screen render needed.
+            Ok(())
         }
-        CelebrationLevel::Medium => {
-            let _ = render_toast(tty_path, state, achievement, label);
-        }
-        CelebrationLevel::Epic => {
-            let _ = render_epic(tty_path, state, achievement, label);
-        }
+        CelebrationLevel::Medium => render_toast(target, state, achievements, label, theme),
+        CelebrationLevel::Epic => render_epic(target, state, achievements, label, theme, style),
     }
 }
 
-/// Count extra display columns from wide characters (emoji).
-/// Most emoji are 2 columns wide but count as 1 char for Rust's `{:^width$}`.
-fn wide_char_extra(s: &str) -> usize {
-    s.chars()
-        .filter(|&c| c as u32 >= 0x1F000 || c == '⚡')
-        .count()
+/// Display width of `s` in terminal columns (emoji and CJK take two).
+pub fn display_width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
 }
 
-/// Center text in a field of `width` display columns, accounting for wide emoji.
+/// Cut `s` down to at most `width` display columns.
+pub(crate) fn truncate_to_width(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (i, c) in s.char_indices() {
+        used += UnicodeWidthChar::width(c).unwrap_or(0);
+        if used > width {
+            return &s[..i];
+        }
+    }
+    s
+}
+
+/// Center text in a field of `width` display columns, accounting for wide
+/// characters and cutting off anything that doesn't fit.
 fn center_padded(text: &str, width: usize) -> String {
-    let adjusted = width.saturating_sub(wide_char_extra(text));
-    format!("{:^width$}", text, width = adjusted)
+    let text = truncate_to_width(text, width);
+    let spare = width - display_width(text);
+    let left = spare / 2;
+    format!("{}{}{}", " ".repeat(left), text, " ".repeat(spare - left))
 }
 
-fn open_tty(tty_path: &str) -> io::Result<std::fs::File> {
-    OpenOptions::new().write(true).open(tty_path)
+/// Left-align text in a field of `width` display columns.
+fn left_padded(text: &str, width: usize) -> String {
+    let text = truncate_to_width(text, width);
+    format!("{}{}", text, " ".repeat(width - display_width(text)))
 }
 
-fn tty_size(tty: &std::fs::File) -> (u16, u16) {
-    use std::os::unix::io::AsRawFd;
-    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
-    let ret = unsafe { libc::ioctl(tty.as_raw_fd(), libc::TIOCGWINSZ, &mut ws) };
-    if ret == 0 && ws.ws_col > 0 && ws.ws_row > 0 {
-        (ws.ws_col, ws.ws_row)
-    } else {
-        (80, 24)
+/// A command that is only emitted when present — used to drop color codes
+/// entirely for `NO_COLOR` and dumb terminals.
+struct Maybe<C>(Option<C>);
+
+impl<C: crossterm::Command> crossterm::Command for Maybe<C> {
+    fn write_ansi(&self, f: &mut impl std::fmt::Write) -> std::fmt::Result {
+        match &self.0 {
+            Some(c) => c.write_ansi(f),
+            None => Ok(()),
+        }
     }
 }
 
+/// Switch foreground color, unless the theme is colorless.
+fn paint(theme: &Theme, color: Color) -> Maybe<SetForegroundColor> {
+    Maybe((!theme.no_color).then_some(SetForegroundColor(color)))
+}
+
+/// Reset colors, unless the theme is colorless (nothing to reset).
+fn unpaint(theme: &Theme) -> Maybe<ResetColor> {
+    Maybe((!theme.no_color).then_some(ResetColor))
+}
+
 /// Format the toast message line for display.
 pub fn format_toast_msg(
     state: &State,
     achievement: Option<&str>,
     label: Option<&str>,
+    theme: &Theme,
 ) -> (String, Color) {
-    if let Some(name) = achievement {
+    let div = &theme.glyphs.divider;
+    let (msg, color) = if let Some(name) = achievement {
         (
-            format!("🏆 {} │ {} │ {} XP", name, state.level_name, state.xp),
-            Color::Yellow,
+            format!(
+                "{} {} {div} {} {div} {} XP",
+                theme.glyphs.trophy, name, state.level_name, state.xp
+            ),
+            theme.fg(theme.palette.achievement),
         )
     } else {
-        let sep = if label.is_some() { " │ " } else { " " };
-        let prefix = label.unwrap_or("⚡");
+        let sep = if label.is_some() {
+            format!(" {div} ")
+        } else {
+            " ".to_string()
+        };
+        let prefix = label.unwrap_or(&theme.glyphs.bolt);
         let next = level_threshold(state.level as usize);
         if next == u32::MAX {
             (
                 format!(
-                    "{}{}{} │ {} XP │ MAX",
+                    "{}{}{} {div} {} XP {div} MAX",
                     prefix, sep, state.level_name, state.xp
                 ),
-                Color::Cyan,
+                theme.fg(theme.palette.toast),
             )
         } else {
             let (xp_in_level, xp_needed) = xp_progress(state.level, state.xp);
-            let bar = xp_bar_string(xp_in_level, xp_needed, 15);
+            let bar = theme.bar(xp_in_level, xp_needed, 15);
             (
                 format!(
-                    "{}{}{} │ {} │ {} XP",
+                    "{}{}{} {div} {} {div} {} XP",
                     prefix, sep, state.level_name, bar, state.xp
                 ),
-                Color::Cyan,
+                theme.fg(theme.palette.toast),
             )
         }
-    }
+    };
+    (theme.text(&msg).into_owned(), color)
 }
 
 /// Mini celebration: brief progress bar on the bottom line of the terminal.
 /// Uses alternate screen (same as toast) for Claude Code compatibility.
 /// Duration: 3 seconds.
-pub fn render_progress_bar(tty_path: &str, state: &State) -> io::Result<()> {
-    let mut tty = open_tty(tty_path)?;
-    let (cols, rows) = tty_size(&tty);
-    let (msg, color) = format_toast_msg(state, None, None);
+pub fn render_progress_bar(tty_path: &str, state: &State, theme: &Theme) -> io::Result<()> {
+    render_progress_bar_to(&mut Tty::open(tty_path)?, state, theme)
+}
 
-    let pad_width = (cols as usize).saturating_sub(2);
-    let bottom_row = rows.saturating_sub(1);
+pub fn render_progress_bar_to(
+    target: &mut impl Target,
+    state: &State,
+    theme: &Theme,
+) -> io::Result<()> {
+    let (msg, color) = format_toast_msg(state, None, None, theme);
 
     execute!(
-        tty,
+        target,
         EnterAlternateScreen,
         cursor::Hide,
         Clear(ClearType::All)
     )?;
-    let _guard = TermGuard { tty: &mut tty };
+    let _guard = TermGuard { tty: target };
 
-    let start = Instant::now();
+    let start = _guard.tty.elapsed();
     loop {
+        let (cols, rows) = _guard.tty.size();
         queue!(
             _guard.tty,
             Clear(ClearType::All),
-            cursor::MoveTo(0, bottom_row),
-            SetForegroundColor(color),
-            Print(format!("{:<width$}", msg, width = pad_width)),
-            ResetColor,
+            cursor::MoveTo(0, rows.saturating_sub(1)),
+            paint(theme, color),
+            Print(left_padded(&msg, (cols as usize).saturating_sub(2))),
+            unpaint(theme),
         )?;
         _guard.tty.flush()?;
-        if start.elapsed() >= Duration::from_millis(3000) {
+        if _guard.tty.key_pressed() || _guard.tty.elapsed() - start >= Duration::from_millis(3000) {
             break;
         }
-        thread::sleep(Duration::from_millis(50));
+        _guard.tty.sleep(Duration::from_millis(50));
     }
 
     Ok(())
 }
 
+/// How long a toast stays up: 1.5s plain, 2.5s for one achievement, and
+/// 1.5s per achievement (capped at 6s) when several are cycled through.
+pub fn toast_duration_ms(achievements: usize) -> u64 {
+    match achievements {
+        0 => 1500,
+        n => (n as u64 * 1500).clamp(2500, 6000),
+    }
+}
+
+/// Toast lines to cycle through: one per achievement (numbered when there are
+/// several), or the plain progress line when nothing was unlocked.
+pub fn toast_messages(
+    state: &State,
+    achievements: &[String],
+    label: Option<&str>,
+    theme: &Theme,
+) -> Vec<(String, Color)> {
+    let n = achievements.len();
+    match achievements {
+        [] => vec![format_toast_msg(state, None, label, theme)],
+        [one] => vec![format_toast_msg(state, Some(one), label, theme)],
+        many => many
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let numbered = format!("{} ({}/{})", a, i + 1, n);
+                format_toast_msg(state, Some(&numbered), label, theme)
+            })
+            .collect(),
+    }
+}
+
 /// Brief alternate screen overlay — the only safe way to display in a terminal
 /// managed by Claude Code's differential renderer without corrupting its state.
-pub fn render_toast(
-    tty_path: &str,
+fn render_toast(
+    target: &mut impl Target,
     state: &State,
-    achievement: Option<&str>,
+    achievements: &[String],
     label: Option<&str>,
+    theme: &Theme,
 ) -> io::Result<()> {
-    let mut tty = open_tty(tty_path)?;
-    let (cols, rows) = tty_size(&tty);
-    let (msg, color) = format_toast_msg(state, achievement, label);
-    let duration = if achievement.is_some() {
-        2500u64
-    } else {
-        1500u64
-    };
-
-    let mid_row = rows / 2;
-    let pad_width = (cols as usize).saturating_sub(2);
+    let messages = toast_messages(state, achievements, label, theme);
+    let duration = toast_duration_ms(achievements.len());
 
     execute!(
-        tty,
+        target,
         EnterAlternateScreen,
         cursor::Hide,
         Clear(ClearType::All)
     )?;
-    let _guard = TermGuard { tty: &mut tty };
+    let _guard = TermGuard { tty: target };
 
-    let start = Instant::now();
+    let start = _guard.tty.elapsed();
     loop {
+        // Re-read the size every frame so a resize is redrawn cleanly
+        let (cols, rows) = _guard.tty.size();
+        // Give each achievement an equal share of the toast
+        let elapsed = (_guard.tty.elapsed() - start).as_millis() as u64;
+        let idx = ((elapsed * messages.len() as u64) / duration).min(messages.len() as u64 - 1);
+        let (msg, color) = &messages[idx as usize];
         queue!(
             _guard.tty,
             Clear(ClearType::All),
-            cursor::MoveTo(0, mid_row),
-            SetForegroundColor(color),
-            Print(format!("{:^width$}", msg, width = pad_width)),
-            ResetColor,
+            cursor::MoveTo(0, rows / 2),
+            paint(theme, *color),
+            Print(center_padded(msg, (cols as usize).saturating_sub(2))),
+            unpaint(theme),
         )?;
         _guard.tty.flush()?;
-        if start.elapsed() >= Duration::from_millis(duration) {
+        if _guard.tty.key_pressed()
+            || _guard.tty.elapsed() - start >= Duration::from_millis(duration)
+        {
             break;
         }
-        thread::sleep(Duration::from_millis(50));
+        _guard.tty.sleep(Duration::from_millis(50));
     }
 
     Ok(())
 }
 
-/// Epic celebration: confetti rain → splash box over confetti background.
-/// Single alternate screen session to avoid flicker.
-fn render_epic(
-    tty_path: &str,
-    state: &State,
-    achievement: Option<&str>,
-    label: Option<&str>,
-) -> io::Result<()> {
-    let mut tty = open_tty(tty_path)?;
-    let mut rng = rand::rng();
-    let (cols, rows) = tty_size(&tty);
+/// Achievement rows for the splash box, at most `max_rows` of them: when
+/// there are too many, the last row summarises the rest.
+pub fn achievement_rows(achievements: &[String], max_rows: usize, trophy: &str) -> Vec<String> {
+    let max_rows = max_rows.max(1);
+    if achievements.len() <= max_rows {
+        return achievements
+            .iter()
+            .map(|a| format!("{} {}", trophy, a))
+            .collect();
+    }
+    let mut rows: Vec<String> = achievements[..max_rows - 1]
+        .iter()
+        .map(|a| format!("{} {}", trophy, a))
+        .collect();
+    rows.push(format!(
+        "{} +{} more achievements",
+        trophy,
+        achievements.len() - (max_rows - 1)
+    ));
+    rows
+}
 
-    execute!(
-        tty,
-        EnterAlternateScreen,
-        cursor::Hide,
-        Clear(ClearType::All)
-    )?;
-    let _guard = TermGuard { tty: &mut tty };
-
-    // Phase 1: confetti rain (3s)
-    let frames = 30u64;
-    let frame_ms = 3000 / frames;
-    for _ in 0..frames {
-        for _ in 0..(cols / 4) {
-            let col = rng.random_range(0..cols);
-            let row = rng.random_range(0..rows.saturating_sub(2));
-            let ch = CONFETTI_CHARS[rng.random_range(0..CONFETTI_CHARS.len())];
-            let color = CONFETTI_COLORS[rng.random_range(0..CONFETTI_COLORS.len())];
-            queue!(
-                _guard.tty,
-                cursor::MoveTo(col, row),
-                SetForegroundColor(color),
-                Print(ch),
-            )?;
-        }
-        _guard.tty.flush()?;
-        thread::sleep(Duration::from_millis(frame_ms));
+/// Draw one animation frame, clipped to the screen.
+fn queue_sprites(
+    out: &mut impl Write,
+    sprites: &[Sprite],
+    cols: u16,
+    rows: u16,
+    theme: &Theme,
+) -> io::Result<()> {
+    for s in sprites.iter().filter(|s| s.col < cols && s.row < rows) {
+        let text = truncate_to_width(&s.text, (cols - s.col) as usize);
+        queue!(
+            out,
+            cursor::MoveTo(s.col, s.row),
+            paint(theme, theme.fg(s.color)),
+            Print(text),
+        )?;
     }
+    Ok(())
+}
 
-    // Phase 2: splash box drawn over confetti background (3.5s)
-    // Build content lines: label (event), achievement (if any), level info
-    let label_line = label.unwrap_or("⚡ Celebration");
-    let level_line = format!("Lvl {} {} ✦ {} XP", state.level, state.level_name, state.xp);
+/// A fresh effect for `scene`, run forward to frame `t`, so an animation laid
+/// out again after a resize carries on where it was.
+fn effect_at(kind: Animation, scene: &Scene, theme: &Theme, seed: u64, t: u32) -> Box<dyn Effect> {
+    let mut effect = animation::effect(kind, scene, theme, seed);
+    for i in 0..t {
+        effect.frame(i);
+    }
+    effect
+}
 
-    // Box has 3 or 4 content rows depending on whether achievement is present
-    let has_achievement = achievement.is_some();
-    let box_height: u16 = if has_achievement { 6 } else { 5 };
-    let mid_row = rows / 2;
-    let box_top = mid_row.saturating_sub(box_height / 2);
-    let inner_width = (cols as usize).saturating_sub(2);
-    let border = "═".repeat(inner_width);
-    let top = format!("╔{}╗", border);
-    let bot = format!("╚{}╝", border);
+/// The epic splash box, laid out for one terminal size: label (event), one
+/// row per achievement, and level info.
+struct Splash {
+    cols: u16,
+    rows: u16,
+    label_line: String,
+    ach_rows: Vec<String>,
+    level_line: String,
+}
 
-    let start = Instant::now();
-    loop {
-        queue!(_guard.tty, Clear(ClearType::All))?;
+impl Splash {
+    fn new(
+        state: &State,
+        achievements: &[String],
+        label: Option<&str>,
+        theme: &Theme,
+        (cols, rows): (u16, u16),
+    ) -> Self {
+        let default_label = format!("{} Celebration", theme.glyphs.bolt);
+        let level_line = format!(
+            "Lvl {} {} {} {} XP",
+            state.level, state.level_name, theme.glyphs.sparkle, state.xp
+        );
+        // Box is 5 rows plus one per achievement, leaving room for the border
+        let ach_rows = achievement_rows(
+            achievements,
+            (rows as usize).saturating_sub(7),
+            &theme.glyphs.trophy,
+        )
+        .iter()
+        .map(|r| theme.text(r).into_owned())
+        .collect();
+        Self {
+            cols,
+            rows,
+            label_line: theme.text(label.unwrap_or(&default_label)).into_owned(),
+            ach_rows,
+            level_line: theme.text(&level_line).into_owned(),
+        }
+    }
 
-        // Redraw confetti background
-        for _ in 0..(cols / 4) {
-            let col = rng.random_range(0..cols);
-            let row = rng.random_range(0..rows.saturating_sub(2));
-            let ch = CONFETTI_CHARS[rng.random_range(0..CONFETTI_CHARS.len())];
-            let color = CONFETTI_COLORS[rng.random_range(0..CONFETTI_COLORS.len())];
-            queue!(
-                _guard.tty,
-                cursor::MoveTo(col, row),
-                SetForegroundColor(color),
-                Print(ch),
-            )?;
+    fn scene(&self, level: u32, theme: &Theme) -> Scene {
+        Scene {
+            cols: self.cols,
+            rows: self.rows,
+            level,
+            lines: std::iter::once((self.label_line.clone(), theme.palette.label))
+                .chain(
+                    self.ach_rows
+                        .iter()
+                        .map(|r| (r.clone(), theme.palette.achievement)),
+                )
+                .chain(std::iter::once((
+                    self.level_line.clone(),
+                    theme.palette.level,
+                )))
+                .collect(),
         }
+    }
+
+    fn queue(&self, out: &mut impl Write, theme: &Theme) -> io::Result<()> {
+        let box_height = 5 + self.ach_rows.len() as u16;
+        let box_top = (self.rows / 2).saturating_sub(box_height / 2);
+        let inner_width = (self.cols as usize).saturating_sub(2);
+        let b = theme.border.chars();
+        let border = b.horizontal.to_string().repeat(inner_width);
+        let v = b.vertical;
+        let border_color = theme.fg(theme.palette.border);
 
-        // Draw splash box on top
         let mut row = box_top;
         queue!(
-            _guard.tty,
+            out,
             cursor::MoveTo(0, row),
-            SetForegroundColor(Color::Yellow),
-            Print(&top),
+            paint(theme, border_color),
+            Print(format!("{}{}{}", b.top_left, border, b.top_right)),
         )?;
         row += 1;
         // Event label line (always shown)
         queue!(
-            _guard.tty,
+            out,
             cursor::MoveTo(0, row),
-            SetForegroundColor(Color::Green),
-            Print(format!("║{}║", center_padded(label_line, inner_width))),
+            paint(theme, theme.fg(theme.palette.label)),
+            Print(format!(
+                "{v}{}{v}",
+                center_padded(&self.label_line, inner_width)
+            )),
         )?;
         row += 1;
-        // Achievement line (only when unlocked)
-        if let Some(ach) = achievement {
-            let ach_line = format!("🏆 {}", ach);
+        // Achievement lines (only when unlocked)
+        for ach_line in &self.ach_rows {
             queue!(
-                _guard.tty,
+                out,
                 cursor::MoveTo(0, row),
-                SetForegroundColor(Color::Yellow),
-                Print(format!("║{}║", center_padded(&ach_line, inner_width))),
+                paint(theme, theme.fg(theme.palette.achievement)),
+                Print(format!("{v}{}{v}", center_padded(ach_line, inner_width))),
             )?;
             row += 1;
         }
         // Separator
         queue!(
-            _guard.tty,
+            out,
             cursor::MoveTo(0, row),
-            SetForegroundColor(Color::Yellow),
-            Print(format!("║{:^width$}║", "", width = inner_width)),
+            paint(theme, border_color),
+            Print(format!("{v}{:^width$}{v}", "", width = inner_width)),
         )?;
         row += 1;
         // Level line
         queue!(
-            _guard.tty,
+            out,
             cursor::MoveTo(0, row),
-            SetForegroundColor(Color::Cyan),
-            Print(format!("║{}║", center_padded(&level_line, inner_width))),
+            paint(theme, theme.fg(theme.palette.level)),
+            Print(format!(
+                "{v}{}{v}",
+                center_padded(&self.level_line, inner_width)
+            )),
         )?;
         row += 1;
         queue!(
-            _guard.tty,
+            out,
             cursor::MoveTo(0, row),
-            SetForegroundColor(Color::Yellow),
-            Print(&bot),
-            ResetColor,
-        )?;
+            paint(theme, border_color),
+            Print(format!("{}{}{}", b.bottom_left, border, b.bottom_right)),
+            unpaint(theme),
+        )
+    }
+}
+
+/// Epic celebration: an animation (3s), then a splash box over the
+/// still-running animation (3.5s). Single alternate screen session to avoid
+/// flicker. Laid out again whenever the terminal is resized; a key press ends
+/// it early.
+fn render_epic(
+    target: &mut impl Target,
+    state: &State,
+    achievements: &[String],
+    label: Option<&str>,
+    theme: &Theme,
+    style: &EpicStyle,
+) -> io::Result<()> {
+    execute!(
+        target,
+        EnterAlternateScreen,
+        cursor::Hide,
+        Clear(ClearType::All)
+    )?;
+    let _guard = TermGuard { tty: target };
+
+    let mut splash = Splash::new(state, achievements, label, theme, _guard.tty.size());
+    let mut effect = effect_at(
+        style.animation,
+        &splash.scene(state.level, theme),
+        theme,
+        style.seed,
+        0,
+    );
+    for t in 0..INTRO_FRAMES + SPLASH_FRAMES {
+        let size = _guard.tty.size();
+        if size != (splash.cols, splash.rows) {
+            splash = Splash::new(state, achievements, label, theme, size);
+            let scene = splash.scene(state.level, theme);
+            effect = effect_at(style.animation, &scene, theme, style.seed, t);
+        }
+        let (cols, rows) = size;
+        queue!(_guard.tty, Clear(ClearType::All))?;
+        queue_sprites(_guard.tty, &effect.frame(t), cols, rows, theme)?;
+        if t >= INTRO_FRAMES {
+            splash.queue(_guard.tty, theme)?;
+        }
         _guard.tty.flush()?;
-        if start.elapsed() >= Duration::from_millis(3500) {
+        _guard.tty.sleep(Duration::from_millis(FRAME_MS));
+        if _guard.tty.key_pressed() {
             break;
         }
-        thread::sleep(Duration::from_millis(50));
     }
 
     Ok(())
 }
 
+/// How long the level-up screen stays up (5s).
+const LEVEL_UP_FRAMES: u32 = 100;
+
+/// Break `name` into lines of block letters no wider than `width`, wrapping at
+/// spaces. None if a single word doesn't fit.
+fn block_words(name: &str, width: usize) -> Option<Vec<String>> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in name.split_whitespace() {
+        if font::width(word) > width {
+            return None;
+        }
+        match lines.last_mut() {
+            Some(line) if font::width(&format!("{line} {word}")) <= width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    Some(lines)
+}
+
+/// Lines of the level-up screen, top to bottom: a header, the level number
+/// and name in block letters (falling back to plain text when they don't fit
+/// in `cols` x `rows`), old → new title, XP to the next level and any
+/// achievements unlocked along the way. The old title is that of
+/// `previous_level`, or of the level just below when it isn't known.
+pub fn level_up_lines(
+    state: &State,
+    previous_level: Option<u32>,
+    achievements: &[String],
+    theme: &Theme,
+    cols: u16,
+    rows: u16,
+) -> Vec<(String, ThemeColor)> {
+    let (cols, rows) = (cols as usize, rows.saturating_sub(1) as usize);
+    let palette = &theme.palette;
+    let sparkle = &theme.glyphs.sparkle;
+    let fill = theme.glyphs.bar_filled;
+    let level = state.level as usize;
+
+    let previous = previous_level
+        .filter(|&p| p >= 1 && (p as usize) < level)
+        .map_or(level.checked_sub(2), |p| Some(p as usize - 1))
+        .and_then(|i| LEVELS.get(i));
+    let titles = match previous {
+        Some((_, prev)) => theme
+            .text(&format!("{prev} → {}", state.level_name))
+            .into_owned(),
+        None => state.level_name.clone(),
+    };
+    let next = match LEVELS.get(level) {
+        Some(&(threshold, name)) => format!(
+            "{} XP to Lvl {} {name}",
+            threshold.saturating_sub(state.xp),
+            level + 1
+        ),
+        None => theme
+            .text(&format!("Max level {sparkle} {} XP", state.xp))
+            .into_owned(),
+    };
+    let mut tail = vec![
+        (String::new(), palette.toast),
+        (titles, palette.label),
+        (next, palette.toast),
+    ];
+    let ach = achievement_rows(achievements, rows.saturating_sub(20), &theme.glyphs.trophy);
+    if !ach.is_empty() {
+        tail.push((String::new(), palette.toast));
+        tail.extend(
+            ach.iter()
+                .map(|a| (theme.text(a).into_owned(), palette.achievement)),
+        );
+    }
+
+    let mut lines = vec![
+        (
+            theme
+                .text(&format!("{sparkle} LEVEL UP {sparkle}"))
+                .into_owned(),
+            palette.label,
+        ),
+        (String::new(), palette.label),
+    ];
+    let number = state.level.to_string();
+    // Rows left between the header and the tail
+    let room = rows.saturating_sub(lines.len() + tail.len());
+    if font::width(&number) <= cols && font::HEIGHT + 2 <= room {
+        lines.extend(
+            font::render(&number, fill)
+                .into_iter()
+                .map(|r| (r, palette.level)),
+        );
+        let name = block_words(&state.level_name, cols)
+            .map(|words| {
+                words
+                    .iter()
+                    .flat_map(|w| font::render(w, fill))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|block| font::HEIGHT + 1 + block.len() <= room);
+        lines.push((String::new(), palette.level));
+        match name {
+            Some(block) => lines.extend(block.into_iter().map(|r| (r, palette.achievement))),
+            None => lines.push((
+                theme.text(&state.level_name).to_uppercase(),
+                palette.achievement,
+            )),
+        }
+    } else {
+        let plain = format!("Lvl {} {}", state.level, state.level_name);
+        lines.push((theme.text(&plain).into_owned(), palette.level));
+    }
+    lines.extend(tail);
+    lines
+}
+
+/// Level-up screen: the new level and title in block letters over the
+/// configured level-up animation.
+pub fn render_level_up(
+    tty_path: &str,
+    state: &State,
+    previous_level: Option<u32>,
+    achievements: &[String],
+    theme: &Theme,
+    style: &EpicStyle,
+) -> io::Result<()> {
+    let mut tty = Tty::open(tty_path)?;
+    render_level_up_to(&mut tty, state, previous_level, achievements, theme, style)
+}
+
+pub fn render_level_up_to(
+    target: &mut impl Target,
+    state: &State,
+    previous_level: Option<u32>,
+    achievements: &[String],
+    theme: &Theme,
+    style: &EpicStyle,
+) -> io::Result<()> {
+    execute!(
+        target,
+        EnterAlternateScreen,
+        cursor::Hide,
+        Clear(ClearType::All)
+    )?;
+    let _guard = TermGuard { tty: target };
+
+    // The banner and typewriter draw the level themselves
+    let backdrop = match style.animation {
+        Animation::Banner | Animation::Typewriter => Animation::Confetti,
+        other => other,
+    };
+    let mut size = _guard.tty.size();
+    let (mut scene, mut text) = level_up_layout(state, previous_level, achievements, theme, size);
+    let mut effect = effect_at(backdrop, &scene, theme, style.seed, 0);
+    for t in 0..LEVEL_UP_FRAMES {
+        if _guard.tty.size() != size {
+            size = _guard.tty.size();
+            (scene, text) = level_up_layout(state, previous_level, achievements, theme, size);
+            effect = effect_at(backdrop, &scene, theme, style.seed, t);
+        }
+        let (cols, rows) = size;
+        queue!(_guard.tty, Clear(ClearType::All))?;
+        queue_sprites(_guard.tty, &effect.frame(t), cols, rows, theme)?;
+        queue_sprites(_guard.tty, &text, cols, rows, theme)?;
+        queue!(_guard.tty, unpaint(theme))?;
+        _guard.tty.flush()?;
+        _guard.tty.sleep(Duration::from_millis(FRAME_MS));
+        if _guard.tty.key_pressed() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// The level-up screen's scene and centered text for one terminal size.
+fn level_up_layout(
+    state: &State,
+    previous_level: Option<u32>,
+    achievements: &[String],
+    theme: &Theme,
+    (cols, rows): (u16, u16),
+) -> (Scene, Vec<Sprite>) {
+    let lines = level_up_lines(state, previous_level, achievements, theme, cols, rows);
+    let top = (rows.saturating_sub(1) as usize).saturating_sub(lines.len()) / 2;
+    let text = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (line, _))| !line.is_empty())
+        .map(|(i, (line, color))| Sprite {
+            col: ((cols as usize).saturating_sub(display_width(line)) / 2) as u16,
+            row: (top + i) as u16,
+            text: line.clone(),
+            color: *color,
+        })
+        .collect();
+    let scene = Scene {
+        cols,
+        rows,
+        level: state.level,
+        lines,
+    };
+    (scene, text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::screen::{Clock, Frame, Recorder};
     use crate::state::State;
 
     #[test]
@@ -461,7 +930,7 @@ mod tests {
             level_name: "Prompt Whisperer".into(),
             ..Default::default()
         };
-        let (msg, color) = format_toast_msg(&state, None, None);
+        let (msg, color) = format_toast_msg(&state, None, None, &Theme::default());
         assert!(msg.contains("Prompt Whisperer"));
         assert!(msg.contains("250 XP"));
         assert!(msg.contains('█') || msg.contains('░'));
@@ -476,7 +945,7 @@ mod tests {
             level_name: "Vibe Architect".into(),
             ..Default::default()
         };
-        let (msg, color) = format_toast_msg(&state, Some("First Commit"), None);
+        let (msg, color) = format_toast_msg(&state, Some("First Commit"), None, &Theme::default());
         assert!(msg.contains("🏆"));
         assert!(msg.contains("First Commit"));
         assert!(msg.contains("Vibe Architect"));
@@ -492,7 +961,8 @@ mod tests {
             level_name: "Prompt Whisperer".into(),
             ..Default::default()
         };
-        let (msg, color) = format_toast_msg(&state, None, Some("✓ Task Completed"));
+        let (msg, color) =
+            format_toast_msg(&state, None, Some("✓ Task Completed"), &Theme::default());
         assert!(msg.contains("✓ Task Completed"));
         assert!(msg.contains("Prompt Whisperer"));
         assert!(msg.contains("250 XP"));
@@ -615,7 +1085,7 @@ mod tests {
             level_name: "Vibe Initiate".into(),
             ..Default::default()
         };
-        let (msg, color) = format_toast_msg(&state, None, None);
+        let (msg, color) = format_toast_msg(&state, None, None, &Theme::default());
         assert!(msg.contains("⚡"));
         assert!(msg.contains("Vibe Initiate"));
         assert!(msg.contains("50 XP"));
@@ -631,7 +1101,7 @@ mod tests {
             level_name: "Vibe Architect".into(),
             ..Default::default()
         };
-        let (msg, color) = format_toast_msg(&state, None, None);
+        let (msg, color) = format_toast_msg(&state, None, None, &Theme::default());
         assert!(msg.contains("⚡"));
         assert!(msg.contains("Vibe Architect"));
         assert!(msg.contains("750 XP"));
@@ -649,7 +1119,7 @@ mod tests {
             level_name: "Code God".into(),
             ..Default::default()
         };
-        let (msg, color) = format_toast_msg(&state, None, None);
+        let (msg, color) = format_toast_msg(&state, None, None, &Theme::default());
         assert!(msg.contains("⚡"));
         assert!(msg.contains("Code God"));
         assert!(msg.contains("320000000 XP"));
@@ -657,6 +1127,138 @@ mod tests {
         assert_eq!(color, Color::Cyan);
     }
 
+    #[test]
+    fn test_render_slots_are_per_tty() {
+        let medium = CelebrationLevel::Medium;
+        let guard = acquire_render_slot("/dev/pts/test-a", &medium).unwrap();
+        // Another terminal can render while this one is busy
+        let other = acquire_render_slot("/dev/pts/test-b", &medium).unwrap();
+        finish_render(other, &medium);
+        finish_render(guard, &medium);
+
+        // Same terminal, same level: held back by the cooldown
+        assert!(acquire_render_slot("/dev/pts/test-a", &medium).is_none());
+        assert!(cooldown_remaining("/dev/pts/test-a") > Duration::ZERO);
+        // A higher level still gets through
+        let epic = acquire_render_slot("/dev/pts/test-a", &CelebrationLevel::Epic);
+        assert!(epic.is_some());
+        drop(epic);
+        assert_eq!(cooldown_remaining("/dev/pts/test-c"), Duration::ZERO);
+        // Idle terminals are forgotten rather than kept forever
+        drop(render_slot("/dev/pts/test-d"));
+        let slots = RENDER_SLOTS.lock().unwrap();
+        assert!(!slots.contains_key("/dev/pts/test-c"));
+        assert!(slots.contains_key("/dev/pts/test-a"));
+    }
+
+    #[test]
+    fn test_format_toast_msg_uses_theme() {
+        let state = State {
+            xp: 250,
+            level: 2,
+            level_name: "Prompt Whisperer".into(),
+            ..Default::default()
+        };
+        let ascii = Theme::builtin("ascii").unwrap();
+        let (msg, color) = format_toast_msg(&state, None, None, &ascii);
+        assert!(msg.is_ascii(), "{msg}");
+        assert!(msg.starts_with(">> Prompt Whisperer | "));
+        assert_eq!(color, Color::Cyan);
+
+        let (msg, _) = format_toast_msg(&state, Some("Win — w"), None, &ascii);
+        assert!(msg.starts_with("[*] Win — w | "));
+
+        let mono = Theme::builtin("monochrome").unwrap();
+        let (_, color) = format_toast_msg(&state, Some("Win — w"), None, &mono);
+        assert_eq!(color, Color::Reset);
+    }
+
+    #[test]
+    fn test_center_padded_counts_display_width() {
+        assert_eq!(center_padded("ab", 6), "  ab  ");
+        // Emoji take two columns
+        let line = center_padded("🏆 Win", 10);
+        assert_eq!(display_width(&line), 10);
+        assert!(line.starts_with("  🏆"));
+        // CJK too, which the old heuristic missed
+        assert_eq!(display_width(&center_padded("漢字", 8)), 8);
+        // Too long: cut to fit, never wider than the box
+        assert_eq!(center_padded("abcdef", 4), "abcd");
+        assert_eq!(display_width(&center_padded("🏆🏆🏆", 5)), 5);
+        assert_eq!(left_padded("ab", 4), "ab  ");
+    }
+
+    #[test]
+    fn test_no_color_emits_no_escape_codes() {
+        use crossterm::Command;
+        let mut theme = Theme::default();
+        let mut out = String::new();
+        paint(&theme, Color::Red).write_ansi(&mut out).unwrap();
+        assert!(!out.is_empty());
+
+        theme.no_color = true;
+        let mut out = String::new();
+        paint(&theme, Color::Red).write_ansi(&mut out).unwrap();
+        unpaint(&theme).write_ansi(&mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_format_toast_msg_ascii_only_terminal() {
+        let state = State::default();
+        let theme = Theme {
+            ascii_only: true,
+            ..Theme::builtin("ascii").unwrap()
+        };
+        let (msg, _) = format_toast_msg(
+            &state,
+            Some("First Commit — Made it"),
+            Some("📝 Git Commit"),
+            &theme,
+        );
+        assert!(msg.is_ascii(), "{msg}");
+        assert!(msg.starts_with("[*] First Commit - Made it | "));
+    }
+
+    #[test]
+    fn test_toast_messages_cycle_through_achievements() {
+        let state = State::default();
+        let achievements = vec!["A — a".to_string(), "B — b".to_string()];
+        let msgs = toast_messages(&state, &achievements, None, &Theme::default());
+        assert_eq!(msgs.len(), 2);
+        assert!(msgs[0].0.contains("A — a (1/2)"));
+        assert!(msgs[1].0.contains("B — b (2/2)"));
+        // A single achievement is shown without a counter
+        let msgs = toast_messages(&state, &achievements[..1], None, &Theme::default());
+        assert!(msgs[0].0.contains("A — a │"));
+        assert_eq!(
+            toast_messages(&state, &[], None, &Theme::default()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_toast_duration_scales_with_achievements() {
+        assert_eq!(toast_duration_ms(0), 1500);
+        assert_eq!(toast_duration_ms(1), 2500);
+        assert_eq!(toast_duration_ms(3), 4500);
+        assert_eq!(toast_duration_ms(10), 6000);
+    }
+
+    #[test]
+    fn test_achievement_rows_summarise_overflow() {
+        let achievements: Vec<String> = (1..=5).map(|i| format!("A{i}")).collect();
+        assert_eq!(achievement_rows(&achievements, 10, "🏆").len(), 5);
+        let rows = achievement_rows(&achievements, 3, "🏆");
+        assert_eq!(rows, vec!["🏆 A1", "🏆 A2", "🏆 +3 more achievements"]);
+        // Tiny terminals still get one row
+        assert_eq!(
+            achievement_rows(&achievements, 0, "🏆"),
+            vec!["🏆 +5 more achievements"]
+        );
+        assert!(achievement_rows(&[], 3, "🏆").is_empty());
+    }
+
     #[test]
     fn test_format_toast_msg_mini_no_achievement() {
         // Mini celebration (None achievement) never shows trophy
@@ -666,7 +1268,7 @@ mod tests {
             level_name: "Prompt Whisperer".into(),
             ..Default::default()
         };
-        let (msg, _) = format_toast_msg(&state, None, None);
+        let (msg, _) = format_toast_msg(&state, None, None, &Theme::default());
         // Should not contain trophy emoji
         assert!(!msg.contains("🏆"));
         // Should contain lightning bolt
@@ -681,7 +1283,7 @@ mod tests {
             level_name: "Prompt Whisperer".into(),
             ..Default::default()
         };
-        let (msg, _) = format_toast_msg(&state, None, None);
+        let (msg, _) = format_toast_msg(&state, None, None, &Theme::default());
         // Mini (no label): "⚡ Level │ bar │ XP" = 2 delimiters
         let delimiter_count = msg.matches('│').count();
         assert_eq!(
@@ -690,4 +1292,268 @@ mod tests {
             delimiter_count
         );
     }
+
+    fn flow_state_master() -> State {
+        State {
+            xp: 1_600,
+            level: 4,
+            level_name: "Flow State Master".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_level_up_lines_block_letters() {
+        let lines = level_up_lines(&flow_state_master(), None, &[], &Theme::default(), 80, 40);
+        let text: Vec<&str> = lines.iter().map(|(l, _)| l.as_str()).collect();
+        assert_eq!(text[0], "✦ LEVEL UP ✦");
+        assert_eq!(text[2..7], font::render("4", '█'));
+        // "Flow State" fits on one 80-column line, "Master" wraps
+        assert_eq!(text[8..13], font::render("Flow State", '█'));
+        assert_eq!(text[13..18], font::render("Master", '█'));
+        assert!(text.contains(&"Vibe Architect → Flow State Master"));
+        assert!(text.contains(&"3400 XP to Lvl 5 Claude Sensei"));
+    }
+
+    #[test]
+    fn test_level_up_lines_show_real_previous_title() {
+        let state = flow_state_master();
+        let title = |previous| {
+            level_up_lines(&state, previous, &[], &Theme::default(), 80, 40)
+                .into_iter()
+                .map(|(l, _)| l)
+                .find(|l| l.contains(" → "))
+        };
+        // Jumped from level 2 straight to 4
+        let from_2 = format!("{} → Flow State Master", LEVELS[1].1);
+        assert_eq!(title(Some(2)), Some(from_2));
+        // Nonsense falls back to the level below
+        let from_3 = Some("Vibe Architect → Flow State Master".to_string());
+        assert_eq!(title(Some(4)), from_3);
+        assert_eq!(title(None), from_3);
+    }
+
+    #[test]
+    fn test_level_up_lines_fall_back_to_plain_text() {
+        let state = flow_state_master();
+        let achievements = vec!["Zen — Reached flow".to_string()];
+        // Name too tall for 24 rows once wrapped: number stays big, name plain
+        let lines = level_up_lines(&state, None, &achievements, &Theme::default(), 40, 24);
+        assert!(lines.iter().any(|(l, _)| l == "FLOW STATE MASTER"));
+        assert!(lines.iter().any(|(l, _)| l == "🏆 Zen — Reached flow"));
+        // Too small for any block letters
+        let lines = level_up_lines(&state, None, &[], &Theme::default(), 40, 8);
+        assert!(lines.iter().any(|(l, _)| l == "Lvl 4 Flow State Master"));
+        assert!(lines.len() <= 7);
+    }
+
+    #[test]
+    fn test_level_up_lines_ascii() {
+        let theme = Theme {
+            ascii_only: true,
+            ..Theme::builtin("ascii").unwrap()
+        };
+        let lines = level_up_lines(&flow_state_master(), None, &[], &theme, 80, 40);
+        assert!(lines.iter().all(|(l, _)| l.is_ascii()));
+        assert!(
+            lines
+                .iter()
+                .any(|(l, _)| l == "Vibe Architect -> Flow State Master")
+        );
+        // Block letters keep their shape
+        assert_eq!(lines[2].0, font::render("4", theme.glyphs.bar_filled)[0]);
+    }
+
+    fn record(
+        cols: u16,
+        rows: u16,
+        draw: impl FnOnce(&mut Recorder) -> io::Result<()>,
+    ) -> Recorder {
+        let mut rec = Recorder::new(cols, rows);
+        draw(&mut rec).unwrap();
+        rec
+    }
+
+    /// Frames as text, each headed by its time.
+    fn frames_text(frames: &[&Frame]) -> String {
+        frames
+            .iter()
+            .map(|f| format!("--- {}ms ---\n{}\n", f.at.as_millis(), f.lines.join("\n")))
+            .collect()
+    }
+
+    /// The last frame drawn at or before `ms` on the alternate screen.
+    fn frame_at(rec: &Recorder, ms: u64) -> &Frame {
+        rec.frames()
+            .iter()
+            .rev()
+            .find(|f| f.alternate && f.at <= Duration::from_millis(ms))
+            .unwrap()
+    }
+
+    fn vibe_architect() -> State {
+        State {
+            xp: 1325,
+            level: 3,
+            level_name: "Vibe Architect".into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_toast() {
+        let state = vibe_architect();
+        let theme = Theme::default();
+        for (cols, rows) in [(80, 24), (40, 10)] {
+            let rec = record(cols, rows, |r| {
+                render_toast(r, &state, &[], Some("✓ Task Completed"), &theme)
+            });
+            let name = format!("toast_{cols}x{rows}");
+            crate::screen::assert_snapshot(&name, &frames_text(&rec.distinct_frames()));
+            assert!(!rec.screen().alternate, "left the alternate screen");
+        }
+    }
+
+    #[test]
+    fn test_snapshot_toast_cycles_achievements() {
+        let achievements = vec![
+            "First Commit — Made your first git commit".to_string(),
+            "Shipped It — First git push".to_string(),
+        ];
+        let rec = record(60, 12, |r| {
+            let theme = Theme::default();
+            render_toast(
+                r,
+                &vibe_architect(),
+                &achievements,
+                Some("🚀 Git Push"),
+                &theme,
+            )
+        });
+        let last = rec.frames().last().unwrap();
+        assert_eq!(last.at, Duration::from_millis(toast_duration_ms(2)));
+        crate::screen::assert_snapshot(
+            "toast_achievements_60x12",
+            &frames_text(&rec.distinct_frames()),
+        );
+    }
+
+    #[test]
+    fn test_snapshot_progress_bar() {
+        for (cols, rows) in [(80, 24), (30, 5)] {
+            let rec = record(cols, rows, |r| {
+                render_progress_bar_to(r, &vibe_architect(), &Theme::default())
+            });
+            let name = format!("progress_bar_{cols}x{rows}");
+            crate::screen::assert_snapshot(&name, &frames_text(&rec.distinct_frames()));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_epic() {
+        let achievements = vec!["Shipped It — First git push".to_string()];
+        let cases = [
+            (80, 24, Animation::Confetti),
+            (40, 12, Animation::Fireworks),
+            (60, 20, Animation::Typewriter),
+        ];
+        for (cols, rows, animation) in cases {
+            let style = EpicStyle {
+                animation,
+                seed: 42,
+            };
+            let rec = record(cols, rows, |r| {
+                let theme = Theme::default();
+                let label = Some("🚀 Git Push");
+                render_epic(r, &vibe_architect(), &achievements, label, &theme, &style)
+            });
+            let end = FRAME_MS * u64::from(INTRO_FRAMES + SPLASH_FRAMES);
+            let keyframes: Vec<&Frame> = [0, end / 4, end / 2, end - FRAME_MS]
+                .iter()
+                .map(|&ms| frame_at(&rec, ms))
+                .collect();
+            let name = format!("epic_{}_{cols}x{rows}", animation.name());
+            crate::screen::assert_snapshot(&name, &frames_text(&keyframes));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_level_up() {
+        let state = State {
+            xp: 1_600,
+            level: 4,
+            level_name: "Flow State Master".into(),
+            ..Default::default()
+        };
+        let style = EpicStyle {
+            animation: Animation::Starfield,
+            seed: 7,
+        };
+        for (cols, rows) in [(80, 30), (40, 16)] {
+            let rec = record(cols, rows, |r| {
+                render_level_up_to(r, &state, None, &[], &Theme::default(), &style)
+            });
+            let last = frame_at(&rec, FRAME_MS * u64::from(LEVEL_UP_FRAMES));
+            let name = format!("level_up_{cols}x{rows}");
+            crate::screen::assert_snapshot(&name, &frames_text(&[last]));
+        }
+    }
+
+    #[test]
+    fn test_epic_reflows_after_resize() {
+        let style = EpicStyle {
+            animation: Animation::Confetti,
+            seed: 42,
+        };
+        let draw = |r: &mut Recorder| {
+            let theme = Theme::default();
+            render_epic(r, &vibe_architect(), &[], None, &theme, &style)
+        };
+        let resized = record(80, 24, |r| {
+            r.resize_at(Duration::from_millis(4000), 40, 12);
+            draw(r)
+        });
+        let fresh = record(40, 12, draw);
+        // Carries on exactly as if it had started at the new size
+        let last = resized.frames().len() - 2;
+        assert_eq!(resized.frames()[last].lines, fresh.frames()[last].lines);
+        let top = fresh.frames()[last]
+            .lines
+            .iter()
+            .find(|l| l.starts_with('╔'))
+            .unwrap();
+        assert_eq!(display_width(top), 40);
+    }
+
+    #[test]
+    fn test_key_press_ends_celebration_early() {
+        let theme = Theme::default();
+        let style = EpicStyle {
+            animation: Animation::Fireworks,
+            seed: 1,
+        };
+        let press = |r: &mut Recorder| r.press_key_at(Duration::from_millis(1000));
+        let epic = record(60, 20, |r| {
+            press(r);
+            render_epic(r, &vibe_architect(), &[], None, &theme, &style)
+        });
+        let level_up = record(60, 20, |r| {
+            press(r);
+            render_level_up_to(r, &vibe_architect(), None, &[], &theme, &style)
+        });
+        let toast = record(60, 20, |r| {
+            r.press_key_at(Duration::from_millis(500));
+            render_toast(
+                r,
+                &vibe_architect(),
+                &vec!["a".to_string(); 4],
+                None,
+                &theme,
+            )
+        });
+        for (rec, at) in [(epic, 1000), (level_up, 1000), (toast, 500)] {
+            assert_eq!(rec.elapsed(), Duration::from_millis(at));
+            assert!(!rec.screen().alternate, "left the alternate screen");
+        }
+    }
 }
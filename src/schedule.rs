@@ -0,0 +1,426 @@
+use crate::celebration::{Celebration, CelebrationLevel};
+use crate::config::{QuietWindow, ScheduleConfig};
+use crate::event::EventKind;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Manual do-not-disturb toggle set by `cwinner dnd`, stored next to `state.json`.
+/// The file only exists while DND is switched on.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DndToggle {
+    /// When DND switches itself off again; `None` means until `cwinner dnd off`.
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl DndToggle {
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|d| d.join("cwinner").join("dnd.json"))
+    }
+
+    pub fn load_from(path: &Path) -> Option<Self> {
+        let s = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&s).ok()
+    }
+
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load() -> Option<Self> {
+        Self::path().and_then(|p| Self::load_from(&p))
+    }
+
+    /// Switch manual DND off by removing the toggle file.
+    pub fn clear() -> anyhow::Result<()> {
+        if let Some(path) = Self::path() {
+            match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.until.is_none_or(|until| now < until)
+    }
+}
+
+impl QuietWindow {
+    /// Whether the local date-time `at` falls inside this window.
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let time = at.time();
+        let day = at.weekday();
+        let starts_on = |d| self.days.is_empty() || self.days.contains(&d);
+        if self.start == self.end {
+            starts_on(day)
+        } else if self.start < self.end {
+            starts_on(day) && time >= self.start && time < self.end
+        } else {
+            // Overnight: the tail after midnight belongs to the previous day's window
+            (starts_on(day) && time >= self.start) || (starts_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// Whether the local date-time `at` is inside any configured quiet window.
+pub fn in_quiet_hours(cfg: &ScheduleConfig, at: NaiveDateTime) -> bool {
+    cfg.quiet_hours.iter().any(|w| w.contains(at))
+}
+
+/// Whether do-not-disturb is in effect, either from the manual toggle or quiet hours.
+pub fn dnd_active(cfg: &ScheduleConfig, toggle: Option<&DndToggle>, now: DateTime<Utc>) -> bool {
+    toggle.is_some_and(|t| t.is_active(now))
+        || in_quiet_hours(cfg, now.with_timezone(&Local).naive_local())
+}
+
+/// Parse a short duration like `30m`, `2h`, `1h30m`, `45s` or `1d`. None
+/// if it is too long to represent.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for c in s.trim().chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let n: i64 = digits.parse().ok()?;
+        digits.clear();
+        let part = match c {
+            's' => Duration::try_seconds(n),
+            'm' => Duration::try_minutes(n),
+            'h' => Duration::try_hours(n),
+            'd' => Duration::try_days(n),
+            _ => return None,
+        };
+        total = total.checked_add(&part?)?;
+    }
+    // Trailing digits without a unit are ambiguous; an empty string is no duration
+    if !digits.is_empty() || total.is_zero() {
+        return None;
+    }
+    Some(total)
+}
+
+/// What do-not-disturb held back, summarized once it ends. Stored next to
+/// the toggle so a daemon restart doesn't lose it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DndDigest {
+    /// Celebrations whose sound was muted or visual downgraded.
+    pub celebrations: u32,
+    /// XP earned while DND was on (awarded as usual).
+    pub xp: u32,
+    pub level_ups: u32,
+    /// Achievements unlocked while DND was on, as "Name — description".
+    pub achievements: Vec<String>,
+    /// Terminal of the last held-back celebration, where the summary goes.
+    pub tty_path: Option<String>,
+}
+
+impl DndDigest {
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|d| d.join("cwinner").join("dnd-digest.json"))
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the digest to `path`, or remove the file once there is nothing in it.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if *self == Self::default() {
+            match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => return Ok(()),
+            }
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .map(|p| Self::load_from(&p))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        match Self::path() {
+            Some(path) => self.save_to(&path),
+            None => Ok(()),
+        }
+    }
+
+    /// Nothing worth summarizing (XP alone never interrupted anyone).
+    pub fn is_empty(&self) -> bool {
+        self.celebrations == 0 && self.level_ups == 0 && self.achievements.is_empty()
+    }
+
+    /// Apply do-not-disturb to one celebration for `tty_path`, downgrading it
+    /// in place and recording whatever gets held back. Returns whether to
+    /// mute its sounds.
+    pub fn suppress(
+        &mut self,
+        cfg: &ScheduleConfig,
+        c: &mut Celebration,
+        xp: u32,
+        tty_path: &str,
+    ) -> bool {
+        self.xp = self.xp.saturating_add(xp);
+        let has_sound = c.level >= CelebrationLevel::Medium || c.leveled_up;
+        let mute = cfg.mute_audio && has_sound;
+        let downgrade = cfg.downgrade_visuals && c.level > CelebrationLevel::Mini;
+        if mute || downgrade {
+            self.celebrations += 1;
+            if c.leveled_up {
+                self.level_ups += 1;
+            }
+            self.achievements.extend(c.achievements.iter().cloned());
+            self.tty_path = Some(tty_path.to_string());
+        }
+        if downgrade {
+            c.level = CelebrationLevel::Mini;
+        }
+        mute
+    }
+
+    /// The end-of-DND summary as its own celebration: a toast carrying
+    /// every held-back achievement.
+    pub fn into_celebration(self) -> Celebration {
+        Celebration {
+            level: CelebrationLevel::Medium,
+            event: EventKind::UserDefined,
+            label: Some(self.summary()),
+            achievements: self.achievements,
+            streak_milestone: None,
+            leveled_up: false,
+            previous_level: None,
+        }
+    }
+
+    /// One-line summary for the toast shown when DND ends.
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!(
+            "{} celebration{}",
+            self.celebrations,
+            if self.celebrations == 1 { "" } else { "s" }
+        )];
+        parts.push(format!("+{} XP", self.xp));
+        if self.level_ups > 0 {
+            parts.push(format!(
+                "{} level-up{}",
+                self.level_ups,
+                if self.level_ups == 1 { "" } else { "s" }
+            ));
+        }
+        format!("🔕 While DND: {}", parts.join(" · "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime, TimeZone, Weekday};
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 0)
+            .unwrap()
+    }
+
+    fn window(start: (u32, u32), end: (u32, u32), days: Vec<Weekday>) -> QuietWindow {
+        QuietWindow {
+            start: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+            days,
+        }
+    }
+
+    #[test]
+    fn test_daytime_window() {
+        let w = window((12, 0), (13, 0), vec![]);
+        assert!(w.contains(at(2026, 3, 4, 12, 30)));
+        assert!(!w.contains(at(2026, 3, 4, 13, 0)));
+        assert!(!w.contains(at(2026, 3, 4, 11, 59)));
+    }
+
+    #[test]
+    fn test_overnight_window_respects_start_day() {
+        // 2026-03-06 is a Friday
+        let w = window((22, 0), (8, 0), vec![Weekday::Fri]);
+        assert!(w.contains(at(2026, 3, 6, 23, 0))); // Friday night
+        assert!(w.contains(at(2026, 3, 7, 7, 59))); // Saturday morning, Friday's window
+        assert!(!w.contains(at(2026, 3, 7, 23, 0))); // Saturday night
+        assert!(!w.contains(at(2026, 3, 6, 7, 0))); // Friday morning belongs to Thursday
+    }
+
+    #[test]
+    fn test_whole_day_window() {
+        let w = window((0, 0), (0, 0), vec![Weekday::Sat, Weekday::Sun]);
+        assert!(w.contains(at(2026, 3, 7, 15, 0))); // Saturday
+        assert!(w.contains(at(2026, 3, 8, 9, 0))); // Sunday
+        assert!(!w.contains(at(2026, 3, 9, 9, 0))); // Monday
+    }
+
+    #[test]
+    fn test_manual_toggle_expires() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 4, 12, 0, 0).unwrap();
+        let indefinite = DndToggle { until: None };
+        assert!(indefinite.is_active(now));
+        let timed = DndToggle {
+            until: Some(now + Duration::minutes(30)),
+        };
+        assert!(timed.is_active(now));
+        assert!(!timed.is_active(now + Duration::minutes(31)));
+        let cfg = ScheduleConfig::default();
+        assert!(dnd_active(&cfg, Some(&timed), now));
+        assert!(!dnd_active(&cfg, None, now));
+    }
+
+    #[test]
+    fn test_toggle_persist_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dnd.json");
+        let toggle = DndToggle {
+            until: Some(Utc.with_ymd_and_hms(2026, 3, 4, 12, 30, 0).unwrap()),
+        };
+        toggle.save_to(&path).unwrap();
+        assert_eq!(DndToggle::load_from(&path), Some(toggle));
+        assert_eq!(DndToggle::load_from(&dir.path().join("missing.json")), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m"), Some(Duration::minutes(30)));
+        assert_eq!(parse_duration("2h"), Some(Duration::hours(2)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("1d"), Some(Duration::days(1)));
+        assert_eq!(parse_duration("30"), None);
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration(""), None);
+        // Too long to represent, rather than a panic
+        assert_eq!(parse_duration("99999999999999d"), None);
+        assert_eq!(parse_duration("99999999999999999999s"), None);
+        let d = parse_duration("100000000d1d").unwrap();
+        assert_eq!(Utc::now().checked_add_signed(d), None);
+    }
+
+    #[test]
+    fn test_digest_summary() {
+        let mut digest = DndDigest {
+            xp: 40,
+            ..Default::default()
+        };
+        assert!(digest.is_empty(), "XP alone is not worth a summary");
+        digest.celebrations = 3;
+        digest.level_ups = 1;
+        assert!(!digest.is_empty());
+        assert_eq!(
+            digest.summary(),
+            "🔕 While DND: 3 celebrations · +40 XP · 1 level-up"
+        );
+    }
+
+    fn celebration(level: CelebrationLevel) -> Celebration {
+        Celebration {
+            level,
+            event: crate::event::EventKind::PostToolUse,
+            achievements: vec![],
+            label: None,
+            streak_milestone: None,
+            leveled_up: false,
+            previous_level: None,
+        }
+    }
+
+    #[test]
+    fn test_suppress_downgrades_and_mutes() {
+        let cfg = ScheduleConfig::default();
+        let mut digest = DndDigest::default();
+        let mut c = Celebration {
+            leveled_up: true,
+            achievements: vec!["Shipped It — First git push".into()],
+            ..celebration(CelebrationLevel::Epic)
+        };
+        let mute = digest.suppress(&cfg, &mut c, 100, "/dev/pts/3");
+        assert_eq!(c.level, CelebrationLevel::Mini);
+        assert!(mute);
+        assert_eq!(digest.celebrations, 1);
+        assert_eq!(digest.level_ups, 1);
+        assert_eq!(digest.achievements, vec!["Shipped It — First git push"]);
+        assert_eq!(digest.xp, 100);
+        assert_eq!(digest.tty_path.as_deref(), Some("/dev/pts/3"));
+    }
+
+    #[test]
+    fn test_suppress_routine_event_only_counts_xp() {
+        let cfg = ScheduleConfig::default();
+        let mut digest = DndDigest::default();
+        let mut c = celebration(CelebrationLevel::Mini);
+        let mute = digest.suppress(&cfg, &mut c, 5, "/dev/pts/3");
+        assert_eq!(c.level, CelebrationLevel::Mini);
+        assert!(!mute);
+        assert!(digest.is_empty());
+        assert_eq!(digest.xp, 5);
+    }
+
+    #[test]
+    fn test_suppress_audio_only() {
+        let cfg = ScheduleConfig {
+            downgrade_visuals: false,
+            ..Default::default()
+        };
+        let mut digest = DndDigest::default();
+        let mut c = celebration(CelebrationLevel::Medium);
+        let mute = digest.suppress(&cfg, &mut c, 25, "/dev/pts/3");
+        assert_eq!(c.level, CelebrationLevel::Medium);
+        assert!(mute);
+        assert_eq!(digest.celebrations, 1);
+    }
+
+    #[test]
+    fn test_into_celebration_carries_held_back_achievements() {
+        let digest = DndDigest {
+            celebrations: 2,
+            xp: 50,
+            level_ups: 0,
+            achievements: vec!["A — held".into()],
+            tty_path: None,
+        };
+        let c = digest.into_celebration();
+        assert_eq!(c.level, CelebrationLevel::Medium);
+        assert_eq!(c.achievements, vec!["A — held"]);
+        assert!(c.label.unwrap().starts_with("🔕 While DND: 2 celebrations"));
+    }
+
+    #[test]
+    fn test_digest_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dnd-digest.json");
+        assert_eq!(DndDigest::load_from(&path), DndDigest::default());
+        let digest = DndDigest {
+            celebrations: 1,
+            xp: 25,
+            tty_path: Some("/dev/pts/3".into()),
+            ..Default::default()
+        };
+        digest.save_to(&path).unwrap();
+        assert_eq!(DndDigest::load_from(&path), digest);
+        // Flushed: nothing left on disk
+        DndDigest::default().save_to(&path).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,695 @@
+//! Where celebrations are drawn: a real terminal device, or a `Recorder` that
+//! interprets the output into a virtual screen and captures every frame, for
+//! snapshot tests and exports.
+
+use crate::config::SkipPolicy;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
+
+/// Time as seen by a render loop. Terminals really sleep; recordings only
+/// advance a counter, so they run instantly and reproducibly.
+pub trait Clock {
+    fn sleep(&mut self, d: Duration);
+    /// Time since the target was opened.
+    fn elapsed(&self) -> Duration;
+}
+
+/// Something a celebration can be rendered to.
+pub trait Target: Write + Clock {
+    /// Size in (columns, rows). May change between frames.
+    fn size(&self) -> (u16, u16);
+    /// The user pressed a key to dismiss the celebration.
+    fn key_pressed(&mut self) -> bool;
+}
+
+/// A terminal device, e.g. `/dev/pts/3`.
+pub struct Tty {
+    file: File,
+    opened: Instant,
+    skip: SkipPolicy,
+    pressed: bool,
+}
+
+impl Tty {
+    pub fn open(path: &str) -> io::Result<Self> {
+        Self::open_with(path, SkipPolicy::Off)
+    }
+
+    /// Open `path`, watching its input for a key press while sleeping between
+    /// frames unless `skip` is off.
+    pub fn open_with(path: &str, skip: SkipPolicy) -> io::Result<Self> {
+        let read = skip != SkipPolicy::Off;
+        let (file, skip) = match OpenOptions::new().read(read).write(true).open(path) {
+            Ok(file) => (file, skip),
+            Err(_) if read => (OpenOptions::new().write(true).open(path)?, SkipPolicy::Off),
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            file,
+            opened: Instant::now(),
+            skip,
+            pressed: false,
+        })
+    }
+
+    /// Wait up to `d` for input. Polling wakes every reader of the terminal,
+    /// so this sees a key even if Claude Code reads it straight after.
+    fn wait_for_input(&self, d: Duration) -> bool {
+        use std::os::unix::io::AsRawFd;
+        let mut fds = libc::pollfd {
+            fd: self.file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ms = d.as_millis().min(i32::MAX as u128) as i32;
+        let ret = unsafe { libc::poll(&mut fds, 1, ms) };
+        ret > 0 && fds.revents & libc::POLLIN != 0
+    }
+
+    /// Read whatever input is waiting, without blocking.
+    fn discard_input(&mut self) {
+        use std::io::Read;
+        use std::os::unix::io::AsRawFd;
+        let mut pending: libc::c_int = 0;
+        let ret = unsafe { libc::ioctl(self.file.as_raw_fd(), libc::FIONREAD, &mut pending) };
+        if ret == 0 && pending > 0 {
+            let mut buf = vec![0; pending as usize];
+            let _ = self.file.read(&mut buf);
+        }
+    }
+}
+
+impl Write for Tty {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Clock for Tty {
+    fn sleep(&mut self, d: Duration) {
+        if self.skip == SkipPolicy::Off || self.pressed {
+            std::thread::sleep(d);
+            return;
+        }
+        let deadline = Instant::now() + d;
+        if self.wait_for_input(d) {
+            self.pressed = true;
+            if self.skip == SkipPolicy::Consume {
+                self.discard_input();
+            }
+            return;
+        }
+        // Poll can return early on a signal
+        std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.opened.elapsed()
+    }
+}
+
+impl Target for Tty {
+    fn size(&self) -> (u16, u16) {
+        use std::os::unix::io::AsRawFd;
+        let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::ioctl(self.file.as_raw_fd(), libc::TIOCGWINSZ, &mut ws) };
+        if ret == 0 && ws.ws_col > 0 && ws.ws_row > 0 {
+            (ws.ws_col, ws.ws_row)
+        } else {
+            (80, 24)
+        }
+    }
+
+    fn key_pressed(&mut self) -> bool {
+        self.pressed
+    }
+}
+
+/// One character cell. The right half of a wide character is an empty cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub text: String,
+    /// Foreground color as its SGR parameters (e.g. `38;5;11`), if any.
+    pub fg: Option<String>,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            text: " ".into(),
+            fg: None,
+        }
+    }
+}
+
+/// Escape sequence being parsed.
+#[derive(Debug, Clone, PartialEq)]
+enum Escape {
+    None,
+    Esc,
+    Csi(String),
+    /// Operating system command; true after an ESC that may start the `ESC \`
+    /// terminator.
+    Osc(String, bool),
+}
+
+/// An in-memory terminal that understands the escape sequences crossterm
+/// emits: cursor movement, clearing, colors, the alternate screen and the
+/// window title. Text past the right edge is dropped rather than wrapped.
+#[derive(Debug, Clone)]
+pub struct VirtualScreen {
+    cols: u16,
+    rows: u16,
+    cells: Vec<Vec<Cell>>,
+    cursor: (u16, u16),
+    fg: Option<String>,
+    /// Showing the alternate screen.
+    pub alternate: bool,
+    /// Window title, as set by `OSC 0`/`OSC 2`.
+    pub title: String,
+    /// Titles saved with `CSI 22 t`.
+    titles: Vec<String>,
+    escape: Escape,
+    /// Trailing bytes of an incomplete UTF-8 sequence.
+    partial: Vec<u8>,
+}
+
+impl VirtualScreen {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self {
+            cols,
+            rows,
+            cells: vec![vec![Cell::default(); cols as usize]; rows as usize],
+            cursor: (0, 0),
+            fg: None,
+            alternate: false,
+            title: String::new(),
+            titles: Vec::new(),
+            escape: Escape::None,
+            partial: Vec::new(),
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols, self.rows)
+    }
+
+    /// Change size, cropping or padding the contents. Unlike real terminals,
+    /// nothing is reflowed.
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.cells.resize_with(rows as usize, Vec::new);
+        for row in &mut self.cells {
+            row.resize_with(cols as usize, Cell::default);
+        }
+        self.cols = cols;
+        self.rows = rows;
+        self.cursor = (
+            self.cursor.0.min(cols.saturating_sub(1)),
+            self.cursor.1.min(rows.saturating_sub(1)),
+        );
+    }
+
+    pub fn cell(&self, col: u16, row: u16) -> Option<&Cell> {
+        self.cells.get(row as usize)?.get(col as usize)
+    }
+
+    /// The screen as text, one string per row with trailing spaces removed.
+    pub fn lines(&self) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|row| {
+                let line: String = row.iter().map(|c| c.text.as_str()).collect();
+                line.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        let mut buf = std::mem::take(&mut self.partial);
+        buf.extend_from_slice(bytes);
+        let mut rest = buf.as_slice();
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(s) => {
+                    s.chars().for_each(|c| self.input(c));
+                    return;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    std::str::from_utf8(valid)
+                        .unwrap_or_default()
+                        .chars()
+                        .for_each(|c| self.input(c));
+                    match e.error_len() {
+                        // Invalid bytes are skipped
+                        Some(n) => rest = &after[n..],
+                        None => {
+                            self.partial = after.to_vec();
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn input(&mut self, c: char) {
+        match std::mem::replace(&mut self.escape, Escape::None) {
+            Escape::None => match c {
+                '\x1b' => self.escape = Escape::Esc,
+                '\r' => self.cursor.0 = 0,
+                '\n' => self.cursor.1 = (self.cursor.1 + 1).min(self.rows.saturating_sub(1)),
+                c if c.is_control() => {}
+                c => self.print(c),
+            },
+            Escape::Esc => match c {
+                '[' => self.escape = Escape::Csi(String::new()),
+                ']' => self.escape = Escape::Osc(String::new(), false),
+                _ => {}
+            },
+            Escape::Csi(mut params) => {
+                if ('\x40'..='\x7e').contains(&c) {
+                    self.csi(&params, c);
+                } else {
+                    params.push(c);
+                    self.escape = Escape::Csi(params);
+                }
+            }
+            Escape::Osc(mut data, after_esc) => match c {
+                '\x07' => self.osc(&data),
+                '\\' if after_esc => self.osc(&data),
+                '\x1b' => self.escape = Escape::Osc(data, true),
+                c => {
+                    data.push(c);
+                    self.escape = Escape::Osc(data, false);
+                }
+            },
+        }
+    }
+
+    fn csi(&mut self, params: &str, action: char) {
+        let nums: Vec<u16> = params
+            .trim_start_matches('?')
+            .split(';')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect();
+        let arg = |i: usize| nums.get(i).copied().filter(|&n| n > 0).unwrap_or(1);
+        let (col, row) = self.cursor;
+        let last_col = self.cols.saturating_sub(1);
+        let last_row = self.rows.saturating_sub(1);
+        match action {
+            'H' | 'f' => self.cursor = ((arg(1) - 1).min(last_col), (arg(0) - 1).min(last_row)),
+            'A' => self.cursor.1 = row.saturating_sub(arg(0)),
+            'B' => self.cursor.1 = (row + arg(0)).min(last_row),
+            'C' => self.cursor.0 = (col + arg(0)).min(last_col),
+            'D' => self.cursor.0 = col.saturating_sub(arg(0)),
+            'G' => self.cursor.0 = (arg(0) - 1).min(last_col),
+            'J' => match nums[0] {
+                0 => {
+                    self.erase_line_from(col, row);
+                    (row as usize + 1..self.rows as usize).for_each(|r| self.blank_row(r));
+                }
+                _ => self.clear(),
+            },
+            'K' => match nums[0] {
+                0 => self.erase_line_from(col, row),
+                _ => self.blank_row(row as usize),
+            },
+            'm' => self.sgr(params),
+            't' => match nums[0] {
+                22 => self.titles.push(self.title.clone()),
+                23 => {
+                    if let Some(title) = self.titles.pop() {
+                        self.title = title;
+                    }
+                }
+                _ => {}
+            },
+            'h' | 'l' if params == "?1049" => {
+                self.alternate = action == 'h';
+                // Both screens start out blank here
+                self.clear();
+            }
+            _ => {}
+        }
+    }
+
+    /// Track the window title; other commands are ignored.
+    fn osc(&mut self, data: &str) {
+        if let Some(title) = data.strip_prefix("0;").or_else(|| data.strip_prefix("2;")) {
+            self.title = title.to_string();
+        }
+    }
+
+    /// Track the foreground color; other attributes are ignored.
+    fn sgr(&mut self, params: &str) {
+        let parts: Vec<&str> = params.split(';').collect();
+        let mut i = 0;
+        while i < parts.len() {
+            match parts[i] {
+                "" | "0" | "39" => self.fg = None,
+                "38" => {
+                    let len = if parts.get(i + 1) == Some(&"2") { 5 } else { 3 };
+                    let end = (i + len).min(parts.len());
+                    self.fg = Some(parts[i..end].join(";"));
+                    i = end;
+                    continue;
+                }
+                p if matches!(p.parse::<u8>(), Ok(30..=37 | 90..=97)) => {
+                    self.fg = Some(p.to_string());
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    fn clear(&mut self) {
+        (0..self.rows as usize).for_each(|r| self.blank_row(r));
+    }
+
+    fn blank_row(&mut self, row: usize) {
+        if let Some(cells) = self.cells.get_mut(row) {
+            cells.fill(Cell::default());
+        }
+    }
+
+    fn erase_line_from(&mut self, col: u16, row: u16) {
+        if let Some(cells) = self.cells.get_mut(row as usize) {
+            let from = (col as usize).min(cells.len());
+            cells[from..].fill(Cell::default());
+        }
+    }
+
+    fn print(&mut self, c: char) {
+        let (col, row) = (self.cursor.0 as usize, self.cursor.1 as usize);
+        let width = UnicodeWidthChar::width(c).unwrap_or(0);
+        if row >= self.rows as usize {
+            return;
+        }
+        if width == 0 {
+            // Combining marks and variation selectors join the previous
+            // character, skipping the right half of a wide one
+            let cells = &mut self.cells[row];
+            let prev = (0..col.min(cells.len()))
+                .rev()
+                .find(|&i| !cells[i].text.is_empty());
+            if let Some(i) = prev {
+                cells[i].text.push(c);
+            }
+            return;
+        }
+        if col + width > self.cols as usize {
+            return;
+        }
+        self.unsplit(row, col);
+        self.unsplit(row, col + width - 1);
+        self.cells[row][col] = Cell {
+            text: c.to_string(),
+            fg: self.fg.clone(),
+        };
+        if width == 2 {
+            self.cells[row][col + 1] = Cell {
+                text: String::new(),
+                fg: self.fg.clone(),
+            };
+        }
+        self.cursor.0 = (col + width) as u16;
+    }
+
+    /// Blank out the wide character `col` is part of, before it's overwritten.
+    fn unsplit(&mut self, row: usize, col: usize) {
+        let cells = &mut self.cells[row];
+        if cells[col].text.is_empty() && col > 0 {
+            cells[col - 1] = Cell::default();
+            cells[col] = Cell::default();
+        } else if cells.get(col + 1).is_some_and(|c| c.text.is_empty())
+            && UnicodeWidthChar::width(cells[col].text.chars().next().unwrap_or(' ')) == Some(2)
+        {
+            cells[col + 1] = Cell::default();
+        }
+    }
+}
+
+/// The screen as of one flush.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Virtual time since recording started.
+    pub at: Duration,
+    pub alternate: bool,
+    pub lines: Vec<String>,
+}
+
+/// A headless target: output is interpreted into a `VirtualScreen`, sleeps
+/// advance a virtual clock, and each flush captures a `Frame` along with the
+/// raw output written since the last one. Resizes and key presses can be
+/// scheduled to test how renders react.
+pub struct Recorder {
+    screen: VirtualScreen,
+    now: Duration,
+    frames: Vec<Frame>,
+    output: Vec<(Duration, String)>,
+    unflushed: Vec<u8>,
+    /// Pending resizes, in time order.
+    resizes: Vec<(Duration, u16, u16)>,
+    key_at: Option<Duration>,
+}
+
+impl Recorder {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        Self {
+            screen: VirtualScreen::new(cols, rows),
+            now: Duration::ZERO,
+            frames: Vec::new(),
+            output: Vec::new(),
+            unflushed: Vec::new(),
+            resizes: Vec::new(),
+            key_at: None,
+        }
+    }
+
+    /// Resize the screen once the clock reaches `at`.
+    pub fn resize_at(&mut self, at: Duration, cols: u16, rows: u16) {
+        self.resizes.push((at, cols, rows));
+        self.resizes.sort_by_key(|&(at, ..)| at);
+    }
+
+    /// Press a key once the clock reaches `at`.
+    pub fn press_key_at(&mut self, at: Duration) {
+        self.key_at = Some(at);
+    }
+
+    pub fn screen(&self) -> &VirtualScreen {
+        &self.screen
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Everything written, split at flushes and stamped with the time of
+    /// the flush.
+    pub fn output(&self) -> &[(Duration, String)] {
+        &self.output
+    }
+
+    /// Frames that differ from the one before, dropping repeats of a still
+    /// screen.
+    pub fn distinct_frames(&self) -> Vec<&Frame> {
+        let mut out: Vec<&Frame> = Vec::new();
+        for f in &self.frames {
+            let same = out
+                .last()
+                .is_some_and(|p| p.lines == f.lines && p.alternate == f.alternate);
+            if !same {
+                out.push(f);
+            }
+        }
+        out
+    }
+}
+
+impl Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.screen.feed(buf);
+        self.unflushed.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.unflushed.is_empty() {
+            let data = String::from_utf8_lossy(&self.unflushed).into_owned();
+            self.output.push((self.now, data));
+            self.unflushed.clear();
+        }
+        self.frames.push(Frame {
+            at: self.now,
+            alternate: self.screen.alternate,
+            lines: self.screen.lines(),
+        });
+        Ok(())
+    }
+}
+
+impl Clock for Recorder {
+    fn sleep(&mut self, d: Duration) {
+        self.now += d;
+        while let Some(&(at, cols, rows)) = self.resizes.first() {
+            if at > self.now {
+                break;
+            }
+            self.screen.resize(cols, rows);
+            self.resizes.remove(0);
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.now
+    }
+}
+
+impl Target for Recorder {
+    fn size(&self) -> (u16, u16) {
+        self.screen.size()
+    }
+
+    fn key_pressed(&mut self) -> bool {
+        self.key_at.is_some_and(|at| self.now >= at)
+    }
+}
+
+/// Compare `actual` with the golden file `src/snapshots/<name>.snap`. A
+/// missing file fails the test; set `UPDATE_SNAPSHOTS=1` to create or
+/// rewrite them.
+#[cfg(test)]
+pub(crate) fn assert_snapshot(name: &str, actual: &str) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src/snapshots")
+        .join(format!("{name}.snap"));
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    match std::fs::read_to_string(&path) {
+        Ok(expected) if !update => {
+            assert!(
+                expected == actual,
+                "snapshot {name} differs; rerun with UPDATE_SNAPSHOTS=1 to accept\n\
+                 --- expected\n{expected}\n--- actual\n{actual}"
+            );
+        }
+        Err(e) if !update => panic!(
+            "snapshot {name} missing ({e}); rerun with UPDATE_SNAPSHOTS=1 to create {}",
+            path.display()
+        ),
+        _ => {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+    use crossterm::terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+    use crossterm::{cursor, queue};
+
+    #[test]
+    fn test_virtual_screen_draws_crossterm_output() {
+        let mut rec = Recorder::new(10, 3);
+        queue!(
+            rec,
+            EnterAlternateScreen,
+            cursor::Hide,
+            Clear(ClearType::All),
+            cursor::MoveTo(2, 1),
+            SetForegroundColor(Color::Yellow),
+            Print("hi"),
+            ResetColor,
+            Print("!"),
+        )
+        .unwrap();
+        rec.flush().unwrap();
+        let s = rec.screen();
+        assert!(s.alternate);
+        assert_eq!(s.lines(), vec!["", "  hi!", ""]);
+        assert!(s.cell(2, 1).unwrap().fg.is_some());
+        assert_eq!(s.cell(4, 1).unwrap().fg, None);
+
+        queue!(rec, LeaveAlternateScreen).unwrap();
+        rec.flush().unwrap();
+        assert!(!rec.screen().alternate);
+        assert_eq!(rec.frames().len(), 2);
+        assert_eq!(rec.frames()[1].lines, vec!["", "", ""]);
+    }
+
+    #[test]
+    fn test_virtual_screen_wide_characters() {
+        let mut s = VirtualScreen::new(6, 1);
+        s.feed("🏆ab".as_bytes());
+        assert_eq!(s.lines(), vec!["🏆ab"]);
+        // Overwriting half of a wide character blanks the other half
+        s.feed("\x1b[1;2Hx".as_bytes());
+        assert_eq!(s.lines(), vec![" xab"]);
+        // Too wide for the last column: dropped, not wrapped
+        s.feed("\x1b[1;6H🏆".as_bytes());
+        assert_eq!(s.lines(), vec![" xab"]);
+        // Variation selectors stay with their emoji
+        s.feed("\x1b[1;1H⚡\u{fe0f}".as_bytes());
+        assert_eq!(s.cell(0, 0).unwrap().text, "⚡\u{fe0f}");
+    }
+
+    #[test]
+    fn test_recorder_resizes_on_schedule() {
+        let mut rec = Recorder::new(6, 2);
+        write!(rec, "abcdef\r\nxy").unwrap();
+        rec.resize_at(Duration::from_millis(100), 3, 1);
+        rec.sleep(Duration::from_millis(50));
+        assert_eq!(rec.size(), (6, 2));
+        rec.sleep(Duration::from_millis(50));
+        assert_eq!(rec.size(), (3, 1));
+        assert_eq!(rec.screen().lines(), vec!["abc"]);
+        assert!(!rec.key_pressed());
+        rec.press_key_at(Duration::from_millis(100));
+        assert!(rec.key_pressed());
+    }
+
+    #[test]
+    fn test_virtual_screen_split_writes() {
+        let mut s = VirtualScreen::new(5, 1);
+        let bytes = "\x1b[1;3H★".as_bytes();
+        for b in bytes {
+            s.feed(std::slice::from_ref(b));
+        }
+        assert_eq!(s.lines(), vec!["  ★"]);
+    }
+
+    #[test]
+    fn test_recorder_clock_is_virtual() {
+        let mut rec = Recorder::new(4, 1);
+        rec.sleep(Duration::from_secs(60));
+        write!(rec, "a").unwrap();
+        rec.flush().unwrap();
+        rec.flush().unwrap();
+        assert_eq!(rec.elapsed(), Duration::from_secs(60));
+        assert_eq!(rec.frames()[0].at, Duration::from_secs(60));
+        assert_eq!(rec.distinct_frames().len(), 1);
+        assert_eq!(rec.output(), [(Duration::from_secs(60), "a".to_string())]);
+    }
+
+    #[test]
+    fn test_missing_snapshot_fails() {
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            return;
+        }
+        let result = std::panic::catch_unwind(|| assert_snapshot("no_such_snapshot", "x"));
+        let err = result.unwrap_err();
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert!(msg.contains("snapshot no_such_snapshot missing"), "{msg}");
+    }
+}
@@ -1,38 +1,152 @@
 use crate::audio::SoundKind;
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const SAMPLE_RATE: u32 = 48000;
 const PI2: f32 = 2.0 * std::f32::consts::PI;
 
+/// Oscillator shape used to synthesize a note.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Waveform {
+    #[default]
+    Sine,
+    Square,
+    Triangle,
+    Noise,
+}
+
+impl Waveform {
+    /// Sample the waveform at time `t` for a note of frequency `freq`.
+    /// `noise` is the per-note PRNG state, only advanced by `Noise`.
+    fn sample(self, freq: f32, t: f32, noise: &mut u32) -> f32 {
+        let phase = (freq * t).fract();
+        match self {
+            Waveform::Sine => (PI2 * phase).sin(),
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Triangle => 4.0 * (phase - 0.5).abs() - 1.0,
+            Waveform::Noise => {
+                // xorshift32: deterministic so rendered packs are reproducible
+                *noise ^= *noise << 13;
+                *noise ^= *noise >> 17;
+                *noise ^= *noise << 5;
+                (*noise as f32 / u32::MAX as f32) * 2.0 - 1.0
+            }
+        }
+    }
+}
+
+/// Attack/decay/release times in seconds, sustain as a 0.0–1.0 level.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Adsr {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Default for Adsr {
+    fn default() -> Self {
+        Self {
+            attack: 0.01,
+            decay: 0.1,
+            sustain: 0.7,
+            release: 0.1,
+        }
+    }
+}
+
+/// Amplitude envelope applied to a note.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Envelope {
+    /// Built-in bell: quick attack (5ms), then exponential decay over the note.
+    Pluck,
+    /// Classic ADSR; the release tail starts after the note's duration.
+    Adsr(Adsr),
+}
+
+impl Adsr {
+    /// Gain while the note is held (attack → decay → sustain).
+    fn held(&self, t: f32) -> f32 {
+        if t < self.attack {
+            t / self.attack
+        } else if t < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (t - self.attack) / self.decay
+        } else {
+            self.sustain
+        }
+    }
+}
+
+impl Envelope {
+    /// Envelope gain at `t` seconds into a note lasting `dur` seconds.
+    fn gain(&self, t: f32, dur: f32) -> f32 {
+        match self {
+            Envelope::Pluck => (t / 0.005).min(1.0) * (-3.0 * t / dur).exp(),
+            Envelope::Adsr(env) if t < dur => env.held(t),
+            // Release ramps down from wherever the envelope was when the note ended
+            Envelope::Adsr(env) if env.release > 0.0 => {
+                env.held(dur) * (1.0 - (t - dur) / env.release).max(0.0)
+            }
+            Envelope::Adsr(_) => 0.0,
+        }
+    }
+
+    /// Extra time the note keeps sounding after its duration.
+    fn tail(&self) -> f32 {
+        match self {
+            Envelope::Pluck => 0.0,
+            Envelope::Adsr(env) => env.release,
+        }
+    }
+}
+
 /// A single note with frequency, start time, duration, and amplitude.
 struct Note {
     freq: f32,
     start: f32,
     dur: f32,
     amp: f32,
+    wave: Waveform,
+    envelope: Envelope,
 }
 
-/// Render a sequence of notes into samples with fade-out envelopes.
+/// Defaults for the built-in melodies: sine tone with the bell envelope.
+const PLUCK: Note = Note {
+    freq: 0.0,
+    start: 0.0,
+    dur: 0.0,
+    amp: 0.0,
+    wave: Waveform::Sine,
+    envelope: Envelope::Pluck,
+};
+
+/// Render a sequence of notes into samples using each note's waveform and envelope.
 fn render_notes(notes: &[Note], total_duration: f32) -> Vec<i16> {
     let num_samples = (SAMPLE_RATE as f32 * total_duration) as usize;
     let mut samples = vec![0f32; num_samples];
 
-    for note in notes {
+    for (n, note) in notes.iter().enumerate() {
         let start_idx = (SAMPLE_RATE as f32 * note.start) as usize;
-        let note_samples = (SAMPLE_RATE as f32 * note.dur) as usize;
+        let note_samples = (SAMPLE_RATE as f32 * (note.dur + note.envelope.tail())) as usize;
+        let mut noise = 0x9E37_79B9 ^ (n as u32 + 1);
         for i in 0..note_samples {
             let idx = start_idx + i;
             if idx >= num_samples {
                 break;
             }
             let t = i as f32 / SAMPLE_RATE as f32;
-            // Smooth fade: quick attack (5ms), then exponential decay
-            let attack = (t / 0.005).min(1.0);
-            let decay = (-3.0 * t / note.dur).exp();
-            let envelope = attack * decay;
-            samples[idx] += envelope * note.amp * (PI2 * note.freq * t).sin();
+            let envelope = note.envelope.gain(t, note.dur);
+            samples[idx] += envelope * note.amp * note.wave.sample(note.freq, t, &mut noise);
         }
     }
 
@@ -57,12 +171,14 @@ fn sound_notes(kind: &SoundKind) -> (Vec<Note>, f32) {
                     start: 0.0,
                     dur: 0.06,
                     amp: 0.7,
+                    ..PLUCK
                 }, // E6
                 Note {
                     freq: 1568.0,
                     start: 0.08,
                     dur: 0.06,
                     amp: 0.5,
+                    ..PLUCK
                 }, // G6
             ];
             (notes, 0.2)
@@ -75,12 +191,14 @@ fn sound_notes(kind: &SoundKind) -> (Vec<Note>, f32) {
                     start: 0.0,
                     dur: 0.3,
                     amp: 0.8,
+                    ..PLUCK
                 }, // C5
                 Note {
                     freq: 659.25,
                     start: 0.15,
                     dur: 0.4,
                     amp: 0.9,
+                    ..PLUCK
                 }, // E5
             ];
             (notes, 0.6)
@@ -93,24 +211,28 @@ fn sound_notes(kind: &SoundKind) -> (Vec<Note>, f32) {
                     start: 0.0,
                     dur: 0.8,
                     amp: 0.7,
+                    ..PLUCK
                 }, // C4
                 Note {
                     freq: 329.63,
                     start: 0.05,
                     dur: 0.8,
                     amp: 0.6,
+                    ..PLUCK
                 }, // E4
                 Note {
                     freq: 392.00,
                     start: 0.1,
                     dur: 0.8,
                     amp: 0.6,
+                    ..PLUCK
                 }, // G4
                 Note {
                     freq: 523.25,
                     start: 0.15,
                     dur: 0.7,
                     amp: 0.5,
+                    ..PLUCK
                 }, // C5 (octave)
             ];
             (notes, 1.0)
@@ -123,24 +245,28 @@ fn sound_notes(kind: &SoundKind) -> (Vec<Note>, f32) {
                     start: 0.0,
                     dur: 0.2,
                     amp: 0.8,
+                    ..PLUCK
                 }, // C5
                 Note {
                     freq: 659.25,
                     start: 0.18,
                     dur: 0.2,
                     amp: 0.8,
+                    ..PLUCK
                 }, // E5
                 Note {
                     freq: 783.99,
                     start: 0.36,
                     dur: 0.2,
                     amp: 0.9,
+                    ..PLUCK
                 }, // G5
                 Note {
                     freq: 1046.5,
                     start: 0.54,
                     dur: 0.6,
                     amp: 1.0,
+                    ..PLUCK
                 }, // C6 (held)
             ];
             (notes, 1.2)
@@ -158,6 +284,7 @@ fn sound_notes(kind: &SoundKind) -> (Vec<Note>, f32) {
                     start,
                     dur: 0.25,
                     amp: 0.7,
+                    ..PLUCK
                 });
                 // Echo at half volume
                 notes.push(Note {
@@ -165,6 +292,7 @@ fn sound_notes(kind: &SoundKind) -> (Vec<Note>, f32) {
                     start: start + 0.12,
                     dur: 0.15,
                     amp: 0.3,
+                    ..PLUCK
                 });
             }
             // Final held chord
@@ -173,15 +301,223 @@ fn sound_notes(kind: &SoundKind) -> (Vec<Note>, f32) {
                 start: 0.7,
                 dur: 0.8,
                 amp: 0.8,
+                ..PLUCK
             }); // C6
             notes.push(Note {
                 freq: 1318.5,
                 start: 0.75,
                 dur: 0.7,
                 amp: 0.6,
+                ..PLUCK
             }); // E6
             (notes, 1.6)
         }
+        // SessionStreak: warm rising fourth, repeated an octave up
+        SoundKind::SessionStreak => {
+            let pairs = [(392.00, 523.25), (783.99, 1046.5)]; // G4 C5, G5 C6
+            let mut notes = Vec::new();
+            for (i, &(low, high)) in pairs.iter().enumerate() {
+                let start = i as f32 * 0.3;
+                notes.push(Note {
+                    freq: low,
+                    start,
+                    dur: 0.15,
+                    amp: 0.6,
+                    ..PLUCK
+                });
+                notes.push(Note {
+                    freq: high,
+                    start: start + 0.12,
+                    dur: 0.35,
+                    amp: 0.8,
+                    ..PLUCK
+                });
+            }
+            (notes, 1.0)
+        }
+        // TestStreak: three quick "check" blips, then a bright resolve
+        SoundKind::TestStreak => {
+            let mut notes: Vec<Note> = (0..3)
+                .map(|i| Note {
+                    freq: 1174.7, // D6
+                    start: i as f32 * 0.1,
+                    dur: 0.05,
+                    amp: 0.5,
+                    ..PLUCK
+                })
+                .collect();
+            for (freq, amp) in [(1046.5, 0.7), (1568.0, 0.6)] {
+                // C6 + G6
+                notes.push(Note {
+                    freq,
+                    start: 0.32,
+                    dur: 0.6,
+                    amp,
+                    ..PLUCK
+                });
+            }
+            (notes, 1.1)
+        }
+        // PushStreak: fanfare call stepping up through D major
+        SoundKind::PushStreak => {
+            let call = [587.33, 739.99, 880.0, 1174.7, 1480.0]; // D5 F#5 A5 D6 F#6
+            let notes = call
+                .iter()
+                .enumerate()
+                .map(|(i, &freq)| Note {
+                    freq,
+                    start: i as f32 * 0.14,
+                    dur: if i == call.len() - 1 { 0.7 } else { 0.16 },
+                    amp: 0.6 + i as f32 * 0.08,
+                    ..PLUCK
+                })
+                .collect();
+            (notes, 1.4)
+        }
+        // ToolStreak: short run of ticks ending on a chime
+        SoundKind::ToolStreak => {
+            let mut notes: Vec<Note> = (0..5)
+                .map(|i| Note {
+                    freq: 880.0 + i as f32 * 110.0,
+                    start: i as f32 * 0.05,
+                    dur: 0.04,
+                    amp: 0.4,
+                    ..PLUCK
+                })
+                .collect();
+            notes.push(Note {
+                freq: 1760.0, // A6
+                start: 0.28,
+                dur: 0.4,
+                amp: 0.7,
+                ..PLUCK
+            });
+            (notes, 0.8)
+        }
+        // LevelUp: quick G major power-up arpeggio into a bright held octave
+        SoundKind::LevelUp => {
+            let arpeggio = [783.99, 987.77, 1174.7, 1568.0]; // G5 B5 D6 G6
+            let mut notes: Vec<Note> = arpeggio
+                .iter()
+                .enumerate()
+                .map(|(i, &freq)| Note {
+                    freq,
+                    start: i as f32 * 0.07,
+                    dur: 0.12,
+                    amp: 0.6,
+                    ..PLUCK
+                })
+                .collect();
+            notes.push(Note {
+                freq: 1568.0,
+                start: 0.3,
+                dur: 0.5,
+                amp: 0.8,
+                wave: Waveform::Triangle,
+                ..PLUCK
+            }); // G6 (held)
+            notes.push(Note {
+                freq: 1975.5,
+                start: 0.3,
+                dur: 0.5,
+                amp: 0.4,
+                ..PLUCK
+            }); // B6
+            (notes, 0.9)
+        }
+        // Achievement: two staccato pickups, then an A major chord with sparkle
+        SoundKind::Achievement => {
+            let mut notes = vec![
+                Note {
+                    freq: 1318.5,
+                    start: 0.0,
+                    dur: 0.08,
+                    amp: 0.6,
+                    ..PLUCK
+                }, // E6
+                Note {
+                    freq: 1318.5,
+                    start: 0.1,
+                    dur: 0.08,
+                    amp: 0.6,
+                    ..PLUCK
+                }, // E6
+            ];
+            for (i, &freq) in [880.0, 1108.7, 1318.5].iter().enumerate() {
+                notes.push(Note {
+                    freq,
+                    start: 0.22 + i as f32 * 0.02,
+                    dur: 0.7,
+                    amp: 0.6,
+                    ..PLUCK
+                }); // A5 C#6 E6
+            }
+            notes.push(Note {
+                freq: 3520.0,
+                start: 0.3,
+                dur: 0.4,
+                amp: 0.15,
+                wave: Waveform::Triangle,
+                ..PLUCK
+            }); // A7 sparkle
+            (notes, 1.0)
+        }
+        // Quest: task-complete motif that resolves up to the tonic
+        SoundKind::Quest => {
+            let motif = [(783.99, 0.0), (659.25, 0.12), (1046.5, 0.24)]; // G5 E5 C6
+            let notes = motif
+                .iter()
+                .map(|&(freq, start)| Note {
+                    freq,
+                    start,
+                    dur: if start > 0.2 { 0.5 } else { 0.15 },
+                    amp: 0.7,
+                    ..PLUCK
+                })
+                .collect();
+            (notes, 0.8)
+        }
+        // SessionEnd: gentle descending goodbye with a soft low root
+        SoundKind::SessionEnd => {
+            let mut notes: Vec<Note> = [783.99, 659.25, 523.25] // G5 E5 C5
+                .iter()
+                .enumerate()
+                .map(|(i, &freq)| Note {
+                    freq,
+                    start: i as f32 * 0.22,
+                    dur: 0.6,
+                    amp: 0.6,
+                    ..PLUCK
+                })
+                .collect();
+            notes.push(Note {
+                freq: 261.63,
+                start: 0.44,
+                dur: 0.9,
+                amp: 0.4,
+                ..PLUCK
+            }); // C4
+            (notes, 1.4)
+        }
+        // Failure: soft descending "wah-wah" in a low register
+        SoundKind::Failure => {
+            let notes = [
+                (329.63, 0.0, 0.25),
+                (311.13, 0.25, 0.25),
+                (293.66, 0.5, 0.6),
+            ] // E4 Eb4 D4
+            .iter()
+            .map(|&(freq, start, dur)| Note {
+                freq,
+                start,
+                dur,
+                amp: 0.5,
+                wave: Waveform::Triangle,
+                ..PLUCK
+            })
+            .collect();
+            (notes, 1.1)
+        }
     }
 }
 
@@ -191,6 +527,208 @@ pub fn generate_wav(kind: &SoundKind) -> Vec<u8> {
     encode_wav(&samples, SAMPLE_RATE)
 }
 
+/// A user-authored sound, loaded from `<name>.toml` in a sound pack.
+///
+/// `at`/`len` and `sequence` lengths are in beats at `tempo` BPM, so the
+/// default tempo of 60 makes one beat one second.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SoundSpec {
+    #[serde(default = "default_tempo")]
+    pub tempo: f32,
+    /// Default waveform for notes that don't set their own.
+    #[serde(default)]
+    pub wave: Waveform,
+    /// Default envelope for notes that don't set their own.
+    #[serde(default)]
+    pub envelope: Adsr,
+    /// Compact melody, played from beat 0: `"C5:0.5 E5:0.5 r:0.25 G5:2"`.
+    /// Each token is a pitch (or `r` for a rest) with an optional `:beats` (default 1).
+    #[serde(default)]
+    pub sequence: String,
+    /// Individually placed notes, mixed on top of `sequence`.
+    #[serde(default)]
+    pub notes: Vec<NoteSpec>,
+}
+
+/// One note in a [`SoundSpec`]. Give either `pitch` (e.g. `"A4"`, `"C#5"`, `"Eb3"`)
+/// or `freq` in Hz; noise notes may omit both.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoteSpec {
+    pub pitch: Option<String>,
+    pub freq: Option<f32>,
+    #[serde(default)]
+    pub at: f32,
+    #[serde(default = "default_len")]
+    pub len: f32,
+    #[serde(default = "default_amp")]
+    pub amp: f32,
+    pub wave: Option<Waveform>,
+    pub envelope: Option<Adsr>,
+}
+
+/// Longest sound a spec may render to, in seconds.
+const MAX_SPEC_SECONDS: f32 = 30.0;
+/// Most notes a spec may have.
+const MAX_SPEC_NOTES: usize = 1024;
+
+fn default_tempo() -> f32 {
+    60.0
+}
+
+fn default_len() -> f32 {
+    1.0
+}
+
+fn default_amp() -> f32 {
+    0.8
+}
+
+/// Convert a note name like `"A4"`, `"C#5"` or `"Bb3"` to a frequency in Hz (A4 = 440).
+pub fn parse_pitch(name: &str) -> Option<f32> {
+    let mut chars = name.chars();
+    let semitone: i32 = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (accidental, octave) = match rest.chars().next() {
+        Some('#') => (1, &rest[1..]),
+        Some('b') => (-1, &rest[1..]),
+        _ => (0, rest),
+    };
+    let octave: i32 = octave.parse().ok()?;
+    let midi = (octave + 1) * 12 + semitone + accidental;
+    Some(440.0 * 2f32.powf((midi - 69) as f32 / 12.0))
+}
+
+impl SoundSpec {
+    pub fn parse(src: &str) -> Result<Self> {
+        let spec: SoundSpec = toml::from_str(src).context("invalid sound spec")?;
+        if !spec.tempo.is_finite() || spec.tempo <= 0.0 {
+            bail!("tempo must be positive, got {}", spec.tempo);
+        }
+        Ok(spec)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let src =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&src).with_context(|| format!("in {}", path.display()))
+    }
+
+    /// Resolve the spec into concrete notes and the total length in seconds.
+    fn to_notes(&self) -> Result<(Vec<Note>, f32)> {
+        let beat = 60.0 / self.tempo;
+        let mut notes = Vec::new();
+
+        let mut cursor = 0.0;
+        for token in self.sequence.split_whitespace() {
+            let (name, beats) = match token.split_once(':') {
+                Some((name, beats)) => (
+                    name,
+                    beats
+                        .parse::<f32>()
+                        .with_context(|| format!("bad length in sequence token {token:?}"))?,
+                ),
+                None => (token, 1.0),
+            };
+            if name != "r" {
+                let freq = parse_pitch(name)
+                    .with_context(|| format!("unknown pitch in sequence token {token:?}"))?;
+                notes.push(Note {
+                    freq,
+                    start: cursor * beat,
+                    dur: beats * beat,
+                    amp: default_amp(),
+                    wave: self.wave,
+                    envelope: Envelope::Adsr(self.envelope),
+                });
+            }
+            cursor += beats;
+        }
+
+        for spec in &self.notes {
+            let wave = spec.wave.unwrap_or(self.wave);
+            let freq = match (&spec.pitch, spec.freq) {
+                (Some(_), Some(_)) => bail!("note sets both pitch and freq"),
+                (Some(name), None) => {
+                    parse_pitch(name).with_context(|| format!("unknown pitch {name:?}"))?
+                }
+                (None, Some(freq)) => freq,
+                (None, None) if wave == Waveform::Noise => 0.0,
+                (None, None) => bail!("note needs a pitch or freq"),
+            };
+            notes.push(Note {
+                freq,
+                start: spec.at * beat,
+                dur: spec.len * beat,
+                amp: spec.amp,
+                wave,
+                envelope: Envelope::Adsr(spec.envelope.unwrap_or(self.envelope)),
+            });
+        }
+
+        if notes.is_empty() {
+            bail!("sound spec has no notes");
+        }
+        if notes.len() > MAX_SPEC_NOTES {
+            bail!(
+                "sound spec has {} notes, at most {MAX_SPEC_NOTES} allowed",
+                notes.len()
+            );
+        }
+        let mut total = 0f32;
+        for n in &notes {
+            let end = n.start + n.dur + n.envelope.tail();
+            if ![n.freq, n.amp, n.start, n.dur, end]
+                .iter()
+                .all(|v| v.is_finite())
+            {
+                bail!("note timing, pitch and amp must be finite numbers");
+            }
+            total = total.max(end);
+        }
+        if total > MAX_SPEC_SECONDS {
+            bail!("sound spec lasts {total:.1}s, at most {MAX_SPEC_SECONDS}s allowed");
+        }
+        Ok((notes, total))
+    }
+
+    pub fn to_wav(&self) -> Result<Vec<u8>> {
+        let (notes, total_duration) = self.to_notes()?;
+        let samples = render_notes(&notes, total_duration);
+        Ok(encode_wav(&samples, SAMPLE_RATE))
+    }
+}
+
+/// Render a pack's `<name>.toml` spec to a cached WAV under the temp dir,
+/// re-rendering only when the spec is newer than the cached file.
+pub fn ensure_spec_sound_file(spec_path: &Path, pack: &str) -> Result<PathBuf> {
+    let tmp_dir = std::env::temp_dir().join("cwinner").join(pack);
+    fs::create_dir_all(&tmp_dir)?;
+    let stem = spec_path
+        .file_stem()
+        .context("sound spec has no file name")?;
+    let path = tmp_dir.join(stem).with_extension("wav");
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    let stale = match (modified(spec_path), modified(&path)) {
+        (Some(spec), Some(wav)) => spec > wav,
+        _ => true,
+    };
+    if stale {
+        fs::write(&path, SoundSpec::load(spec_path)?.to_wav()?)?;
+    }
+    Ok(path)
+}
+
 /// Encode mono samples as stereo WAV (HDMI/DisplayPort requires stereo).
 fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
     let num_channels: u16 = 2;
@@ -228,17 +766,11 @@ fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
 
 pub fn extract_all_sounds(dest: &Path) -> Result<()> {
     fs::create_dir_all(dest)?;
-    for kind in [
-        SoundKind::Mini,
-        SoundKind::Milestone,
-        SoundKind::Epic,
-        SoundKind::Fanfare,
-        SoundKind::Streak,
-    ] {
+    for kind in SoundKind::ALL {
         let filename = format!("{}.wav", kind.name());
         let path = dest.join(filename);
         if !path.exists() {
-            fs::write(&path, generate_wav(&kind))?;
+            fs::write(&path, generate_wav(kind))?;
         }
     }
     Ok(())
@@ -270,14 +802,8 @@ mod tests {
 
     #[test]
     fn test_all_sounds_generate() {
-        for kind in [
-            SoundKind::Mini,
-            SoundKind::Milestone,
-            SoundKind::Epic,
-            SoundKind::Fanfare,
-            SoundKind::Streak,
-        ] {
-            let wav = generate_wav(&kind);
+        for kind in SoundKind::ALL {
+            let wav = generate_wav(kind);
             assert!(wav.len() > 100, "{:?} generated empty WAV", kind);
         }
     }
@@ -292,6 +818,15 @@ mod tests {
             "epic.wav",
             "fanfare.wav",
             "streak.wav",
+            "streak_session.wav",
+            "streak_test.wav",
+            "streak_push.wav",
+            "streak_tool.wav",
+            "levelup.wav",
+            "achievement.wav",
+            "quest.wav",
+            "session_end.wav",
+            "failure.wav",
         ] {
             assert!(tmp.path().join(name).exists(), "{} missing", name);
         }
@@ -312,4 +847,107 @@ mod tests {
             "Fanfare should be shorter than Streak"
         );
     }
+
+    #[test]
+    fn test_parse_pitch() {
+        assert_eq!(parse_pitch("A4"), Some(440.0));
+        assert!((parse_pitch("C5").unwrap() - 523.25).abs() < 0.01);
+        assert!((parse_pitch("C#5").unwrap() - 554.37).abs() < 0.01);
+        assert_eq!(parse_pitch("Db5"), parse_pitch("C#5"));
+        assert_eq!(parse_pitch("H4"), None);
+        assert_eq!(parse_pitch("A"), None);
+    }
+
+    #[test]
+    fn test_sound_spec_renders_wav() {
+        let spec = SoundSpec::parse(
+            r#"
+tempo = 120
+wave = "square"
+sequence = "C5:0.5 r:0.5 E5 G5:2"
+
+[envelope]
+attack = 0.01
+decay = 0.05
+sustain = 0.5
+release = 0.2
+
+[[notes]]
+at = 0
+len = 0.25
+wave = "noise"
+amp = 0.3
+"#,
+        )
+        .unwrap();
+        let (notes, total) = spec.to_notes().unwrap();
+        assert_eq!(notes.len(), 4);
+        // Last sequence note: starts at beat 2 (1s), lasts 2 beats (1s), plus 0.2s release
+        assert!((total - 2.2).abs() < 1e-4);
+        let wav = spec.to_wav().unwrap();
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert!(wav.len() > 44);
+    }
+
+    #[test]
+    fn test_sound_spec_tempo_scales_timing() {
+        let slow = SoundSpec::parse("tempo = 60\nsequence = \"A4\"").unwrap();
+        let fast = SoundSpec::parse("tempo = 240\nsequence = \"A4\"").unwrap();
+        let (slow_notes, _) = slow.to_notes().unwrap();
+        let (fast_notes, _) = fast.to_notes().unwrap();
+        assert_eq!(slow_notes[0].dur, 1.0);
+        assert_eq!(fast_notes[0].dur, 0.25);
+    }
+
+    #[test]
+    fn test_sound_spec_rejects_bad_input() {
+        assert!(
+            SoundSpec::parse("sequence = \"X9\"")
+                .unwrap()
+                .to_notes()
+                .is_err()
+        );
+        assert!(SoundSpec::parse("").unwrap().to_notes().is_err());
+        assert!(SoundSpec::parse("tempo = 0\nsequence = \"A4\"").is_err());
+        assert!(SoundSpec::parse("tempo = nan\nsequence = \"A4\"").is_err());
+        assert!(SoundSpec::parse("tempo = inf\nsequence = \"A4\"").is_err());
+        // Too long to render, or too many notes
+        let long = SoundSpec::parse("tempo = 0.0001\nsequence = \"A4\"").unwrap();
+        let err = long.to_notes().err().unwrap();
+        assert!(err.to_string().contains("at most"), "{err}");
+        let many = format!("tempo = 6000\nsequence = \"{}\"", "A4:0.1 ".repeat(2000));
+        assert!(SoundSpec::parse(&many).unwrap().to_notes().is_err());
+        let huge = "[[notes]]\nfreq = 440.0\nat = 1e30";
+        assert!(SoundSpec::parse(huge).unwrap().to_notes().is_err());
+        assert!(SoundSpec::parse("bogus = 1").is_err());
+        let both = "[[notes]]\npitch = \"A4\"\nfreq = 440.0";
+        assert!(SoundSpec::parse(both).unwrap().to_notes().is_err());
+    }
+
+    #[test]
+    fn test_adsr_envelope_shape() {
+        let env = Envelope::Adsr(Adsr {
+            attack: 0.1,
+            decay: 0.1,
+            sustain: 0.5,
+            release: 0.2,
+        });
+        assert!((env.gain(0.05, 1.0) - 0.5).abs() < 1e-4); // mid-attack
+        assert!((env.gain(0.1, 1.0) - 1.0).abs() < 1e-4); // peak
+        assert!((env.gain(0.5, 1.0) - 0.5).abs() < 1e-4); // sustain
+        assert!((env.gain(1.1, 1.0) - 0.25).abs() < 1e-4); // half-way through release
+        assert_eq!(env.gain(1.3, 1.0), 0.0);
+    }
+
+    #[test]
+    fn test_ensure_spec_sound_file_caches_render() {
+        let tmp = tempfile::tempdir().unwrap();
+        let spec = tmp.path().join("mini.toml");
+        fs::write(&spec, "sequence = \"E6:0.1 G6:0.1\"").unwrap();
+        let pack = format!("test-spec-{}", std::process::id());
+        let wav = ensure_spec_sound_file(&spec, &pack).unwrap();
+        assert!(wav.exists());
+        assert_eq!(&fs::read(&wav).unwrap()[0..4], b"RIFF");
+        let _ = fs::remove_dir_all(wav.parent().unwrap());
+    }
 }
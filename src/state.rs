@@ -1,6 +1,12 @@
+use crate::streak::{
+    DayZone, FREEZE_EVERY, HISTORY_DAYS, Streak, StreakDay, StreakKind, StreakPolicy,
+};
+use crate::sync::SyncSeed;
+use crate::xp::XpLedger;
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Streak milestones that trigger special celebrations
@@ -11,6 +17,9 @@ pub struct CommitResult {
     pub first_today: bool,
     /// If the streak just hit a milestone (5, 10, 25, 100), contains the milestone value
     pub streak_milestone: Option<u32>,
+    /// Streak freezes spent on missed days to keep the streak going.
+    pub freezes_used: u32,
+    pub freeze_earned: bool,
 }
 
 pub const LEVELS: &[(u32, &str)] = &[
@@ -236,14 +245,123 @@ pub const LEVELS: &[(u32, &str)] = &[
     (319_000_000, "Code God"),
 ];
 
+/// Current `state.json` format. Bump it and add a step to `MIGRATIONS` when a
+/// change needs more than a new `#[serde(default)]` field.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` file to version `n + 2`.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[v1_streak_days_in_utc];
+
+/// v1 (unversioned) counted streak days in UTC without recording it.
+fn v1_streak_days_in_utc(obj: &mut Map<String, Value>) {
+    let dated = obj.get("last_commit_date").is_some_and(|d| !d.is_null());
+    let zoned = obj.get("streak_timezone").is_some_and(|z| !z.is_null());
+    if dated && !zoned {
+        obj.insert("streak_timezone".into(), "UTC".into());
+    }
+}
+
+/// Rotating backups kept next to `state.json`, newest first.
+pub const BACKUPS: u32 = 3;
+/// How often a save rotates in a new backup.
+const BACKUP_EVERY: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// Present while a quarantined `state.json` waits for `cwinner state repair`;
+/// holds the quarantined file's path.
+const REPAIR_MARKER: &str = ".needs-repair";
+
+/// `path` with `suffix` added to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// How `State::merge` combines counters (XP, commits, sessions) from two
+/// states. Achievements, tools and history are always united, and the most
+/// recent streaks kept.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Merge {
+    /// Take the other state's counters.
+    Replace,
+    /// Keep the higher of each counter.
+    #[default]
+    Max,
+    /// Add the counters, for progress made separately on two machines.
+    Sum,
+}
+
+impl Merge {
+    pub const ALL: [Merge; 3] = [Merge::Replace, Merge::Max, Merge::Sum];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Merge::Replace => "replace",
+            Merge::Max => "max",
+            Merge::Sum => "sum",
+        }
+    }
+
+    fn combine(self, ours: u32, theirs: u32) -> u32 {
+        match self {
+            Merge::Replace => theirs,
+            Merge::Max => ours.max(theirs),
+            Merge::Sum => ours.saturating_add(theirs),
+        }
+    }
+}
+
+impl std::str::FromStr for Merge {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Merge::ALL
+            .into_iter()
+            .find(|m| m.name() == s)
+            .ok_or_else(|| anyhow::anyhow!("unknown merge strategy: {s}"))
+    }
+}
+
+/// What `State::repair` did.
+#[derive(Debug, PartialEq)]
+pub enum Repair {
+    /// Nothing was quarantined and the file reads fine.
+    Healthy,
+    /// Restored from this file.
+    Restored { from: PathBuf, xp: u32 },
+    /// Nothing was readable; started over.
+    Fresh,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct State {
+    /// Format of the file this was read from; `SCHEMA_VERSION` once loaded.
+    #[serde(default)]
+    pub schema_version: u32,
     pub xp: u32,
     pub level: u32,
     pub level_name: String,
     pub commits_total: u32,
     pub commit_streak_days: u32,
     pub last_commit_date: Option<NaiveDate>,
+    /// Timezone `last_commit_date` is in (see `DayZone::name`). Unset in state
+    /// saved while streak days were still counted in UTC.
+    #[serde(default)]
+    pub streak_timezone: Option<String>,
+    /// Streak freezes held, each covering one missed day.
+    #[serde(default)]
+    pub streak_freezes: u32,
+    /// Recent streak days, oldest first.
+    #[serde(default)]
+    pub streak_history: Vec<StreakDay>,
+    /// Streaks other than the commit streak.
+    #[serde(default)]
+    pub streaks: BTreeMap<StreakKind, Streak>,
+    /// XP earned today, for `[xp]` daily caps.
+    #[serde(default)]
+    pub xp_ledger: XpLedger,
+    /// This machine's progress from before it first synced.
+    #[serde(default)]
+    pub sync_seed: Option<SyncSeed>,
     pub sessions_total: u32,
     pub achievements_unlocked: Vec<String>,
     pub tools_used: HashSet<String>,
@@ -254,12 +372,19 @@ impl Default for State {
     fn default() -> Self {
         let (_, name) = LEVELS[0];
         Self {
+            schema_version: SCHEMA_VERSION,
             xp: 0,
             level: 1,
             level_name: name.to_string(),
             commits_total: 0,
             commit_streak_days: 0,
             last_commit_date: None,
+            streak_timezone: None,
+            streak_freezes: 0,
+            streak_history: vec![],
+            streaks: BTreeMap::new(),
+            xp_ledger: XpLedger::default(),
+            sync_seed: None,
             sessions_total: 0,
             achievements_unlocked: vec![],
             tools_used: HashSet::new(),
@@ -269,6 +394,14 @@ impl Default for State {
 }
 
 impl State {
+    /// Take the fields that describe this machine rather than progress from
+    /// `local`: the schema version, XP ledger and sync seed.
+    pub fn keep_local(&mut self, local: State) {
+        self.schema_version = local.schema_version;
+        self.xp_ledger = local.xp_ledger;
+        self.sync_seed = local.sync_seed;
+    }
+
     pub fn add_xp(&mut self, amount: u32) {
         self.xp = self.xp.saturating_add(amount);
         self.update_level();
@@ -284,19 +417,75 @@ impl State {
         }
     }
 
-    /// Returns CommitResult with first_today flag and optional streak milestone
-    pub fn record_commit(&mut self) -> CommitResult {
+    /// Move `last_commit_date` into `zone` if it was recorded in another one.
+    pub fn set_day_zone(&mut self, zone: &DayZone) {
+        let name = zone.name();
+        if self.streak_timezone.as_deref() == Some(name.as_str()) {
+            return;
+        }
+        let from = self
+            .streak_timezone
+            .as_deref()
+            .and_then(DayZone::from_name)
+            .unwrap_or(DayZone::UTC);
+        self.last_commit_date = self.last_commit_date.map(|d| zone.convert(d, &from));
+        for day in &mut self.streak_history {
+            day.date = zone.convert(day.date, &from);
+        }
+        for streak in self.streaks.values_mut() {
+            streak.last_date = streak.last_date.map(|d| zone.convert(d, &from));
+        }
+        self.streak_timezone = Some(name);
+    }
+
+    /// Returns CommitResult with first_today flag and optional streak milestone.
+    /// A gap in the streak is covered by freezes if enough are held.
+    pub fn record_commit(&mut self, policy: &StreakPolicy) -> CommitResult {
+        self.record_commit_at(Utc::now(), policy)
+    }
+
+    pub fn record_commit_at(&mut self, now: DateTime<Utc>, policy: &StreakPolicy) -> CommitResult {
+        self.set_day_zone(&policy.zone);
         self.commits_total += 1;
-        let today = Utc::now().date_naive();
+        let today = policy.zone.date(now);
         let first_today = self.last_commit_date.map(|d| d != today).unwrap_or(true);
         let old_streak = self.commit_streak_days;
+        let mut freezes_used = 0;
+        let mut freeze_earned = false;
         if first_today {
-            let yesterday = today.pred_opt().unwrap();
-            if self.last_commit_date == Some(yesterday) {
-                self.commit_streak_days += 1;
-            } else if self.last_commit_date != Some(today) {
-                self.commit_streak_days = 1;
+            match self.last_commit_date {
+                Some(last) if last < today => {
+                    let missed = policy.missed_days(last, today);
+                    let covered = missed.len() as u32 <= self.streak_freezes;
+                    if !missed.is_empty() && covered {
+                        freezes_used = missed.len() as u32;
+                        self.streak_freezes -= freezes_used;
+                        self.streak_history.extend(
+                            missed
+                                .into_iter()
+                                .map(|date| StreakDay { date, frozen: true }),
+                        );
+                    }
+                    if covered {
+                        self.commit_streak_days += 1;
+                    } else {
+                        self.commit_streak_days = 1;
+                    }
+                }
+                _ => self.commit_streak_days = 1,
+            }
+            if self.commit_streak_days % FREEZE_EVERY == 0
+                && self.streak_freezes < policy.max_freezes
+            {
+                self.streak_freezes += 1;
+                freeze_earned = true;
             }
+            self.streak_history.push(StreakDay {
+                date: today,
+                frozen: false,
+            });
+            let oldest = today - chrono::Days::new(HISTORY_DAYS as u64);
+            self.streak_history.retain(|d| d.date > oldest);
             self.last_commit_date = Some(today);
         }
         let streak_milestone = if self.commit_streak_days != old_streak {
@@ -310,6 +499,44 @@ impl State {
         CommitResult {
             first_today,
             streak_milestone,
+            freezes_used,
+            freeze_earned,
+        }
+    }
+
+    /// Current length of a streak, in days or tool calls.
+    pub fn streak_count(&self, kind: StreakKind) -> u32 {
+        match kind {
+            StreakKind::Commit => self.commit_streak_days,
+            kind => self.streaks.get(&kind).map_or(0, |s| s.count),
+        }
+    }
+
+    /// Count today towards a daily streak other than commits. Returns the
+    /// milestone reached, if any.
+    pub fn record_streak_day(
+        &mut self,
+        kind: StreakKind,
+        now: DateTime<Utc>,
+        policy: &StreakPolicy,
+    ) -> Option<u32> {
+        self.set_day_zone(&policy.zone);
+        let today = policy.zone.date(now);
+        self.streaks
+            .entry(kind)
+            .or_default()
+            .record_day(kind, today, policy)
+    }
+
+    /// Extend the run of successful tool calls, or end it on a failure.
+    /// Returns the milestone reached, if any.
+    pub fn record_tool_result(&mut self, success: bool) -> Option<u32> {
+        let streak = self.streaks.entry(StreakKind::Tool).or_default();
+        if success {
+            streak.extend(StreakKind::Tool)
+        } else {
+            streak.count = 0;
+            None
         }
     }
 
@@ -318,6 +545,60 @@ impl State {
         self.tools_used.insert(tool.to_string())
     }
 
+    /// Fold `other` into this state. `Merge::Replace` takes `other` as is;
+    /// otherwise counters follow `how`, and streaks come from whichever state
+    /// extended them last.
+    pub fn merge(&mut self, mut other: State, how: Merge) {
+        if how == Merge::Replace {
+            let local = std::mem::replace(self, other);
+            self.keep_local(local);
+            return;
+        }
+        // Compare streak days in one timezone
+        if let Some(zone) = self.streak_timezone.as_deref().and_then(DayZone::from_name) {
+            other.set_day_zone(&zone);
+        } else {
+            self.streak_timezone = other.streak_timezone.clone();
+        }
+        self.xp = how.combine(self.xp, other.xp);
+        self.update_level();
+        self.commits_total = how.combine(self.commits_total, other.commits_total);
+        self.sessions_total = how.combine(self.sessions_total, other.sessions_total);
+        for id in &other.achievements_unlocked {
+            self.unlock_achievement(id);
+        }
+        self.tools_used.extend(other.tools_used);
+        self.last_event_at = self.last_event_at.max(other.last_event_at);
+
+        let theirs = (other.last_commit_date, other.commit_streak_days);
+        if theirs > (self.last_commit_date, self.commit_streak_days) {
+            self.last_commit_date = other.last_commit_date;
+            self.commit_streak_days = other.commit_streak_days;
+            self.streak_freezes = other.streak_freezes;
+        }
+        for day in other.streak_history {
+            match self.streak_history.iter_mut().find(|d| d.date == day.date) {
+                // A real commit beats a freeze
+                Some(ours) => ours.frozen &= day.frozen,
+                None => self.streak_history.push(day),
+            }
+        }
+        self.streak_history.sort_by_key(|d| d.date);
+        if let Some(newest) = self.streak_history.last().map(|d| d.date) {
+            let oldest = newest - chrono::Days::new(HISTORY_DAYS as u64);
+            self.streak_history.retain(|d| d.date > oldest);
+        }
+
+        for (kind, streak) in other.streaks {
+            let ours = self.streaks.entry(kind).or_default();
+            let best = ours.best.max(streak.best);
+            if (streak.last_date, streak.count) > (ours.last_date, ours.count) {
+                *ours = streak;
+            }
+            ours.best = best;
+        }
+    }
+
     pub fn unlock_achievement(&mut self, id: &str) -> bool {
         if !self.achievements_unlocked.contains(&id.to_string()) {
             self.achievements_unlocked.push(id.to_string());
@@ -327,15 +608,226 @@ impl State {
         }
     }
 
+    /// Parse `state.json`, upgrading older formats. Returns the state and the
+    /// version it was stored as.
+    pub fn from_json(data: &str) -> anyhow::Result<(Self, u32)> {
+        let mut value: Value = serde_json::from_str(data)?;
+        let obj = value
+            .as_object_mut()
+            .ok_or_else(|| anyhow::anyhow!("state is not a JSON object"))?;
+        // Files from before versioning have no schema_version
+        let version = match obj.get("schema_version") {
+            None => 1,
+            Some(v) => v
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .filter(|&v| v >= 1)
+                .ok_or_else(|| anyhow::anyhow!("invalid schema_version {v}"))?,
+        };
+        if version > SCHEMA_VERSION {
+            anyhow::bail!(
+                "state is schema v{version}, newer than this cwinner understands (v{SCHEMA_VERSION}); upgrade cwinner"
+            );
+        }
+        for migrate in &MIGRATIONS[version as usize - 1..] {
+            migrate(obj);
+        }
+        obj.insert("schema_version".into(), SCHEMA_VERSION.into());
+        Ok((serde_json::from_value(value)?, version))
+    }
+
     pub fn load_from(path: &Path) -> anyhow::Result<Self> {
         let s = std::fs::read_to_string(path)?;
-        Ok(serde_json::from_str(&s)?)
+        Ok(Self::from_json(&s)?.0)
+    }
+
+    /// Copy `path` aside as `state.json.<tag>.bak`, returning the copy.
+    pub fn backup(path: &Path, tag: &str) -> anyhow::Result<PathBuf> {
+        let backup = sibling(path, &format!(".{tag}.bak"));
+        std::fs::copy(path, &backup)?;
+        Ok(backup)
+    }
+
+    /// The `n`th newest rotating backup of `path`, counting from 1.
+    pub fn rotating_backup(path: &Path, n: u32) -> PathBuf {
+        sibling(path, &format!(".{n}.bak"))
+    }
+
+    /// Copy `path` in as the newest rotating backup, if the newest one is
+    /// older than `BACKUP_EVERY`.
+    fn rotate_backups(path: &Path) -> std::io::Result<()> {
+        let newest = Self::rotating_backup(path, 1);
+        let recent = std::fs::metadata(&newest)
+            .and_then(|m| m.modified())
+            .is_ok_and(|t| t.elapsed().is_ok_and(|age| age < BACKUP_EVERY));
+        if recent || !path.exists() {
+            return Ok(());
+        }
+        for n in (1..BACKUPS).rev() {
+            match std::fs::rename(
+                Self::rotating_backup(path, n),
+                Self::rotating_backup(path, n + 1),
+            ) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        std::fs::copy(path, newest).map(|_| ())
+    }
+
+    /// The quarantined file, while `path` is waiting for `cwinner state repair`.
+    pub fn needs_repair(path: &Path) -> Option<PathBuf> {
+        let marker = std::fs::read_to_string(sibling(path, REPAIR_MARKER)).ok()?;
+        Some(PathBuf::from(marker.trim_end()))
+    }
+
+    /// Move an unreadable `path` aside and stop saves until it is repaired.
+    /// Returns where the file went.
+    fn quarantine(path: &Path) -> PathBuf {
+        let stamp = Utc::now().format("%Y%m%dT%H%M%S");
+        let moved = sibling(path, &format!(".corrupt-{stamp}"));
+        // If it can't be moved, it stays put and is still not saved over
+        let moved = match std::fs::rename(path, &moved) {
+            Ok(()) => moved,
+            Err(_) => path.to_path_buf(),
+        };
+        let marker = sibling(path, REPAIR_MARKER);
+        if let Err(e) = std::fs::write(&marker, moved.to_string_lossy().as_bytes()) {
+            eprintln!("[cwinner] could not write {}: {e}", marker.display());
+        }
+        moved
+    }
+
+    /// The newest rotating backup that can be read, to stand in for the
+    /// quarantined file until it is repaired.
+    fn from_backups(path: &Path) -> Self {
+        for n in 1..=BACKUPS {
+            let backup = Self::rotating_backup(path, n);
+            if let Ok(state) = Self::load_from(&backup) {
+                eprintln!(
+                    "[cwinner] using backup {} (XP {}); progress is not saved until `cwinner state repair`",
+                    backup.display(),
+                    state.xp
+                );
+                return state;
+            }
+        }
+        eprintln!(
+            "[cwinner] no readable backup; progress is not saved until `cwinner state repair`"
+        );
+        Self::default()
+    }
+
+    /// Load `path`, keeping a backup of the file before it is migrated to the
+    /// current schema. A file that can't be read is quarantined and never
+    /// saved over: the newest readable backup is used until it is repaired.
+    pub fn load_or_recover(path: &Path) -> Self {
+        let data = match std::fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return if Self::needs_repair(path).is_some() {
+                    Self::from_backups(path)
+                } else {
+                    Self::default()
+                };
+            }
+            Err(e) => {
+                eprintln!("[cwinner] STATE UNREADABLE: {}: {e}", path.display());
+                if Self::needs_repair(path).is_none() {
+                    Self::quarantine(path);
+                }
+                return Self::from_backups(path);
+            }
+        };
+        match Self::from_json(&data) {
+            Ok((state, version)) if version < SCHEMA_VERSION => {
+                match Self::backup(path, &format!("v{version}")) {
+                    Ok(backup) => {
+                        eprintln!(
+                            "[cwinner] state upgraded from v{version} to v{SCHEMA_VERSION}; previous file kept at {}",
+                            backup.display()
+                        );
+                        if let Err(e) = state.save_to(path) {
+                            eprintln!("[cwinner] saving upgraded state: {e:#}");
+                        }
+                    }
+                    // Leave the old file alone; it is upgraded again next time
+                    Err(e) => eprintln!("[cwinner] backing up state before upgrade: {e:#}"),
+                }
+                state
+            }
+            Ok((state, _)) => state,
+            Err(e) => {
+                let moved = Self::quarantine(path);
+                eprintln!(
+                    "[cwinner] STATE CORRUPT: {} ({e:#}); moved to {}",
+                    path.display(),
+                    moved.display()
+                );
+                Self::from_backups(path)
+            }
+        }
+    }
+
+    /// Put `path` back in order after it was quarantined: restore the latest
+    /// readable candidate (the quarantined file, the file itself if fixed by
+    /// hand, or a rotating backup) and allow saves again. With `fresh`, start
+    /// over when nothing is readable.
+    pub fn repair(path: &Path, fresh: bool) -> anyhow::Result<Repair> {
+        let quarantined = match Self::needs_repair(path) {
+            Some(q) => q,
+            None => match std::fs::read_to_string(path) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Repair::Healthy),
+                Ok(data) if Self::from_json(&data).is_ok() => return Ok(Repair::Healthy),
+                // Unreadable, but nothing has loaded it yet
+                _ => Self::quarantine(path),
+            },
+        };
+        let mut candidates = vec![quarantined.clone(), path.to_path_buf()];
+        candidates.extend((1..=BACKUPS).map(|n| Self::rotating_backup(path, n)));
+        // Latest written wins, not most XP: `state import --merge replace` can
+        // lower it. A backup is copied just before the save that replaces it,
+        // so it never looks newer than the file it came from.
+        let modified = |c: &Path| std::fs::metadata(c).and_then(|m| m.modified()).ok();
+        let best = candidates
+            .iter()
+            .filter_map(|c| Self::load_from(c).ok().map(|s| (c, modified(c), s)))
+            .reduce(|best, next| if next.1 > best.1 { next } else { best });
+        let outcome = match best {
+            Some((from, _, state)) => {
+                std::fs::remove_file(sibling(path, REPAIR_MARKER))?;
+                state.save_to(path)?;
+                Repair::Restored {
+                    from: from.clone(),
+                    xp: state.xp,
+                }
+            }
+            None if fresh => {
+                std::fs::remove_file(sibling(path, REPAIR_MARKER))?;
+                Self::default().save_to(path)?;
+                Repair::Fresh
+            }
+            None => anyhow::bail!(
+                "no readable state or backup; fix {} by hand and run this again, or run `cwinner state repair --fresh` to start over",
+                quarantined.display()
+            ),
+        };
+        Ok(outcome)
     }
 
     pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(quarantined) = Self::needs_repair(path) {
+            anyhow::bail!(
+                "not saving: {} is quarantined; run `cwinner state repair`",
+                quarantined.display()
+            );
+        }
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
+        if let Err(e) = Self::rotate_backups(path) {
+            eprintln!("[cwinner] rotating state backups: {e}");
+        }
         let data = serde_json::to_string_pretty(self)?;
         // Per-process temp name so two daemons never clobber each other's
         // in-flight write before the atomic rename.
@@ -350,15 +842,68 @@ impl State {
     }
 
     pub fn load() -> Self {
-        Self::state_path()
-            .and_then(|p| Self::load_from(&p).ok())
-            .unwrap_or_default()
+        let Some(path) = Self::state_path() else {
+            return Self::default();
+        };
+        let _lock = StateLock::acquire_or_warn(&path);
+        Self::load_or_recover(&path)
     }
 
-    pub fn save(&self) {
-        if let Some(path) = Self::state_path() {
-            let _ = self.save_to(&path);
+    /// Apply `f` to the state at `path` and save the result, holding the
+    /// state lock throughout so no other process's update is lost in between.
+    pub fn update_at<T>(path: &Path, f: impl FnOnce(&mut State) -> T) -> T {
+        let _lock = StateLock::acquire_or_warn(path);
+        let mut state = Self::load_or_recover(path);
+        let out = f(&mut state);
+        if let Err(e) = state.save_to(path) {
+            eprintln!("[cwinner] {e:#}");
         }
+        out
+    }
+
+    /// `update_at` on the default state file.
+    pub fn update<T>(f: impl FnOnce(&mut State) -> T) -> T {
+        match Self::state_path() {
+            Some(path) => Self::update_at(&path, f),
+            None => f(&mut Self::default()),
+        }
+    }
+}
+
+/// Advisory lock serializing every process's reads and writes of a state
+/// file, held on `state.json.lock` until dropped. `load_or_recover`,
+/// `save_to` and `repair` expect the caller to hold it.
+pub struct StateLock {
+    _file: std::fs::File,
+}
+
+impl StateLock {
+    /// Wait for the lock on `path`.
+    pub fn acquire(path: &Path) -> std::io::Result<Self> {
+        use std::os::unix::io::AsRawFd;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(sibling(path, ".lock"))?;
+        while unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } != 0 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+        Ok(Self { _file: file })
+    }
+
+    /// `acquire`, going ahead unlocked (with a warning) if locking fails,
+    /// e.g. on a filesystem without `flock`.
+    pub fn acquire_or_warn(path: &Path) -> Option<Self> {
+        Self::acquire(path)
+            .map_err(|e| eprintln!("[cwinner] locking {}: {e}", path.display()))
+            .ok()
     }
 }
 
@@ -403,10 +948,247 @@ mod tests {
         assert_eq!(loaded.level, 2);
     }
 
+    const V1_STATE: &str = r#"{
+        "xp": 1200, "level": 3, "level_name": "Vibe Architect",
+        "commits_total": 40, "commit_streak_days": 6,
+        "last_commit_date": "2026-10-01", "sessions_total": 12,
+        "achievements_unlocked": ["first_commit"], "tools_used": ["Bash"],
+        "last_event_at": null
+    }"#;
+
+    #[test]
+    fn test_unversioned_state_is_migrated_with_backup() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, V1_STATE).unwrap();
+
+        let s = State::load_or_recover(&path);
+        assert_eq!((s.xp, s.commit_streak_days), (1200, 6));
+        assert_eq!(s.schema_version, SCHEMA_VERSION);
+        assert_eq!(s.streak_timezone.as_deref(), Some("UTC"));
+
+        let backup = dir.path().join("state.json.v1.bak");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), V1_STATE);
+        let (saved, version) = State::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!((saved.xp, version), (1200, SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let s = State {
+            schema_version: SCHEMA_VERSION + 1,
+            ..Default::default()
+        };
+        let err = State::from_json(&serde_json::to_string(&s).unwrap()).unwrap_err();
+        assert!(err.to_string().contains("newer"), "{err}");
+        assert!(State::from_json(r#"{"schema_version": 0}"#).is_err());
+    }
+
+    fn write_state(path: &Path, xp: u32) {
+        let s = State {
+            xp,
+            ..Default::default()
+        };
+        std::fs::write(path, serde_json::to_string(&s).unwrap()).unwrap();
+    }
+
+    fn merge_pair() -> (State, State) {
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let mut ours = State {
+            commits_total: 30,
+            commit_streak_days: 4,
+            last_commit_date: Some(day(10)),
+            streak_timezone: Some("UTC".into()),
+            streak_history: vec![StreakDay {
+                date: day(10),
+                frozen: true,
+            }],
+            achievements_unlocked: vec!["first_commit".into()],
+            ..Default::default()
+        };
+        ours.add_xp(1000);
+        let mut theirs = State {
+            commits_total: 10,
+            commit_streak_days: 2,
+            last_commit_date: Some(day(12)),
+            streak_timezone: Some("UTC".into()),
+            streak_history: vec![
+                StreakDay {
+                    date: day(10),
+                    frozen: false,
+                },
+                StreakDay {
+                    date: day(12),
+                    frozen: false,
+                },
+            ],
+            achievements_unlocked: vec!["first_push".into(), "first_commit".into()],
+            ..Default::default()
+        };
+        theirs.add_xp(400);
+        theirs.record_tool_result(true);
+        (ours, theirs)
+    }
+
+    #[test]
+    fn test_merge_strategies() {
+        let (mut ours, theirs) = merge_pair();
+        ours.merge(theirs.clone(), Merge::Max);
+        assert_eq!((ours.xp, ours.commits_total), (1000, 30));
+        assert_eq!(
+            ours.achievements_unlocked,
+            vec!["first_commit", "first_push"]
+        );
+        // Their streak is more recent; a commit beats our freeze
+        assert_eq!(ours.commit_streak_days, 2);
+        assert_eq!(ours.streak_history.len(), 2);
+        assert!(!ours.streak_history[0].frozen);
+        assert_eq!(ours.streak_count(StreakKind::Tool), 1);
+
+        let (mut ours, _) = merge_pair();
+        ours.merge(theirs.clone(), Merge::Sum);
+        assert_eq!((ours.xp, ours.commits_total), (1400, 40));
+        let mut expected = State::default();
+        expected.add_xp(1400);
+        assert_eq!(ours.level, expected.level);
+
+        let (mut ours, _) = merge_pair();
+        ours.merge(theirs, Merge::Replace);
+        assert_eq!((ours.xp, ours.commits_total), (400, 10));
+        assert_eq!("sum".parse::<Merge>().unwrap(), Merge::Sum);
+        assert!("avg".parse::<Merge>().is_err());
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        State::update_at(&path, |s| s.add_xp(1));
+                    }
+                })
+            })
+            .collect();
+        for w in workers {
+            w.join().unwrap();
+        }
+        assert_eq!(State::load_from(&path).unwrap().xp, 100);
+    }
+
+    #[test]
+    fn test_saves_rotate_backups() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        State::default().save_to(&path).unwrap();
+        assert!(!State::rotating_backup(&path, 1).exists());
+        write_state(&path, 10);
+        State::default().save_to(&path).unwrap();
+        // The backup is recent, so the next save leaves it alone
+        write_state(&path, 20);
+        State::default().save_to(&path).unwrap();
+        assert_eq!(
+            State::load_from(&State::rotating_backup(&path, 1))
+                .unwrap()
+                .xp,
+            10
+        );
+        assert!(!State::rotating_backup(&path, 2).exists());
+    }
+
+    #[test]
+    fn test_corrupt_state_is_quarantined_not_reset() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        write_state(&State::rotating_backup(&path, 1), 900);
+        std::fs::write(&path, "{\"xp\": 12").unwrap();
+
+        let s = State::load_or_recover(&path);
+        assert_eq!(s.xp, 900);
+        assert!(!path.exists());
+        let quarantined = State::needs_repair(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&quarantined).unwrap(),
+            "{\"xp\": 12"
+        );
+
+        // Loading again keeps using the backup; saving is refused
+        assert_eq!(State::load_or_recover(&path).xp, 900);
+        let err = s.save_to(&path).unwrap_err();
+        assert!(err.to_string().contains("cwinner state repair"), "{err}");
+        assert!(!path.exists());
+        assert_eq!(
+            State::load_or_recover(&dir.path().join("missing.json")).xp,
+            0
+        );
+    }
+
+    #[test]
+    fn test_repair_restores_latest_save() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        assert_eq!(State::repair(&path, false).unwrap(), Repair::Healthy);
+        let age = |p: &Path, mins: u64| {
+            let at = std::time::SystemTime::now() - std::time::Duration::from_secs(mins * 60);
+            std::fs::File::options()
+                .write(true)
+                .open(p)
+                .unwrap()
+                .set_modified(at)
+                .unwrap();
+        };
+
+        // Backups from before an import that lowered XP
+        write_state(&State::rotating_backup(&path, 1), 900);
+        age(&State::rotating_backup(&path, 1), 10);
+        write_state(&State::rotating_backup(&path, 2), 950);
+        age(&State::rotating_backup(&path, 2), 20);
+        std::fs::write(&path, "garbage").unwrap();
+        State::load_or_recover(&path);
+        // Nothing newer is readable: the latest backup, not the most XP
+        assert_eq!(
+            State::repair(&path, false).unwrap(),
+            Repair::Restored {
+                from: State::rotating_backup(&path, 1),
+                xp: 900
+            }
+        );
+
+        std::fs::write(&path, "garbage").unwrap();
+        State::load_or_recover(&path);
+        let quarantined = State::needs_repair(&path).unwrap();
+        // The user fixes the quarantined file by hand
+        write_state(&quarantined, 300);
+        assert_eq!(
+            State::repair(&path, false).unwrap(),
+            Repair::Restored {
+                from: quarantined,
+                xp: 300
+            }
+        );
+        assert_eq!(State::needs_repair(&path), None);
+        assert_eq!(State::load_from(&path).unwrap().xp, 300);
+    }
+
+    #[test]
+    fn test_repair_without_readable_state_needs_fresh() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, "garbage").unwrap();
+        let err = State::repair(&path, false).unwrap_err();
+        assert!(err.to_string().contains("--fresh"), "{err}");
+        assert!(State::needs_repair(&path).is_some());
+        assert_eq!(State::repair(&path, true).unwrap(), Repair::Fresh);
+        assert_eq!(State::load_from(&path).unwrap().xp, 0);
+    }
+
     #[test]
     fn test_commit_streak() {
         let mut s = State::default();
-        let result = s.record_commit();
+        let result = s.record_commit(&StreakPolicy::new(DayZone::UTC));
         assert_eq!(s.commits_total, 1);
         assert_eq!(s.commit_streak_days, 1);
         assert!(result.first_today);
@@ -420,7 +1202,7 @@ mod tests {
         let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
         s.last_commit_date = Some(yesterday);
         s.commit_streak_days = 4;
-        let result = s.record_commit();
+        let result = s.record_commit(&StreakPolicy::new(DayZone::UTC));
         assert_eq!(s.commit_streak_days, 5);
         assert_eq!(result.streak_milestone, Some(5));
     }
@@ -431,7 +1213,7 @@ mod tests {
         let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
         s.last_commit_date = Some(yesterday);
         s.commit_streak_days = 9;
-        let result = s.record_commit();
+        let result = s.record_commit(&StreakPolicy::new(DayZone::UTC));
         assert_eq!(s.commit_streak_days, 10);
         assert_eq!(result.streak_milestone, Some(10));
     }
@@ -442,7 +1224,7 @@ mod tests {
         let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
         s.last_commit_date = Some(yesterday);
         s.commit_streak_days = 24;
-        let result = s.record_commit();
+        let result = s.record_commit(&StreakPolicy::new(DayZone::UTC));
         assert_eq!(s.commit_streak_days, 25);
         assert_eq!(result.streak_milestone, Some(25));
     }
@@ -453,7 +1235,7 @@ mod tests {
         let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
         s.last_commit_date = Some(yesterday);
         s.commit_streak_days = 99;
-        let result = s.record_commit();
+        let result = s.record_commit(&StreakPolicy::new(DayZone::UTC));
         assert_eq!(s.commit_streak_days, 100);
         assert_eq!(result.streak_milestone, Some(100));
     }
@@ -464,11 +1246,100 @@ mod tests {
         let yesterday = chrono::Utc::now().date_naive().pred_opt().unwrap();
         s.last_commit_date = Some(yesterday);
         s.commit_streak_days = 5;
-        let result = s.record_commit();
+        let result = s.record_commit(&StreakPolicy::new(DayZone::UTC));
         assert_eq!(s.commit_streak_days, 6);
         assert_eq!(result.streak_milestone, None);
     }
 
+    #[test]
+    fn test_streak_days_follow_local_timezone() {
+        let la = StreakPolicy::new(DayZone::resolve(Some("America/Los_Angeles")));
+        let at = |d: u32, h: u32| {
+            NaiveDate::from_ymd_opt(2026, 10, d)
+                .unwrap()
+                .and_hms_opt(h, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let mut s = State::default();
+        // 5 pm and 9 am the next morning in California, the same UTC day
+        s.record_commit_at(at(18, 0), &la);
+        let result = s.record_commit_at(at(18, 16), &la);
+        assert!(result.first_today);
+        assert_eq!(s.commit_streak_days, 2);
+        assert_eq!(s.streak_timezone.as_deref(), Some("America/Los_Angeles"));
+        // Late evening and just past midnight UTC are one day there
+        assert!(!s.record_commit_at(at(19, 1), &la).first_today);
+        assert_eq!(s.commit_streak_days, 2);
+    }
+
+    #[test]
+    fn test_legacy_utc_date_is_migrated() {
+        let d = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let mut s = State {
+            last_commit_date: Some(d),
+            commit_streak_days: 7,
+            ..Default::default()
+        };
+        s.set_day_zone(&DayZone::resolve(Some("Pacific/Kiritimati")));
+        assert_eq!(s.last_commit_date, d.succ_opt());
+        assert_eq!(s.streak_timezone.as_deref(), Some("Pacific/Kiritimati"));
+        // Saved state without the field loads as legacy UTC
+        let json = serde_json::to_value(State::default()).unwrap();
+        let mut obj = json.as_object().unwrap().clone();
+        obj.remove("streak_timezone");
+        let loaded: State = serde_json::from_value(obj.into()).unwrap();
+        assert_eq!(loaded.streak_timezone, None);
+    }
+
+    #[test]
+    fn test_freezes_cover_missed_days() {
+        let policy = StreakPolicy::new(DayZone::UTC);
+        let day = |d: u32| {
+            NaiveDate::from_ymd_opt(2026, 10, d)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let mut s = State::default();
+        for d in 1..=7 {
+            s.record_commit_at(day(d), &policy);
+        }
+        assert_eq!(s.commit_streak_days, 7);
+        assert_eq!(s.streak_freezes, 1);
+        // One missed day is covered
+        let result = s.record_commit_at(day(9), &policy);
+        assert_eq!(result.freezes_used, 1);
+        assert_eq!(s.commit_streak_days, 8);
+        assert_eq!(s.streak_freezes, 0);
+        assert!(s.streak_history.iter().any(|d| d.frozen));
+        // The next gap isn't
+        s.record_commit_at(day(11), &policy);
+        assert_eq!(s.commit_streak_days, 1);
+    }
+
+    #[test]
+    fn test_weekends_can_be_skipped() {
+        let policy = StreakPolicy {
+            skip_weekends: true,
+            max_freezes: 0,
+            ..StreakPolicy::new(DayZone::UTC)
+        };
+        let noon = |d: u32| {
+            NaiveDate::from_ymd_opt(2026, 10, d)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        let mut s = State::default();
+        s.record_commit_at(noon(16), &policy); // Friday
+        s.record_commit_at(noon(19), &policy); // Monday
+        assert_eq!(s.commit_streak_days, 2);
+        assert_eq!(s.streak_freezes, 0);
+    }
+
     #[test]
     fn test_tool_first_use() {
         let mut s = State::default();
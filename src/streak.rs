@@ -0,0 +1,518 @@
+//! Calendar days for streaks, counted in the user's timezone rather than UTC,
+//! and the rules for keeping a streak alive: weekends and freezes.
+
+use crate::config::StreakConfig;
+use crate::state::State;
+use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// A streak freeze is earned every this many streak days.
+pub const FREEZE_EVERY: u32 = 7;
+
+/// Days of history kept for `cwinner streak`.
+pub const HISTORY_DAYS: i64 = 56;
+
+/// The timezone streak days are counted in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayZone {
+    Named(Tz),
+    /// The system timezone, when its name can't be determined.
+    Local,
+}
+
+impl DayZone {
+    pub const UTC: DayZone = DayZone::Named(Tz::UTC);
+
+    /// `configured` (`streak.timezone`) if it names a timezone, otherwise the
+    /// system timezone.
+    pub fn resolve(configured: Option<&str>) -> Self {
+        if let Some(name) = configured {
+            match name.parse::<Tz>() {
+                Ok(tz) => return DayZone::Named(tz),
+                Err(_) => eprintln!(
+                    "[cwinnerd] unknown streak.timezone {name:?}, using the system timezone"
+                ),
+            }
+        }
+        iana_time_zone::get_timezone()
+            .ok()
+            .and_then(|name| name.parse().ok())
+            .map(DayZone::Named)
+            .unwrap_or(DayZone::Local)
+    }
+
+    /// Parse a name returned by `name`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "local" => Some(DayZone::Local),
+            name => name.parse().ok().map(DayZone::Named),
+        }
+    }
+
+    /// IANA name, or "local" for an unnamed system timezone.
+    pub fn name(&self) -> String {
+        match self {
+            DayZone::Named(tz) => tz.name().to_string(),
+            DayZone::Local => "local".to_string(),
+        }
+    }
+
+    /// The calendar date here at `at`.
+    pub fn date(&self, at: DateTime<Utc>) -> NaiveDate {
+        match self {
+            DayZone::Named(tz) => at.with_timezone(tz).date_naive(),
+            DayZone::Local => at.with_timezone(&Local).date_naive(),
+        }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date(Utc::now())
+    }
+
+    /// Midday of `date` here.
+    fn midday(&self, date: NaiveDate) -> DateTime<Utc> {
+        let noon = date.and_hms_opt(12, 0, 0).unwrap_or_default();
+        let utc = match self {
+            DayZone::Named(tz) => tz.from_local_datetime(&noon).earliest().map(|t| t.to_utc()),
+            DayZone::Local => Local
+                .from_local_datetime(&noon)
+                .earliest()
+                .map(|t| t.to_utc()),
+        };
+        utc.unwrap_or_else(|| noon.and_utc())
+    }
+
+    /// A date recorded in `from`, moved here: the date the middle of that day
+    /// falls on. Moves it by at most a day, so a streak survives the change.
+    pub fn convert(&self, date: NaiveDate, from: &DayZone) -> NaiveDate {
+        self.date(from.midday(date))
+    }
+}
+
+/// The kinds of streak tracked. Commit streaks live in `State`'s commit
+/// fields, with freezes; the rest in `State::streaks`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreakKind {
+    /// Days with a commit.
+    Commit,
+    /// Days with a Claude Code session.
+    Session,
+    /// Days with a passing test run.
+    Test,
+    /// Days with a push.
+    Push,
+    /// Tool calls in a row without a failure.
+    Tool,
+}
+
+impl StreakKind {
+    pub const ALL: [StreakKind; 5] = [
+        StreakKind::Commit,
+        StreakKind::Session,
+        StreakKind::Test,
+        StreakKind::Push,
+        StreakKind::Tool,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StreakKind::Commit => "commit",
+            StreakKind::Session => "session",
+            StreakKind::Test => "test",
+            StreakKind::Push => "push",
+            StreakKind::Tool => "tool",
+        }
+    }
+
+    /// Counts calendar days, rather than tool calls.
+    pub fn is_daily(self) -> bool {
+        self != StreakKind::Tool
+    }
+
+    /// Counts that trigger a streak celebration.
+    pub fn milestones(self) -> &'static [u32] {
+        match self {
+            StreakKind::Commit => crate::state::STREAK_MILESTONES,
+            StreakKind::Session | StreakKind::Test | StreakKind::Push => &[3, 7, 14, 30, 100],
+            StreakKind::Tool => &[25, 50, 100, 250, 500],
+        }
+    }
+
+    /// Celebration label for reaching `count`.
+    pub fn label(self, count: u32) -> String {
+        match self {
+            StreakKind::Tool => format!("🔥 {count} clean tool calls in a row"),
+            kind => format!("🔥 {count}-day {} streak", kind.name()),
+        }
+    }
+}
+
+/// A streak other than the commit streak.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Streak {
+    pub count: u32,
+    pub best: u32,
+    /// Last day counted, for daily streaks.
+    pub last_date: Option<NaiveDate>,
+}
+
+impl Streak {
+    /// Count `today`, continuing the streak if no required day was missed
+    /// since the last one. Returns the milestone reached, if any.
+    pub fn record_day(
+        &mut self,
+        kind: StreakKind,
+        today: NaiveDate,
+        policy: &StreakPolicy,
+    ) -> Option<u32> {
+        match self.last_date {
+            Some(last) if last == today => return None,
+            Some(last) if last < today && policy.missed_days(last, today).is_empty() => {
+                self.count += 1
+            }
+            _ => self.count = 1,
+        }
+        self.last_date = Some(today);
+        self.bump(kind)
+    }
+
+    /// Count one more in a row. Returns the milestone reached, if any.
+    pub fn extend(&mut self, kind: StreakKind) -> Option<u32> {
+        self.count += 1;
+        self.bump(kind)
+    }
+
+    fn bump(&mut self, kind: StreakKind) -> Option<u32> {
+        self.best = self.best.max(self.count);
+        kind.milestones()
+            .contains(&self.count)
+            .then_some(self.count)
+    }
+}
+
+/// A day in the commit streak: committed, or covered by a freeze.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StreakDay {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub frozen: bool,
+}
+
+/// How streak days are counted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreakPolicy {
+    pub zone: DayZone,
+    pub skip_weekends: bool,
+    pub max_freezes: u32,
+}
+
+impl StreakPolicy {
+    /// Every day counts, with the default freeze allowance.
+    pub fn new(zone: DayZone) -> Self {
+        Self {
+            zone,
+            skip_weekends: false,
+            max_freezes: StreakConfig::default().max_freezes,
+        }
+    }
+
+    pub fn from_config(cfg: &StreakConfig) -> Self {
+        Self {
+            zone: DayZone::resolve(cfg.timezone.as_deref()),
+            skip_weekends: cfg.skip_weekends,
+            max_freezes: cfg.max_freezes,
+        }
+    }
+
+    /// Whether missing `date` costs the streak.
+    pub fn is_required(&self, date: NaiveDate) -> bool {
+        !(self.skip_weekends && matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+    }
+
+    /// Required days strictly between `last` and `today`.
+    pub fn missed_days(&self, last: NaiveDate, today: NaiveDate) -> Vec<NaiveDate> {
+        last.iter_days()
+            .skip(1)
+            .take_while(|d| *d < today)
+            .filter(|d| self.is_required(*d))
+            .collect()
+    }
+
+    /// The last day a commit keeps alive a streak last extended on `last`,
+    /// spending up to `freezes` on the required days missed before it.
+    pub fn last_safe_day(&self, last: NaiveDate, freezes: u32) -> NaiveDate {
+        let mut budget = freezes;
+        for day in last.iter_days().skip(1) {
+            if self.is_required(day) {
+                if budget == 0 {
+                    return day;
+                }
+                budget -= 1;
+            }
+        }
+        NaiveDate::MAX
+    }
+}
+
+/// Mon–Sun rows for the last `weeks` weeks up to `today`: `■` committed,
+/// `❄` frozen, `·` missed, blank for days that didn't count, are to come, or
+/// predate the history.
+pub fn history_grid(
+    history: &[StreakDay],
+    policy: &StreakPolicy,
+    today: NaiveDate,
+    weeks: u32,
+) -> Vec<String> {
+    let monday = today.week(Weekday::Mon).first_day() - chrono::Days::new(7 * (weeks as u64 - 1));
+    let start = history.first().map_or(today, |d| d.date);
+    let mut rows = vec!["Mo Tu We Th Fr Sa Su".to_string()];
+    for week in 0..weeks {
+        let row: Vec<&str> = (0..7)
+            .map(|i| {
+                let day = monday + chrono::Days::new(u64::from(week * 7 + i));
+                match history.iter().find(|d| d.date == day) {
+                    Some(d) if d.frozen => "❄",
+                    Some(_) => "■",
+                    None if day < start || day >= today || !policy.is_required(day) => " ",
+                    None => "·",
+                }
+            })
+            .collect();
+        rows.push(row.join("  ").trim_end().to_string());
+    }
+    rows
+}
+
+/// `cwinner streak`: the current streak, freezes held, when it will break
+/// and the recent history.
+pub fn view(state: &State, policy: &StreakPolicy, today: NaiveDate) -> String {
+    let mut out = String::new();
+    let freezes = state.streak_freezes;
+    let safe = state
+        .last_commit_date
+        .map(|last| policy.last_safe_day(last, freezes));
+    match safe {
+        Some(safe) if today <= safe => {
+            let _ = writeln!(out, "🔥 Commit streak: {} days", state.commit_streak_days);
+            if state.last_commit_date == Some(today) {
+                let _ = writeln!(out, "  Committed today.");
+            }
+            let _ = writeln!(
+                out,
+                "  Commit by {} to keep it going.",
+                safe.format("%a %-d %b")
+            );
+        }
+        Some(safe) => {
+            let _ = writeln!(
+                out,
+                "Streak of {} days ended after {}; the next commit starts a new one.",
+                state.commit_streak_days,
+                safe.format("%a %-d %b")
+            );
+        }
+        None => {
+            let _ = writeln!(out, "No streak yet; commit to start one.");
+        }
+    }
+    if policy.max_freezes > 0 {
+        let _ = write!(out, "❄ Freezes: {freezes} of {}", policy.max_freezes);
+        if freezes < policy.max_freezes {
+            let next = (state.commit_streak_days / FREEZE_EVERY + 1) * FREEZE_EVERY;
+            let _ = write!(out, " (next at {next} days)");
+        }
+        let _ = writeln!(out);
+    }
+    if policy.skip_weekends {
+        let _ = writeln!(out, "  Weekends don't count.");
+    }
+    let _ = writeln!(out);
+    for row in history_grid(&state.streak_history, policy, today, 4) {
+        let _ = writeln!(out, "{}", format!("  {row}").trim_end());
+    }
+    if !state.streaks.is_empty() {
+        let _ = writeln!(out);
+    }
+    for (kind, streak) in &state.streaks {
+        // A daily streak that missed a required day is already over
+        let alive = match streak.last_date {
+            Some(last) if kind.is_daily() => {
+                last >= today || policy.missed_days(last, today).is_empty()
+            }
+            _ => true,
+        };
+        let count = if alive { streak.count } else { 0 };
+        let unit = if kind.is_daily() { "days" } else { "in a row" };
+        let _ = writeln!(
+            out,
+            "  {:<8}{count} {unit} (best {})",
+            kind.name(),
+            streak.best
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_resolve_configured_zone() {
+        let la = DayZone::resolve(Some("America/Los_Angeles"));
+        assert_eq!(la.name(), "America/Los_Angeles");
+        assert_eq!(DayZone::from_name(&la.name()), Some(la));
+        assert_eq!(DayZone::from_name("local"), Some(DayZone::Local));
+        assert_eq!(DayZone::from_name("Mars/Olympus"), None);
+        // An unknown name falls back to the system timezone
+        assert_eq!(
+            DayZone::resolve(Some("Mars/Olympus")),
+            DayZone::resolve(None)
+        );
+    }
+
+    #[test]
+    fn test_date_follows_zone() {
+        // 01:00 UTC is still the previous evening in California
+        let at = ymd(2026, 10, 18).and_hms_opt(1, 0, 0).unwrap().and_utc();
+        let la = DayZone::resolve(Some("America/Los_Angeles"));
+        assert_eq!(la.date(at), ymd(2026, 10, 17));
+        assert_eq!(DayZone::UTC.date(at), ymd(2026, 10, 18));
+    }
+
+    #[test]
+    fn test_missed_days_skip_weekends() {
+        let mut policy = StreakPolicy::new(DayZone::UTC);
+        // Fri 2026-10-16 → Tue 2026-10-20
+        let (fri, tue) = (ymd(2026, 10, 16), ymd(2026, 10, 20));
+        assert_eq!(policy.missed_days(fri, tue).len(), 3);
+        policy.skip_weekends = true;
+        assert_eq!(policy.missed_days(fri, tue), vec![ymd(2026, 10, 19)]);
+        assert!(policy.missed_days(fri, fri.succ_opt().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_last_safe_day() {
+        let mut policy = StreakPolicy::new(DayZone::UTC);
+        let fri = ymd(2026, 10, 16);
+        assert_eq!(policy.last_safe_day(fri, 0), ymd(2026, 10, 17));
+        assert_eq!(policy.last_safe_day(fri, 2), ymd(2026, 10, 19));
+        policy.skip_weekends = true;
+        assert_eq!(policy.last_safe_day(fri, 0), ymd(2026, 10, 19));
+        assert_eq!(policy.last_safe_day(fri, 1), ymd(2026, 10, 20));
+    }
+
+    #[test]
+    fn test_view() {
+        let policy = StreakPolicy::new(DayZone::UTC);
+        let today = ymd(2026, 10, 18); // a Sunday
+        let history = [
+            StreakDay {
+                date: ymd(2026, 10, 15),
+                frozen: false,
+            },
+            StreakDay {
+                date: ymd(2026, 10, 16),
+                frozen: true,
+            },
+            StreakDay {
+                date: ymd(2026, 10, 17),
+                frozen: false,
+            },
+        ];
+        let mut state = State {
+            commit_streak_days: 8,
+            last_commit_date: Some(ymd(2026, 10, 17)),
+            streak_freezes: 1,
+            streak_history: history.to_vec(),
+            ..Default::default()
+        };
+        let text = view(&state, &policy, today);
+        let expected = "\
+🔥 Commit streak: 8 days
+  Commit by Mon 19 Oct to keep it going.
+❄ Freezes: 1 of 2 (next at 14 days)
+
+  Mo Tu We Th Fr Sa Su
+
+
+
+           ■  ❄  ■
+";
+        assert_eq!(text, expected);
+
+        state.streaks.insert(
+            StreakKind::Test,
+            Streak {
+                count: 4,
+                best: 9,
+                last_date: Some(ymd(2026, 10, 17)),
+            },
+        );
+        state.streaks.insert(
+            StreakKind::Push,
+            Streak {
+                count: 3,
+                best: 3,
+                last_date: Some(ymd(2026, 10, 12)),
+            },
+        );
+        let text = view(&state, &policy, today);
+        assert!(
+            text.ends_with("\n\n  test    4 days (best 9)\n  push    0 days (best 3)\n"),
+            "{text}"
+        );
+
+        let broken = view(&state, &policy, ymd(2026, 10, 21));
+        assert!(
+            broken.starts_with("Streak of 8 days ended after Mon 19 Oct"),
+            "{broken}"
+        );
+    }
+
+    #[test]
+    fn test_daily_streak_counts_each_day_once() {
+        let policy = StreakPolicy::new(DayZone::UTC);
+        let mut streak = Streak::default();
+        let kind = StreakKind::Test;
+        assert_eq!(streak.record_day(kind, ymd(2026, 10, 1), &policy), None);
+        assert_eq!(streak.record_day(kind, ymd(2026, 10, 1), &policy), None);
+        assert_eq!(streak.record_day(kind, ymd(2026, 10, 2), &policy), None);
+        assert_eq!(streak.record_day(kind, ymd(2026, 10, 3), &policy), Some(3));
+        assert_eq!(streak.count, 3);
+        streak.record_day(kind, ymd(2026, 10, 5), &policy);
+        assert_eq!((streak.count, streak.best), (1, 3));
+    }
+
+    #[test]
+    fn test_tool_streak_milestones() {
+        let mut streak = Streak::default();
+        let hits: Vec<u32> = (0..60)
+            .filter_map(|_| streak.extend(StreakKind::Tool))
+            .collect();
+        assert_eq!(hits, vec![25, 50]);
+        assert_eq!(
+            StreakKind::Tool.label(50),
+            "🔥 50 clean tool calls in a row"
+        );
+        assert_eq!(StreakKind::Push.label(7), "🔥 7-day push streak");
+    }
+
+    #[test]
+    fn test_convert_moves_at_most_a_day() {
+        let d = ymd(2026, 3, 1);
+        let la = DayZone::resolve(Some("America/Los_Angeles"));
+        let kiritimati = DayZone::resolve(Some("Pacific/Kiritimati"));
+        assert_eq!(la.convert(d, &DayZone::UTC), d);
+        assert_eq!(kiritimati.convert(d, &DayZone::UTC), ymd(2026, 3, 2));
+        assert_eq!(DayZone::UTC.convert(d, &DayZone::UTC), d);
+    }
+}
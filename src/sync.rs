@@ -0,0 +1,673 @@
+//! Progress shared between machines through a synced folder (`[sync] dir`).
+//!
+//! Each machine appends what every event earned to its own journal shard,
+//! `<machine>.jsonl`, and never touches the others, so the sync tool never
+//! sees a conflict. The combined state folds each shard into that machine's
+//! progress and merges them: XP and counters summed, achievements and tools
+//! united, and each streak taken from the machine that extended it last.
+
+use crate::config::SyncConfig;
+use crate::state::{Merge, State};
+use crate::streak::{HISTORY_DAYS, Streak, StreakDay, StreakKind};
+use anyhow::Context;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+/// A shard is rewritten as a single entry once it grows past this many.
+const COMPACT_AFTER: usize = 2000;
+
+/// The commit streak as it stood after an event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CommitStreak {
+    days: u32,
+    last_date: Option<NaiveDate>,
+    timezone: Option<String>,
+    freezes: u32,
+}
+
+impl CommitStreak {
+    fn of(s: &State) -> Self {
+        Self {
+            days: s.commit_streak_days,
+            last_date: s.last_commit_date,
+            timezone: s.streak_timezone.clone(),
+            freezes: s.streak_freezes,
+        }
+    }
+}
+
+/// The streak changes one event made. The tool streak is left out: clean
+/// tool calls in a row only mean something on the machine making them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Streaks {
+    /// Set when the commit streak changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit: Option<CommitStreak>,
+    /// History days added or changed. When the timezone changed, all of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<StreakDay>,
+    /// Daily streaks that changed.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    kinds: BTreeMap<StreakKind, Streak>,
+}
+
+impl Streaks {
+    fn diff(before: &State, after: &State) -> Option<Self> {
+        let commit = CommitStreak::of(after);
+        let rezoned = after.streak_timezone != before.streak_timezone;
+        let streaks = Self {
+            history: after
+                .streak_history
+                .iter()
+                .filter(|d| rezoned || !before.streak_history.contains(d))
+                .cloned()
+                .collect(),
+            kinds: after
+                .streaks
+                .iter()
+                .filter(|&(&kind, streak)| {
+                    kind.is_daily() && before.streaks.get(&kind) != Some(streak)
+                })
+                .map(|(&kind, streak)| (kind, streak.clone()))
+                .collect(),
+            commit: Some(commit).filter(|c| *c != CommitStreak::of(before)),
+        };
+        (streaks != Self::default()).then_some(streaks)
+    }
+
+    fn apply(self, s: &mut State) {
+        if let Some(c) = self.commit {
+            // History days are counted in the streak's timezone
+            if c.timezone != s.streak_timezone {
+                s.streak_history.clear();
+            }
+            s.commit_streak_days = c.days;
+            s.last_commit_date = c.last_date;
+            s.streak_timezone = c.timezone;
+            s.streak_freezes = c.freezes;
+        }
+        for day in self.history {
+            match s.streak_history.iter_mut().find(|d| d.date == day.date) {
+                Some(ours) => *ours = day,
+                None => s.streak_history.push(day),
+            }
+        }
+        s.streak_history.sort_by_key(|d| d.date);
+        if let Some(newest) = s.streak_history.last().map(|d| d.date) {
+            let oldest = newest - chrono::Days::new(HISTORY_DAYS as u64);
+            s.streak_history.retain(|d| d.date > oldest);
+        }
+        s.streaks.extend(self.kinds);
+    }
+}
+
+/// What one event (or a compacted run of them) added on one machine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    at: DateTime<Utc>,
+    #[serde(default)]
+    xp: u32,
+    #[serde(default)]
+    commits: u32,
+    #[serde(default)]
+    sessions: u32,
+    #[serde(default)]
+    achievements: Vec<String>,
+    #[serde(default)]
+    tools: Vec<String>,
+    /// Set when the streaks changed.
+    #[serde(default)]
+    streaks: Option<Streaks>,
+}
+
+impl Entry {
+    /// The progress made going from `before` to `after`, if any.
+    pub fn diff(before: &State, after: &State, at: DateTime<Utc>) -> Option<Self> {
+        let entry = Self {
+            at,
+            xp: after.xp.saturating_sub(before.xp),
+            commits: after.commits_total.saturating_sub(before.commits_total),
+            sessions: after.sessions_total.saturating_sub(before.sessions_total),
+            achievements: after
+                .achievements_unlocked
+                .iter()
+                .filter(|a| !before.achievements_unlocked.contains(a))
+                .cloned()
+                .collect(),
+            tools: after
+                .tools_used
+                .difference(&before.tools_used)
+                .cloned()
+                .collect(),
+            streaks: Streaks::diff(before, after),
+        };
+        let empty = Self {
+            at,
+            xp: 0,
+            commits: 0,
+            sessions: 0,
+            achievements: vec![],
+            tools: vec![],
+            streaks: None,
+        };
+        (entry != empty).then_some(entry)
+    }
+}
+
+/// What a machine had earned before it first synced, kept in its local
+/// state. Once synced, the state also holds the other machines' progress,
+/// so this is all that may be seeded into a new shard again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncSeed {
+    /// Machine whose shard holds the progress.
+    pub machine: String,
+    pub progress: Option<Entry>,
+}
+
+/// One machine's progress: its shard's entries added up.
+pub fn fold(entries: &[Entry]) -> State {
+    let mut s = State::default();
+    fold_onto(&mut s, entries);
+    s
+}
+
+/// Add `entries` to the progress folded so far.
+fn fold_onto(s: &mut State, entries: &[Entry]) {
+    for e in entries {
+        s.add_xp(e.xp);
+        s.commits_total = s.commits_total.saturating_add(e.commits);
+        s.sessions_total = s.sessions_total.saturating_add(e.sessions);
+        for a in &e.achievements {
+            s.unlock_achievement(a);
+        }
+        s.tools_used.extend(e.tools.iter().cloned());
+        if let Some(streaks) = &e.streaks {
+            streaks.clone().apply(s);
+        }
+        s.last_event_at = s.last_event_at.max(Some(e.at));
+    }
+}
+
+/// Parse shard lines, skipping those that don't parse (e.g. one the sync
+/// tool is still writing).
+fn parse_lines(data: &[u8]) -> Vec<Entry> {
+    data.split(|&b| b == b'\n')
+        .filter_map(|line| serde_json::from_slice(line).ok())
+        .collect()
+}
+
+fn read_shard(path: &Path) -> std::io::Result<Vec<Entry>> {
+    Ok(parse_lines(&std::fs::read(path)?))
+}
+
+/// A shard as folded when it was last read.
+struct Folded {
+    inode: u64,
+    len: u64,
+    modified: SystemTime,
+    /// Bytes up to the end of the last whole line.
+    consumed: u64,
+    entries: usize,
+    state: State,
+}
+
+/// Folded shards, so an event only reads what was appended since the last.
+static FOLDED: LazyLock<Mutex<HashMap<PathBuf, Folded>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A shard's progress and how many entries it has. Only whole lines count,
+/// so a line still being written is picked up once it's complete.
+fn fold_shard(path: &Path) -> std::io::Result<(State, usize)> {
+    let meta = std::fs::metadata(path)?;
+    let modified = meta.modified()?;
+    let cached = FOLDED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(path)
+        .filter(|f| f.inode == meta.ino() && f.len <= meta.len());
+    let folded = match cached {
+        Some(f) if f.len == meta.len() && f.modified == modified => f,
+        // Grown: fold what was appended. Rewritten (new inode) or shrunk: refold.
+        cached => {
+            let mut f = cached.unwrap_or_else(|| Folded {
+                inode: meta.ino(),
+                len: 0,
+                modified,
+                consumed: 0,
+                entries: 0,
+                state: State::default(),
+            });
+            let mut file = std::fs::File::open(path)?;
+            file.seek(SeekFrom::Start(f.consumed))?;
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            let whole = data.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            let entries = parse_lines(&data[..whole]);
+            fold_onto(&mut f.state, &entries);
+            f.entries += entries.len();
+            f.len = f.consumed + data.len() as u64;
+            f.consumed += whole as u64;
+            f.modified = modified;
+            f
+        }
+    };
+    let out = (folded.state.clone(), folded.entries);
+    FOLDED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.to_path_buf(), folded);
+    Ok(out)
+}
+
+/// The shards in a sync folder, and which one is this machine's.
+#[derive(Debug, Clone)]
+pub struct Shards {
+    dir: PathBuf,
+    machine: String,
+    /// Own shard is rewritten as a single entry past this many.
+    compact_after: usize,
+}
+
+impl Shards {
+    pub fn new(dir: impl Into<PathBuf>, machine: &str) -> Self {
+        // Keep the name usable as a file name everywhere
+        let machine = machine
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        Self {
+            dir: dir.into(),
+            machine,
+            compact_after: COMPACT_AFTER,
+        }
+    }
+
+    /// The configured sync folder, if sync is on.
+    pub fn from_config(cfg: &SyncConfig) -> Option<Self> {
+        let dir = cfg.dir.as_deref()?;
+        let dir = match dir.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir()?.join(rest),
+            None => PathBuf::from(dir),
+        };
+        let machine = cfg.machine.clone().unwrap_or_else(hostname);
+        Some(Self::new(dir, &machine))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn own_shard(&self) -> PathBuf {
+        self.shard(&self.machine)
+    }
+
+    fn shard(&self, machine: &str) -> PathBuf {
+        self.dir.join(format!("{machine}.jsonl"))
+    }
+
+    /// Every machine's progress merged.
+    pub fn combined(&self) -> anyhow::Result<State> {
+        Ok(self.read_all()?.0)
+    }
+
+    /// `combined`, and how many entries this machine's shard has.
+    fn read_all(&self) -> anyhow::Result<(State, usize)> {
+        let mut combined = State::default();
+        let mut own = 0;
+        let listing = std::fs::read_dir(&self.dir)
+            .with_context(|| format!("reading {}", self.dir.display()))?;
+        let mut shards: Vec<PathBuf> = listing
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|x| x == "jsonl"))
+            .collect();
+        // Same result on every machine, whatever order the folder lists in
+        shards.sort();
+        for shard in shards {
+            let (folded, entries) =
+                fold_shard(&shard).with_context(|| format!("{}", shard.display()))?;
+            if shard == self.own_shard() {
+                own = entries;
+            }
+            combined.merge(folded, Merge::Sum);
+        }
+        Ok((combined, own))
+    }
+
+    /// Append an entry to this machine's shard.
+    fn append(&self, entry: &Entry) -> anyhow::Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.own_shard())?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Rewrite this machine's shard as one entry.
+    fn compact(&self) -> anyhow::Result<()> {
+        let path = self.own_shard();
+        let entries = read_shard(&path)?;
+        let at = entries.last().map_or_else(Utc::now, |e| e.at);
+        let Some(summary) = Entry::diff(&State::default(), &fold(&entries), at) else {
+            return Ok(());
+        };
+        let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_string(&summary)? + "\n")?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Start this machine's shard with its progress from before it first
+    /// synced: everything in `state` on the first sync, and the recorded
+    /// seed if its shard has gone since. A renamed machine's old shard still
+    /// counts the seed, so nothing is added then.
+    fn seed(&self, state: &mut State, now: DateTime<Utc>) -> anyhow::Result<()> {
+        let seed = match state.sync_seed.clone() {
+            None => SyncSeed {
+                machine: self.machine.clone(),
+                progress: Entry::diff(&State::default(), state, now),
+            },
+            Some(seed) if seed.machine != self.machine && self.shard(&seed.machine).exists() => {
+                return Ok(());
+            }
+            Some(seed) => SyncSeed {
+                machine: self.machine.clone(),
+                ..seed
+            },
+        };
+        if let Some(progress) = &seed.progress {
+            self.append(progress)?;
+        }
+        state.sync_seed = Some(seed);
+        Ok(())
+    }
+
+    /// Run `f` against the combined state, recording what it earned in this
+    /// machine's shard. On the first sync, progress made before is recorded
+    /// too. `state` ends up as the combined state plus `f`'s progress.
+    pub fn apply<T>(&self, state: &mut State, f: impl FnOnce(&mut State) -> T) -> T {
+        let now = Utc::now();
+        let synced = std::fs::create_dir_all(&self.dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| {
+                if !self.own_shard().exists() {
+                    self.seed(state, now)?;
+                }
+                self.read_all()
+            });
+        let (combined, own) = match synced {
+            Ok(read) => read,
+            Err(e) => {
+                eprintln!("[cwinner] sync: {e:#}; using local progress");
+                return f(state);
+            }
+        };
+        // Fields shards don't carry stay as they are locally
+        let local = std::mem::replace(state, combined);
+        match local.streaks.get(&StreakKind::Tool) {
+            Some(tool) => state.streaks.insert(StreakKind::Tool, tool.clone()),
+            None => state.streaks.remove(&StreakKind::Tool),
+        };
+        state.keep_local(local);
+        let before = state.clone();
+        let out = f(state);
+        if let Some(entry) = Entry::diff(&before, state, now) {
+            let result = self.append(&entry).and_then(|()| {
+                if own + 1 > self.compact_after {
+                    self.compact()
+                } else {
+                    Ok(())
+                }
+            });
+            if let Err(e) = result {
+                eprintln!("[cwinner] sync: {e:#}");
+            }
+        }
+        out
+    }
+}
+
+/// This machine's hostname, or "local" if it can't be read.
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    match std::str::from_utf8(&buf[..len]) {
+        Ok(name) if rc == 0 && !name.is_empty() => name.to_string(),
+        _ => "local".to_string(),
+    }
+}
+
+/// Names of the machines with a shard in the sync folder.
+pub fn machines(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|x| x == "jsonl"))
+        .filter_map(|p| Some(p.file_stem()?.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn earn(state: &mut State, xp: u32, achievement: Option<&str>) {
+        state.add_xp(xp);
+        state.commits_total += 1;
+        if let Some(a) = achievement {
+            state.unlock_achievement(a);
+        }
+    }
+
+    #[test]
+    fn test_machines_sum_xp_and_unite_achievements() {
+        let dir = tempfile::tempdir().unwrap();
+        let laptop = Shards::new(dir.path(), "laptop");
+        let wsl = Shards::new(dir.path(), "wsl box");
+
+        let mut on_laptop = State::default();
+        laptop.apply(&mut on_laptop, |s| earn(s, 100, Some("first_commit")));
+        let mut on_wsl = State::default();
+        wsl.apply(&mut on_wsl, |s| earn(s, 50, Some("first_commit")));
+        wsl.apply(&mut on_wsl, |s| earn(s, 25, Some("night_owl")));
+
+        // The laptop picks up the WSL box's progress on its next event
+        laptop.apply(&mut on_laptop, |s| earn(s, 5, None));
+        assert_eq!(on_laptop.xp, 180);
+        assert_eq!(on_laptop.commits_total, 4);
+        assert_eq!(
+            on_laptop.achievements_unlocked,
+            vec!["first_commit", "night_owl"]
+        );
+        assert!(dir.path().join("wsl-box.jsonl").exists());
+        assert_eq!(machines(dir.path()), vec!["laptop", "wsl-box"]);
+        assert_eq!(wsl.combined().unwrap().xp, 180);
+    }
+
+    #[test]
+    fn test_first_sync_seeds_existing_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let shards = Shards::new(dir.path(), "laptop");
+        let mut state = State::default();
+        earn(&mut state, 700, Some("first_commit"));
+
+        shards.apply(&mut state, |s| s.add_xp(5));
+        assert_eq!(state.xp, 705);
+        assert_eq!(shards.combined().unwrap().xp, 705);
+        let lines = std::fs::read_to_string(shards.own_shard()).unwrap();
+        assert_eq!(lines.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_renamed_machine_does_not_seed_others_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let laptop = Shards::new(dir.path(), "laptop");
+        let desktop = Shards::new(dir.path(), "desktop");
+        let mut on_desktop = State::default();
+        desktop.apply(&mut on_desktop, |s| earn(s, 300, None));
+        let mut state = State::default();
+        earn(&mut state, 700, Some("first_commit"));
+        laptop.apply(&mut state, |s| s.add_xp(5));
+        assert_eq!(state.xp, 1005);
+
+        // The hostname changed: the old shard still counts the laptop's progress
+        let renamed = Shards::new(dir.path(), "laptop-2");
+        renamed.apply(&mut state, |_| ());
+        assert_eq!(state.xp, 1005);
+        renamed.apply(&mut state, |s| s.add_xp(1));
+        assert_eq!(renamed.combined().unwrap().xp, 1006);
+
+        // Shards deleted: only the laptop's own pre-sync progress comes back
+        std::fs::remove_file(laptop.own_shard()).unwrap();
+        std::fs::remove_file(renamed.own_shard()).unwrap();
+        renamed.apply(&mut state, |_| ());
+        assert_eq!(state.xp, 1000);
+        assert_eq!(state.achievements_unlocked, vec!["first_commit"]);
+    }
+
+    #[test]
+    fn test_latest_streak_wins_and_torn_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = Shards::new(dir.path(), "a");
+        let b = Shards::new(dir.path(), "b");
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d);
+
+        let mut on_a = State::default();
+        a.apply(&mut on_a, |s| {
+            s.commit_streak_days = 9;
+            s.last_commit_date = day(10);
+        });
+        let mut on_b = State::default();
+        b.apply(&mut on_b, |s| {
+            // b saw a's streak and extended it
+            assert_eq!(s.commit_streak_days, 9);
+            s.commit_streak_days = 10;
+            s.last_commit_date = day(11);
+        });
+        // A torn write from the sync tool
+        let mut shard = std::fs::OpenOptions::new()
+            .append(true)
+            .open(a.own_shard())
+            .unwrap();
+        write!(shard, "{{\"at\": \"2026-10-").unwrap();
+
+        let combined = a.combined().unwrap();
+        assert_eq!(combined.commit_streak_days, 10);
+        assert_eq!(combined.last_commit_date, day(11));
+    }
+
+    #[test]
+    fn test_tool_streak_stays_local_and_streaks_are_deltas() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = Shards::new(dir.path(), "a");
+        let day = |d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let mut state = State::default();
+        a.apply(&mut state, |s| {
+            s.streak_history = (1..=20)
+                .map(|d| StreakDay {
+                    date: day(d),
+                    frozen: false,
+                })
+                .collect();
+        });
+        for _ in 0..3 {
+            a.apply(&mut state, |s| s.record_tool_result(true));
+        }
+        assert_eq!(state.streaks[&StreakKind::Tool].count, 3);
+        a.apply(&mut state, |s| {
+            s.streak_history.push(StreakDay {
+                date: day(21),
+                frozen: true,
+            })
+        });
+
+        let entries = read_shard(&a.own_shard()).unwrap();
+        // Tool calls changed nothing shared
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].streaks.as_ref().unwrap().history.len(), 1);
+        let combined = a.combined().unwrap();
+        assert_eq!(combined.streak_history.len(), 21);
+        assert!(!combined.streaks.contains_key(&StreakKind::Tool));
+    }
+
+    #[test]
+    fn test_appended_and_completed_lines_are_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = Shards::new(dir.path(), "a");
+        let mut state = State::default();
+        a.apply(&mut state, |s| s.add_xp(10));
+        assert_eq!(a.combined().unwrap().xp, 10);
+
+        // Another machine's line arrives in two writes
+        let line = serde_json::to_string(
+            &Entry::diff(
+                &State::default(),
+                &{
+                    let mut s = State::default();
+                    s.add_xp(7);
+                    s
+                },
+                Utc::now(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let (head, tail) = line.split_at(line.len() / 2);
+        let b = dir.path().join("b.jsonl");
+        std::fs::write(&b, head).unwrap();
+        assert_eq!(a.combined().unwrap().xp, 10);
+        let mut file = std::fs::OpenOptions::new().append(true).open(&b).unwrap();
+        writeln!(file, "{tail}").unwrap();
+        assert_eq!(a.combined().unwrap().xp, 17);
+        writeln!(file, "{line}").unwrap();
+        assert_eq!(a.combined().unwrap().xp, 24);
+    }
+
+    #[test]
+    fn test_compaction_keeps_totals() {
+        let dir = tempfile::tempdir().unwrap();
+        let shards = Shards {
+            compact_after: 10,
+            ..Shards::new(dir.path(), "laptop")
+        };
+        let mut state = State::default();
+        for _ in 0..10 {
+            shards.apply(&mut state, |s| s.add_xp(1));
+        }
+        let lines = std::fs::read_to_string(shards.own_shard()).unwrap();
+        assert_eq!(lines.lines().count(), 10);
+        shards.apply(&mut state, |s| s.add_xp(1));
+        let lines = std::fs::read_to_string(shards.own_shard()).unwrap();
+        assert_eq!(lines.lines().count(), 1);
+        assert_eq!(shards.combined().unwrap().xp, 11);
+        shards.apply(&mut state, |s| s.add_xp(1));
+        assert_eq!(shards.combined().unwrap().xp, 12);
+    }
+
+    #[test]
+    fn test_missing_folder_falls_back_to_local() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("not-a-dir");
+        std::fs::write(&file, "").unwrap();
+        let shards = Shards::new(&file, "laptop");
+        let mut state = State::default();
+        shards.apply(&mut state, |s| s.add_xp(5));
+        assert_eq!(state.xp, 5);
+    }
+}
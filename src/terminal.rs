@@ -0,0 +1,242 @@
+use crate::config::ColorDepth;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// `TERM` values whose fonts can't show box drawing or emoji reliably: the
+/// Linux console and serial/hardware terminals.
+const LIMITED_TERMS: &[&str] = &["linux", "vt100", "vt102", "vt220", "ansi", "cons25", "dumb"];
+
+/// The parts of a terminal's environment that decide how to draw in it. The
+/// hook captures these so the daemon can render for the user's terminal
+/// rather than its own environment.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TermEnv {
+    pub term: Option<String>,
+    pub colorterm: Option<String>,
+    /// First non-empty of `LC_ALL`, `LC_CTYPE`, `LANG`.
+    pub locale: Option<String>,
+    /// `NO_COLOR` is set to a non-empty value (see no-color.org).
+    pub no_color: bool,
+    /// `TMUX`: server socket, pid and session, when running inside tmux.
+    pub tmux: Option<String>,
+    pub tmux_pane: Option<String>,
+}
+
+impl TermEnv {
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self {
+            term: var("TERM"),
+            colorterm: var("COLORTERM"),
+            locale: var("LC_ALL")
+                .or_else(|| var("LC_CTYPE"))
+                .or_else(|| var("LANG")),
+            no_color: var("NO_COLOR").is_some(),
+            tmux: var("TMUX"),
+            tmux_pane: var("TMUX_PANE"),
+        }
+    }
+
+    /// Add to hook event metadata.
+    pub fn to_metadata(&self, metadata: &mut HashMap<String, Value>) {
+        let fields = [
+            ("term", &self.term),
+            ("colorterm", &self.colorterm),
+            ("locale", &self.locale),
+            ("tmux", &self.tmux),
+            ("tmux_pane", &self.tmux_pane),
+        ];
+        for (key, value) in fields {
+            if let Some(v) = value {
+                metadata.insert(key.into(), Value::from(v.as_str()));
+            }
+        }
+        metadata.insert("no_color".into(), Value::from(self.no_color));
+    }
+
+    /// Read back from hook event metadata. None for events from hooks that
+    /// predate capability detection.
+    pub fn from_metadata(metadata: &HashMap<String, Value>) -> Option<Self> {
+        let no_color = metadata.get("no_color")?.as_bool().unwrap_or(false);
+        let text = |key: &str| metadata.get(key).and_then(|v| v.as_str()).map(String::from);
+        Some(Self {
+            term: text("term"),
+            colorterm: text("colorterm"),
+            locale: text("locale"),
+            no_color,
+            tmux: text("tmux"),
+            tmux_pane: text("tmux_pane"),
+        })
+    }
+}
+
+/// What a terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TermCaps {
+    /// Box drawing, symbols and emoji; otherwise draw with ASCII only.
+    pub unicode: bool,
+    /// Any color at all; otherwise emit no color escape codes.
+    pub color: bool,
+    pub depth: ColorDepth,
+}
+
+impl TermCaps {
+    pub fn detect(env: &TermEnv) -> Self {
+        let term = env.term.as_deref();
+        let dumb = term == Some("dumb");
+        let utf8 = env.locale.as_deref().is_some_and(|l| {
+            let l = l.to_ascii_lowercase();
+            l.contains("utf-8") || l.contains("utf8")
+        });
+        Self {
+            unicode: utf8 && !term.is_some_and(|t| LIMITED_TERMS.contains(&t)),
+            color: !env.no_color && !dumb,
+            depth: detect_color_depth(env.colorterm.as_deref(), term),
+        }
+    }
+
+    /// Capabilities of the terminal this process is running in.
+    pub fn current() -> Self {
+        TermCaps::detect(&TermEnv::from_env())
+    }
+}
+
+/// Best color depth for a terminal from `COLORTERM` and `TERM`.
+pub fn detect_color_depth(colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+    if matches!(colorterm, Some("truecolor" | "24bit")) {
+        ColorDepth::Truecolor
+    } else if term.is_some_and(|t| t.contains("256color")) {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// ASCII stand-ins for the symbols cwinner itself prints.
+const ASCII_FALLBACKS: &[(char, &str)] = &[
+    ('—', "-"),
+    ('–', "-"),
+    ('│', "|"),
+    ('·', "-"),
+    ('…', "..."),
+    ('→', "->"),
+    ('✓', "+"),
+    ('✦', "*"),
+    ('★', "*"),
+    ('❄', "*"),
+    ('🏆', "[*]"),
+    ('🎁', "[*]"),
+    ('⚡', ">>"),
+    ('📝', "*"),
+    ('🚀', "^"),
+    ('👋', "~"),
+    ('🎯', "(o)"),
+    ('🔕', "(z)"),
+];
+
+/// Reduce text to printable ASCII: known symbols get a stand-in, anything
+/// else non-ASCII (emoji, variation selectors) is dropped.
+pub fn to_ascii(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else if let Some((_, repl)) = ASCII_FALLBACKS.iter().find(|(k, _)| *k == c) {
+            out.push_str(repl);
+        }
+    }
+    // Dropped emoji leave stray spaces behind ("📊 Stats" → " Stats")
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(term: &str, locale: Option<&str>) -> TermEnv {
+        TermEnv {
+            term: Some(term.into()),
+            colorterm: None,
+            locale: locale.map(String::from),
+            no_color: false,
+            tmux: None,
+            tmux_pane: None,
+        }
+    }
+
+    #[test]
+    fn test_detect_modern_terminal() {
+        let caps = TermCaps::detect(&TermEnv {
+            colorterm: Some("truecolor".into()),
+            ..env("xterm-256color", Some("en_US.UTF-8"))
+        });
+        assert!(caps.unicode && caps.color);
+        assert_eq!(caps.depth, ColorDepth::Truecolor);
+    }
+
+    #[test]
+    fn test_detect_limited_terminals() {
+        // Linux console: colors yes, emoji no
+        let caps = TermCaps::detect(&env("linux", Some("en_US.UTF-8")));
+        assert!(!caps.unicode);
+        assert!(caps.color);
+        assert_eq!(caps.depth, ColorDepth::Ansi16);
+        // C locale over a serial line
+        assert!(!TermCaps::detect(&env("vt220", None)).unicode);
+        assert!(!TermCaps::detect(&env("screen", Some("C"))).unicode);
+        assert!(TermCaps::detect(&env("screen", Some("C.utf8"))).unicode);
+        let dumb = TermCaps::detect(&env("dumb", Some("en_US.UTF-8")));
+        assert!(!dumb.unicode && !dumb.color);
+    }
+
+    #[test]
+    fn test_no_color() {
+        let caps = TermCaps::detect(&TermEnv {
+            no_color: true,
+            ..env("xterm-256color", Some("en_US.UTF-8"))
+        });
+        assert!(!caps.color);
+        assert!(caps.unicode);
+    }
+
+    #[test]
+    fn test_detect_color_depth() {
+        assert_eq!(
+            detect_color_depth(Some("truecolor"), Some("xterm")),
+            ColorDepth::Truecolor
+        );
+        assert_eq!(
+            detect_color_depth(None, Some("screen-256color")),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(detect_color_depth(None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(detect_color_depth(None, None), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let e = TermEnv {
+            colorterm: Some("24bit".into()),
+            no_color: true,
+            tmux: Some("/tmp/tmux-1000/default,4242,0".into()),
+            tmux_pane: Some("%3".into()),
+            ..env("tmux-256color", Some("de_DE.UTF-8"))
+        };
+        let mut metadata = HashMap::new();
+        e.to_metadata(&mut metadata);
+        assert_eq!(TermEnv::from_metadata(&metadata), Some(e));
+        // Events from older hooks carry no terminal info
+        assert_eq!(TermEnv::from_metadata(&HashMap::new()), None);
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(to_ascii("📝 Git Commit"), "* Git Commit");
+        assert_eq!(
+            to_ascii("🏆 First Commit — Made it │ Lvl 2"),
+            "[*] First Commit - Made it | Lvl 2"
+        );
+        assert_eq!(to_ascii("🦀 Rustacean"), "Rustacean");
+        assert_eq!(to_ascii("plain text"), "plain text");
+    }
+}
@@ -0,0 +1,604 @@
+use crate::config::{ColorDepth, VisualConfig};
+use crate::terminal::{TermCaps, to_ascii};
+use anyhow::Context;
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Names of the themes that ship with cwinner.
+pub const BUILTIN: &[&str] = &["default", "monochrome", "ascii", "high-contrast", "holiday"];
+
+/// A color as written in a theme: one of the 16 terminal color names (always
+/// rendered as-is), `"#rrggbb"` (downsampled to the terminal's color depth), or
+/// `"default"` for the terminal's own foreground.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ThemeColor {
+    Default,
+    Named(Color),
+    Rgb(u8, u8, u8),
+}
+
+const NAMED_COLORS: &[(&str, Color)] = &[
+    ("black", Color::Black),
+    ("dark_red", Color::DarkRed),
+    ("dark_green", Color::DarkGreen),
+    ("dark_yellow", Color::DarkYellow),
+    ("dark_blue", Color::DarkBlue),
+    ("dark_magenta", Color::DarkMagenta),
+    ("dark_cyan", Color::DarkCyan),
+    ("grey", Color::Grey),
+    ("dark_grey", Color::DarkGrey),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("white", Color::White),
+];
+
+/// xterm's default RGB values for `NAMED_COLORS`, used to downsample to 16 colors.
+const NAMED_RGB: &[(u8, u8, u8)] = &[
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+impl TryFrom<String> for ThemeColor {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let s = s.trim().to_ascii_lowercase();
+        if s == "default" {
+            return Ok(ThemeColor::Default);
+        }
+        if let Some(hex) = s.strip_prefix('#') {
+            let channel = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+            };
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(ThemeColor::Rgb(r, g, b)),
+                _ => Err(format!("invalid hex color {s:?} (expected #rrggbb)")),
+            };
+        }
+        NAMED_COLORS
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|&(_, c)| ThemeColor::Named(c))
+            .ok_or_else(|| format!("unknown color {s:?}"))
+    }
+}
+
+impl From<ThemeColor> for String {
+    fn from(c: ThemeColor) -> Self {
+        match c {
+            ThemeColor::Default => "default".into(),
+            ThemeColor::Named(color) => NAMED_COLORS
+                .iter()
+                .find(|(_, c)| *c == color)
+                .map(|(name, _)| name.to_string())
+                .unwrap_or_else(|| "default".into()),
+            ThemeColor::Rgb(r, g, b) => format!("#{r:02x}{g:02x}{b:02x}"),
+        }
+    }
+}
+
+impl ThemeColor {
+    fn named(name: &str) -> Self {
+        ThemeColor::try_from(name.to_string()).unwrap_or(ThemeColor::Default)
+    }
+
+    /// The terminal color to emit at the given color depth.
+    pub fn to_color(self, depth: ColorDepth) -> Color {
+        match self {
+            ThemeColor::Default => Color::Reset,
+            ThemeColor::Named(c) => c,
+            ThemeColor::Rgb(r, g, b) => match depth {
+                ColorDepth::Auto | ColorDepth::Truecolor => Color::Rgb { r, g, b },
+                ColorDepth::Ansi256 => Color::AnsiValue(rgb_to_ansi256(r, g, b)),
+                ColorDepth::Ansi16 => rgb_to_named(r, g, b),
+            },
+        }
+    }
+}
+
+/// Nearest entry in the xterm 256-color palette (6×6×6 cube or grey ramp).
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    if r == g && g == b {
+        return match r {
+            0..=7 => 16,
+            249..=255 => 231,
+            v => 232 + ((v as u16 - 8 + 5) / 10).min(23) as u8,
+        };
+    }
+    // Cube levels are 0, 95, 135, 175, 215, 255
+    let level = |v: u8| match v {
+        0..=47 => 0,
+        48..=114 => 1,
+        v => (v as u16 - 35) / 40,
+    };
+    (16 + 36 * level(r) + 6 * level(g) + level(b)) as u8
+}
+
+/// Nearest of the 16 named terminal colors.
+pub fn rgb_to_named(r: u8, g: u8, b: u8) -> Color {
+    let dist = |&(nr, ng, nb): &(u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, nr) + d(g, ng) + d(b, nb)
+    };
+    NAMED_RGB
+        .iter()
+        .zip(NAMED_COLORS)
+        .min_by_key(|(rgb, _)| dist(rgb))
+        .map(|(_, &(_, c))| c)
+        .unwrap_or(Color::Reset)
+}
+
+/// Colors for each part of a celebration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Palette {
+    /// Plain progress toast.
+    pub toast: ThemeColor,
+    /// Achievement toast and splash achievement lines.
+    pub achievement: ThemeColor,
+    /// Splash box border.
+    pub border: ThemeColor,
+    /// Splash event label.
+    pub label: ThemeColor,
+    /// Splash level line.
+    pub level: ThemeColor,
+    pub confetti: Vec<ThemeColor>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            toast: ThemeColor::named("cyan"),
+            achievement: ThemeColor::named("yellow"),
+            border: ThemeColor::named("yellow"),
+            label: ThemeColor::named("green"),
+            level: ThemeColor::named("cyan"),
+            confetti: ["red", "green", "yellow", "blue", "magenta", "cyan", "white"]
+                .iter()
+                .map(|n| ThemeColor::named(n))
+                .collect(),
+        }
+    }
+}
+
+/// Characters and symbols drawn by the renderer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Glyphs {
+    pub confetti: Vec<char>,
+    pub trophy: String,
+    /// Prefix for toasts and splashes without an event label.
+    pub bolt: String,
+    /// Separator in the splash level line.
+    pub sparkle: String,
+    /// Separator between toast fields.
+    pub divider: String,
+    pub bar_filled: char,
+    pub bar_empty: char,
+}
+
+impl Default for Glyphs {
+    fn default() -> Self {
+        Self {
+            confetti: vec!['✦', '★', '♦', '●', '*', '+', '#', '✿', '❋'],
+            trophy: "🏆".into(),
+            bolt: "⚡".into(),
+            sparkle: "✦".into(),
+            divider: "│".into(),
+            bar_filled: '█',
+            bar_empty: '░',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderStyle {
+    #[default]
+    Double,
+    Single,
+    Rounded,
+    Heavy,
+    Ascii,
+}
+
+/// Box-drawing characters for a border style.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BorderChars {
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub vertical: char,
+}
+
+impl BorderStyle {
+    pub fn chars(self) -> BorderChars {
+        let [
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        ] = match self {
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            BorderStyle::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            BorderStyle::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+            BorderStyle::Heavy => ['┏', '┓', '┗', '┛', '━', '┃'],
+            BorderStyle::Ascii => ['+', '+', '+', '+', '-', '|'],
+        };
+        BorderChars {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        }
+    }
+}
+
+/// How celebrations look: palette, glyphs and border style. Custom themes
+/// live in `~/.config/cwinner/themes/<name>.toml` and only need the fields
+/// they change; everything else falls back to the default theme.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub palette: Palette,
+    pub glyphs: Glyphs,
+    pub border: BorderStyle,
+    /// Color depth to render at; set from `[visual] color_depth` on load.
+    #[serde(skip)]
+    pub depth: ColorDepth,
+    /// Draw with ASCII only; set on load for terminals without Unicode.
+    #[serde(skip)]
+    pub ascii_only: bool,
+    /// Emit no color codes; set on load for `NO_COLOR` and dumb terminals.
+    #[serde(skip)]
+    pub no_color: bool,
+}
+
+impl Theme {
+    /// A built-in theme by name.
+    pub fn builtin(name: &str) -> Option<Theme> {
+        let theme = match name {
+            "default" => Theme::default(),
+            "monochrome" => {
+                let plain = ThemeColor::Default;
+                Theme {
+                    palette: Palette {
+                        toast: plain,
+                        achievement: plain,
+                        border: plain,
+                        label: plain,
+                        level: plain,
+                        confetti: vec![plain],
+                    },
+                    border: BorderStyle::Single,
+                    ..Theme::default()
+                }
+            }
+            "ascii" => Theme {
+                glyphs: Glyphs {
+                    confetti: vec!['*', '+', 'o', '.', 'x', '#', '~', '^'],
+                    trophy: "[*]".into(),
+                    bolt: ">>".into(),
+                    sparkle: "*".into(),
+                    divider: "|".into(),
+                    bar_filled: '#',
+                    bar_empty: '-',
+                },
+                border: BorderStyle::Ascii,
+                ..Theme::default()
+            },
+            "high-contrast" => Theme {
+                palette: Palette {
+                    toast: ThemeColor::named("white"),
+                    achievement: ThemeColor::named("yellow"),
+                    border: ThemeColor::named("white"),
+                    label: ThemeColor::named("white"),
+                    level: ThemeColor::named("yellow"),
+                    confetti: vec![ThemeColor::named("white"), ThemeColor::named("yellow")],
+                },
+                glyphs: Glyphs {
+                    bar_empty: '·',
+                    ..Glyphs::default()
+                },
+                border: BorderStyle::Heavy,
+                ..Theme::default()
+            },
+            "holiday" => Theme {
+                palette: Palette {
+                    toast: ThemeColor::Rgb(0xd4, 0xaf, 0x37),
+                    achievement: ThemeColor::Rgb(0xd4, 0xaf, 0x37),
+                    border: ThemeColor::Rgb(0xc0, 0x1c, 0x28),
+                    label: ThemeColor::Rgb(0x2e, 0xa0, 0x43),
+                    level: ThemeColor::Rgb(0xf5, 0xf5, 0xf5),
+                    confetti: vec![
+                        ThemeColor::Rgb(0xc0, 0x1c, 0x28),
+                        ThemeColor::Rgb(0x2e, 0xa0, 0x43),
+                        ThemeColor::Rgb(0xd4, 0xaf, 0x37),
+                        ThemeColor::Rgb(0xf5, 0xf5, 0xf5),
+                    ],
+                },
+                glyphs: Glyphs {
+                    confetti: vec!['❄', '❅', '❆', '✦', '★', '*'],
+                    trophy: "🎁".into(),
+                    sparkle: "❄".into(),
+                    ..Glyphs::default()
+                },
+                border: BorderStyle::Double,
+                ..Theme::default()
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    /// Directory holding custom theme files.
+    pub fn themes_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|d| d.join("cwinner").join("themes"))
+    }
+
+    pub fn load_from(path: &Path) -> anyhow::Result<Theme> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading theme {}", path.display()))?;
+        let theme: Theme =
+            toml::from_str(&text).with_context(|| format!("parsing theme {}", path.display()))?;
+        if theme.glyphs.confetti.is_empty() || theme.palette.confetti.is_empty() {
+            anyhow::bail!("theme {} has no confetti glyphs or colors", path.display());
+        }
+        Ok(theme)
+    }
+
+    /// A built-in theme, or `<name>.toml` from the themes directory.
+    pub fn load_named(name: &str) -> anyhow::Result<Theme> {
+        if let Some(theme) = Theme::builtin(name) {
+            return Ok(theme);
+        }
+        let dir = Theme::themes_dir().ok_or_else(|| anyhow::anyhow!("no config directory"))?;
+        let path = dir.join(format!("{name}.toml"));
+        if !path.exists() {
+            anyhow::bail!("unknown theme {name:?}");
+        }
+        Theme::load_from(&path)
+    }
+
+    /// The theme selected by `[visual]` for the terminal this process runs in.
+    pub fn from_config(cfg: &VisualConfig) -> Theme {
+        Theme::for_terminal(cfg, &TermCaps::current())
+    }
+
+    /// The theme selected by `[visual]`, falling back to the default theme if
+    /// it can't be loaded, adapted to what the terminal can display.
+    pub fn for_terminal(cfg: &VisualConfig, caps: &TermCaps) -> Theme {
+        let mut theme = Theme::load_named(&cfg.theme).unwrap_or_else(|e| {
+            eprintln!("[cwinner] theme: {e:#}; using default");
+            Theme::default()
+        });
+        theme.depth = match cfg.color_depth {
+            ColorDepth::Auto => caps.depth,
+            depth => depth,
+        };
+        if !caps.unicode {
+            theme.make_ascii();
+        }
+        theme.no_color = !caps.color;
+        theme
+    }
+
+    /// Swap every non-ASCII glyph and the border for the ASCII theme's.
+    fn make_ascii(&mut self) {
+        let ascii = Theme::builtin("ascii").unwrap_or_default();
+        let (g, a) = (&mut self.glyphs, ascii.glyphs);
+        g.confetti.retain(char::is_ascii);
+        if g.confetti.is_empty() {
+            g.confetti = a.confetti;
+        }
+        for (glyph, fallback) in [
+            (&mut g.trophy, a.trophy),
+            (&mut g.bolt, a.bolt),
+            (&mut g.sparkle, a.sparkle),
+            (&mut g.divider, a.divider),
+        ] {
+            if !glyph.is_ascii() {
+                *glyph = fallback;
+            }
+        }
+        if !g.bar_filled.is_ascii() || !g.bar_empty.is_ascii() {
+            g.bar_filled = a.bar_filled;
+            g.bar_empty = a.bar_empty;
+        }
+        self.border = BorderStyle::Ascii;
+        self.ascii_only = true;
+    }
+
+    /// Text as this theme can show it: reduced to ASCII on limited terminals.
+    pub fn text<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.ascii_only && !s.is_ascii() {
+            Cow::Owned(to_ascii(s))
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+
+    /// Names of all available themes: built-ins, then custom files.
+    pub fn available() -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN.iter().map(|s| s.to_string()).collect();
+        if let Some(entries) = Theme::themes_dir().and_then(|d| std::fs::read_dir(d).ok()) {
+            let mut custom: Vec<String> = entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|x| x == "toml"))
+                .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+                .filter(|n| !names.contains(n))
+                .collect();
+            custom.sort();
+            names.extend(custom);
+        }
+        names
+    }
+
+    /// Terminal color for a palette entry.
+    pub fn fg(&self, c: ThemeColor) -> Color {
+        c.to_color(self.depth)
+    }
+
+    /// An XP bar drawn with this theme's glyphs.
+    pub fn bar(&self, current_xp: u32, next_xp: u32, width: usize) -> String {
+        crate::renderer::xp_bar_with(
+            current_xp,
+            next_xp,
+            width,
+            self.glyphs.bar_filled,
+            self.glyphs.bar_empty,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_builtins_load() {
+        for name in BUILTIN {
+            let theme = Theme::builtin(name).unwrap();
+            assert!(!theme.glyphs.confetti.is_empty(), "{name}");
+            assert!(!theme.palette.confetti.is_empty(), "{name}");
+        }
+        assert!(Theme::builtin("nope").is_none());
+    }
+
+    #[test]
+    fn test_ascii_theme_is_ascii() {
+        let theme = Theme::builtin("ascii").unwrap();
+        let g = &theme.glyphs;
+        assert!(g.confetti.iter().all(char::is_ascii));
+        assert!(g.trophy.is_ascii() && g.bolt.is_ascii() && g.sparkle.is_ascii());
+        assert!(g.bar_filled.is_ascii() && g.bar_empty.is_ascii());
+        let b = theme.border.chars();
+        assert!(
+            [b.top_left, b.horizontal, b.vertical]
+                .iter()
+                .all(char::is_ascii)
+        );
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        let parse = |s: &str| ThemeColor::try_from(s.to_string());
+        assert_eq!(parse("Yellow"), Ok(ThemeColor::Named(Color::Yellow)));
+        assert_eq!(parse("#ff8000"), Ok(ThemeColor::Rgb(255, 128, 0)));
+        assert_eq!(parse("default"), Ok(ThemeColor::Default));
+        assert!(parse("#ff80").is_err());
+        assert!(parse("chartreuse").is_err());
+        assert_eq!(String::from(ThemeColor::Rgb(1, 2, 255)), "#0102ff");
+    }
+
+    #[test]
+    fn test_color_depth_downsampling() {
+        let orange = ThemeColor::Rgb(255, 135, 0);
+        assert_eq!(
+            orange.to_color(ColorDepth::Truecolor),
+            Color::Rgb {
+                r: 255,
+                g: 135,
+                b: 0
+            }
+        );
+        assert_eq!(orange.to_color(ColorDepth::Ansi256), Color::AnsiValue(208));
+        assert_eq!(
+            ThemeColor::Rgb(250, 10, 10).to_color(ColorDepth::Ansi16),
+            Color::Red
+        );
+        // Named colors are never remapped
+        let cyan = ThemeColor::Named(Color::Cyan);
+        assert_eq!(cyan.to_color(ColorDepth::Ansi256), Color::Cyan);
+        assert_eq!(rgb_to_ansi256(128, 128, 128), 244);
+    }
+
+    #[test]
+    fn test_limited_terminal_fallback() {
+        let cfg = VisualConfig {
+            theme: "holiday".into(),
+            ..Default::default()
+        };
+        let caps = TermCaps {
+            unicode: false,
+            color: false,
+            depth: ColorDepth::Ansi16,
+        };
+        let theme = Theme::for_terminal(&cfg, &caps);
+        assert!(theme.ascii_only && theme.no_color);
+        assert_eq!(theme.border, BorderStyle::Ascii);
+        assert!(theme.glyphs.confetti.iter().all(char::is_ascii));
+        assert!(theme.glyphs.trophy.is_ascii());
+        // The holiday palette is kept, just downsampled
+        assert_eq!(theme.depth, ColorDepth::Ansi16);
+        assert_eq!(theme.text("📝 Git Commit"), "* Git Commit");
+
+        let caps = TermCaps {
+            unicode: true,
+            color: true,
+            depth: ColorDepth::Truecolor,
+        };
+        let theme = Theme::for_terminal(&cfg, &caps);
+        assert!(!theme.ascii_only && !theme.no_color);
+        assert_eq!(theme.text("📝 Git Commit"), "📝 Git Commit");
+    }
+
+    #[test]
+    fn test_custom_theme_overrides_only_given_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mine.toml");
+        std::fs::write(
+            &path,
+            r##"
+border = "rounded"
+
+[palette]
+achievement = "#ff00ff"
+
+[glyphs]
+trophy = "<>"
+"##,
+        )
+        .unwrap();
+        let theme = Theme::load_from(&path).unwrap();
+        assert_eq!(theme.border, BorderStyle::Rounded);
+        assert_eq!(theme.palette.achievement, ThemeColor::Rgb(255, 0, 255));
+        assert_eq!(theme.palette.toast, Palette::default().toast);
+        assert_eq!(theme.glyphs.trophy, "<>");
+        assert_eq!(theme.glyphs.bolt, "⚡");
+
+        std::fs::write(&path, "[glyphs]\nconfetti = []\n").unwrap();
+        assert!(Theme::load_from(&path).is_err());
+        std::fs::write(&path, "sparkles = true\n").unwrap();
+        assert!(Theme::load_from(&path).is_err());
+    }
+}
@@ -194,21 +194,48 @@ fn parse_sha256_digest(checksum_file: &str) -> Option<String> {
     }
 }
 
-/// Compute the SHA-256 of a file using the system tool (`sha256sum` on Linux,
-/// `shasum -a 256` on macOS) and return the lowercase hex digest.
+/// The system SHA-256 tool (`sha256sum` on Linux, `shasum -a 256` on macOS)
+/// and its name.
+fn sha256_command() -> (Command, &'static str) {
+    if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("shasum");
+        cmd.args(["-a", "256"]);
+        (cmd, "shasum")
+    } else {
+        (Command::new("sha256sum"), "sha256sum")
+    }
+}
+
+/// Compute the SHA-256 of a file using the system tool and return the
+/// lowercase hex digest.
 fn sha256_of_file(path: &Path) -> Result<String> {
     let path_str = path.to_str().context("non-UTF-8 path")?;
-    let (cmd, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
-        ("shasum", vec!["-a", "256", path_str])
-    } else {
-        ("sha256sum", vec![path_str])
-    };
-    let output = Command::new(cmd)
-        .args(&args)
+    let (mut cmd, name) = sha256_command();
+    let output = cmd
+        .arg(path_str)
         .output()
-        .with_context(|| format!("failed to run {cmd}"))?;
+        .with_context(|| format!("failed to run {name}"))?;
     if !output.status.success() {
-        bail!("{cmd} failed to hash {path_str}");
+        bail!("{name} failed to hash {path_str}");
+    }
+    parse_sha256_digest(&String::from_utf8_lossy(&output.stdout))
+        .context("could not parse checksum tool output")
+}
+
+/// SHA-256 of `data` as a lowercase hex digest, piped through the system tool.
+pub fn sha256_of_bytes(data: &[u8]) -> Result<String> {
+    use std::io::Write;
+    use std::process::Stdio;
+    let (mut cmd, name) = sha256_command();
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run {name}"))?;
+    child.stdin.take().context("no stdin")?.write_all(data)?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        bail!("{name} failed");
     }
     parse_sha256_digest(&String::from_utf8_lossy(&output.stdout))
         .context("could not parse checksum tool output")
@@ -243,6 +270,14 @@ mod tests {
         assert_eq!(parse_sha256_digest("deadbeef short"), None);
     }
 
+    #[test]
+    fn test_sha256_of_bytes_matches_known_vector() {
+        assert_eq!(
+            sha256_of_bytes(b"abc").unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
     #[test]
     fn test_sha256_of_file_matches_known_vector() {
         // SHA-256 of the bytes "hello" (no trailing newline) is well-known.
@@ -0,0 +1,635 @@
+//! The XP economy (`[xp]`): what an event is worth, and why. Routine tool
+//! calls are also weighed against the last hour of calls (`[xp.routine]`).
+
+use crate::celebration::{CelebrationLevel, effective_event_kind};
+use crate::config::{RoutineXp, XpConfig};
+use crate::event::{Event, EventKind};
+use crate::state::State;
+use anyhow::{Context, bail};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// An event's name in `[xp]` tables: its effective kind in snake_case.
+pub fn event_key(event: &Event) -> &'static str {
+    match effective_event_kind(event) {
+        EventKind::PostToolUse => "post_tool_use",
+        EventKind::PostToolUseFailure => "post_tool_use_failure",
+        EventKind::TaskCompleted => "task_completed",
+        EventKind::SessionEnd => "session_end",
+        EventKind::GitCommit => "git_commit",
+        EventKind::GitPush => "git_push",
+        EventKind::UserDefined => "user_defined",
+    }
+}
+
+/// Keys that can configure `event`, most specific first: the tool's
+/// (`post_tool_use.Bash`), then the event's.
+fn keys(event: &Event) -> Vec<String> {
+    let key = event_key(event);
+    let mut keys = Vec::new();
+    if let (Some(tool), "post_tool_use") = (&event.tool, key) {
+        keys.push(format!("{key}.{tool}"));
+    }
+    keys.push(key.to_string());
+    keys
+}
+
+/// Tools that only look at files.
+const READ_TOOLS: &[&str] = &["Read", "Glob", "Grep", "LS"];
+/// Shell commands that only look at things.
+const READ_COMMANDS: &[&str] = &[
+    "ls", "pwd", "cat", "head", "tail", "less", "wc", "find", "tree", "echo", "which", "stat",
+    "file", "du", "df",
+];
+const READ_GIT: &[&str] = &["status", "log", "diff", "show", "branch"];
+/// Flags that make a looking command change things (`find -delete`,
+/// `git branch -D`).
+const WRITE_FLAGS: &[&str] = &[
+    "-delete", "-exec", "-execdir", "-ok", "-okdir", "-D", "-d", "-m", "-M", "--delete", "--move",
+];
+/// How far back routine calls are kept, at least.
+const HISTORY: Duration = Duration::hours(1);
+/// Most routine calls kept.
+const HISTORY_MAX: usize = 1000;
+
+/// Whether every command in a shell line only looks at things. Any
+/// redirection counts as a write.
+fn is_read_only(command: &str) -> bool {
+    !command.contains('>')
+        && command
+            .split(['&', '|', ';'])
+            .map(|part| part.split_whitespace().collect::<Vec<_>>())
+            .filter(|words| !words.is_empty())
+            .all(|words| {
+                let looks = match words[0] {
+                    "git" => words.get(1).is_some_and(|w| READ_GIT.contains(w)),
+                    word => READ_COMMANDS.contains(&word),
+                };
+                looks && !words.iter().any(|w| WRITE_FLAGS.contains(w))
+            })
+}
+
+/// A successful tool call, as far as repeats are concerned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Call {
+    #[serde(default)]
+    pub tool: String,
+    /// Fingerprint of the tool and what it was called with; identical
+    /// calls share it. The call itself isn't kept.
+    pub what: String,
+    /// Whether the call only looked at things.
+    pub read: bool,
+    /// Whether it is a routine call (at most a mini celebration), counted
+    /// against `xp.routine.hourly_cap`.
+    pub routine: bool,
+}
+
+impl Call {
+    /// `event` as a call, if it is a successful tool call whose input is
+    /// known. Shell lines are told apart by their command, other tools by
+    /// their whole input.
+    fn of(event: &Event, level: &CelebrationLevel) -> Option<Call> {
+        if event_key(event) != "post_tool_use" {
+            return None;
+        }
+        let tool = event.tool.as_deref()?;
+        let meta = |k: &str| event.metadata.get(k).and_then(|v| v.as_str());
+        let (what, read) = match meta("command") {
+            Some(command) if tool == "Bash" => (
+                command.split_whitespace().collect::<Vec<_>>().join(" "),
+                is_read_only(command),
+            ),
+            _ => (
+                meta("tool_input_hash")?.to_string(),
+                READ_TOOLS.contains(&tool),
+            ),
+        };
+        Some(Call {
+            tool: tool.to_string(),
+            what: fingerprint(&format!("{tool}: {what}")),
+            read,
+            routine: *level <= CelebrationLevel::Mini,
+        })
+    }
+}
+
+/// A short fingerprint of `text` (64-bit FNV-1a), the same across runs.
+pub fn fingerprint(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// A tool call in the ledger's recent history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Recent {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub call: Call,
+    pub xp: u32,
+}
+
+/// XP earned today, for the daily caps, and the last hour's tool calls,
+/// for `xp.routine`. Kept per machine.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct XpLedger {
+    /// The day the totals are for.
+    pub day: Option<NaiveDate>,
+    pub total: u32,
+    /// By every key that could configure the event.
+    pub by_key: BTreeMap<String, u32>,
+    /// Oldest first.
+    pub recent: Vec<Recent>,
+}
+
+impl XpLedger {
+    fn earned(&self, day: NaiveDate, key: Option<&str>) -> u32 {
+        if self.day != Some(day) {
+            return 0;
+        }
+        match key {
+            Some(key) => self.by_key.get(key).copied().unwrap_or(0),
+            None => self.total,
+        }
+    }
+
+    /// Whether `call` reads what an earlier read did, with nothing but
+    /// reads since.
+    fn is_noop_read(&self, call: &Call) -> bool {
+        call.read
+            && self
+                .recent
+                .iter()
+                .rev()
+                .take_while(|r| r.call.read)
+                .any(|r| r.call.what == call.what)
+    }
+
+    /// Count `award`, made at `now` on `day` for `event`.
+    pub fn record(&mut self, now: DateTime<Utc>, day: NaiveDate, event: &Event, award: &Award) {
+        if self.day != Some(day) {
+            self.day = Some(day);
+            self.total = 0;
+            self.by_key.clear();
+        }
+        self.total = self.total.saturating_add(award.xp);
+        for key in keys(event) {
+            let earned = self.by_key.entry(key).or_default();
+            *earned = earned.saturating_add(award.xp);
+        }
+        if let Some(call) = &award.call {
+            self.recent.push(Recent {
+                at: now,
+                call: call.clone(),
+                xp: award.xp,
+            });
+        }
+        self.recent.retain(|r| now - r.at <= HISTORY);
+        let excess = self.recent.len().saturating_sub(HISTORY_MAX);
+        self.recent.drain(..excess);
+    }
+}
+
+/// One step of an award: what happened, and the XP after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub what: String,
+    pub xp: u32,
+}
+
+/// The XP for an event, with how it was reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Award {
+    pub xp: u32,
+    pub steps: Vec<Step>,
+    /// The tool call the event was, if it was one.
+    pub call: Option<Call>,
+}
+
+impl Award {
+    fn step(&mut self, what: String, xp: u32) {
+        self.xp = xp;
+        self.steps.push(Step { what, xp });
+    }
+
+    /// The steps as a table, ending in the total.
+    pub fn explain(&self) -> String {
+        let mut out = String::new();
+        for step in &self.steps {
+            let _ = writeln!(out, "  {:<50}{:>6}", step.what, step.xp);
+        }
+        let _ = writeln!(out, "= {} XP", self.xp);
+        out
+    }
+}
+
+/// An event as hooks send it, for `cwinner xp explain`. `session_id` and
+/// `tty_path` may be left out.
+pub fn parse_event(data: &str) -> anyhow::Result<Event> {
+    let mut value: Value = serde_json::from_str(data).context("not JSON")?;
+    let Some(obj) = value.as_object_mut() else {
+        bail!("expected a JSON object");
+    };
+    for field in ["session_id", "tty_path"] {
+        obj.entry(field).or_insert_with(|| Value::from(""));
+    }
+    serde_json::from_value(value).context("not a cwinner event")
+}
+
+/// The XP `event`, celebrated at `level`, earns at `now` (on `day`) given
+/// `state`.
+pub fn award(
+    event: &Event,
+    level: &CelebrationLevel,
+    state: &State,
+    cfg: &XpConfig,
+    now: DateTime<Utc>,
+    day: NaiveDate,
+) -> Award {
+    let mut award = Award {
+        xp: 0,
+        steps: vec![],
+        call: Call::of(event, level),
+    };
+    let keys = keys(event);
+    match keys.iter().find_map(|k| Some((k, *cfg.events.get(k)?))) {
+        Some((key, xp)) => award.step(format!("xp.events.{key}"), xp),
+        None => {
+            let (name, xp) = match level {
+                CelebrationLevel::Off => ("off", 0),
+                CelebrationLevel::Mini => ("mini", cfg.base.mini),
+                CelebrationLevel::Medium => ("medium", cfg.base.medium),
+                CelebrationLevel::Epic => ("epic", cfg.base.epic),
+            };
+            award.step(format!("{name} celebration (xp.base.{name})"), xp);
+        }
+    }
+    if award.xp == 0 {
+        return award;
+    }
+
+    let streak = state.commit_streak_days;
+    let tier = cfg
+        .streak_tiers
+        .iter()
+        .filter(|t| streak >= t.days)
+        .max_by_key(|t| t.days);
+    if let Some(tier) = tier.filter(|t| t.multiplier != 1.0) {
+        let xp = (award.xp as f64 * tier.multiplier).round().max(0.0) as u32;
+        award.step(
+            format!(
+                "× {} for a {}+ day streak ({streak} days)",
+                tier.multiplier, tier.days
+            ),
+            xp,
+        );
+    }
+
+    let ledger = &state.xp_ledger;
+    if let Some(call) = award.call.clone() {
+        routine(&mut award, &call, ledger, &cfg.routine, now);
+    }
+    if let Some((key, cap)) = keys.iter().find_map(|k| Some((k, *cfg.daily_caps.get(k)?))) {
+        let left = cap.saturating_sub(ledger.earned(day, Some(key)));
+        if award.xp > left {
+            award.step(
+                format!("xp.daily_caps.{key}: {left} of {cap} left today"),
+                left,
+            );
+        }
+    }
+    if cfg.daily_cap > 0 {
+        let left = cfg.daily_cap.saturating_sub(ledger.earned(day, None));
+        if award.xp > left {
+            award.step(
+                format!("xp.daily_cap: {left} of {} left today", cfg.daily_cap),
+                left,
+            );
+        }
+    }
+    award
+}
+
+/// Apply `xp.routine` to a tool call.
+fn routine(award: &mut Award, call: &Call, ledger: &XpLedger, cfg: &RoutineXp, now: DateTime<Utc>) {
+    if cfg.ignore_noop_reads && ledger.is_noop_read(call) {
+        award.step(
+            format!("no-op read: same {} again, nothing changed", call.tool),
+            0,
+        );
+        return;
+    }
+    let window = Duration::seconds(cfg.repeat_window_secs.min(i64::MAX as u64) as i64);
+    let repeats = ledger
+        .recent
+        .iter()
+        .filter(|r| r.call.what == call.what && now - r.at <= window)
+        .count();
+    if repeats > 0 && cfg.repeat_decay < 1.0 {
+        let factor = cfg.repeat_decay.max(0.0).powi(repeats.min(64) as i32);
+        let xp = (award.xp as f64 * factor).floor() as u32;
+        award.step(
+            format!(
+                "× {} for {repeats} identical call(s) in {}s",
+                cfg.repeat_decay, cfg.repeat_window_secs
+            ),
+            xp,
+        );
+    }
+    if call.routine && cfg.hourly_cap > 0 {
+        let earned: u32 = ledger
+            .recent
+            .iter()
+            .filter(|r| r.call.routine && now - r.at <= HISTORY)
+            .map(|r| r.xp)
+            .sum();
+        let left = cfg.hourly_cap.saturating_sub(earned);
+        if award.xp > left {
+            award.step(
+                format!(
+                    "xp.routine.hourly_cap: {left} of {} left this hour",
+                    cfg.hourly_cap
+                ),
+                left,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StreakTier;
+    use std::collections::HashMap;
+
+    fn event(kind: EventKind, tool: Option<&str>) -> Event {
+        Event {
+            event: kind,
+            tool: tool.map(String::from),
+            session_id: "s".into(),
+            tty_path: "/dev/null".into(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn tool_call(tool: &str, key: &str, arg: &str) -> Event {
+        let mut e = event(EventKind::PostToolUse, Some(tool));
+        let input = serde_json::json!({ key: arg });
+        e.metadata.insert(
+            "tool_input_hash".into(),
+            fingerprint(&input.to_string()).into(),
+        );
+        if key == "command" {
+            e.metadata.insert(key.into(), arg.into());
+        }
+        e
+    }
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 18).unwrap()
+    }
+
+    fn now() -> DateTime<Utc> {
+        day().and_hms_opt(12, 0, 0).unwrap().and_utc()
+    }
+
+    /// Award `e` at `at` and record it, returning the XP.
+    fn earn(state: &mut State, cfg: &XpConfig, e: &Event, at: DateTime<Utc>) -> u32 {
+        let a = award(e, &CelebrationLevel::Mini, state, cfg, at, day());
+        state.xp_ledger.record(at, day(), e, &a);
+        a.xp
+    }
+
+    #[test]
+    fn test_streak_bonus_doubles_xp() {
+        let state = State {
+            commit_streak_days: 5,
+            ..Default::default()
+        };
+        let cfg = XpConfig::default();
+        let e = event(EventKind::TaskCompleted, None);
+        let medium = CelebrationLevel::Medium;
+        assert_eq!(award(&e, &medium, &state, &cfg, now(), day()).xp, 50); // 25 * 2
+        let state = State::default();
+        assert_eq!(award(&e, &medium, &state, &cfg, now(), day()).xp, 25);
+    }
+
+    #[test]
+    fn test_overrides_and_tiers() {
+        let mut cfg = XpConfig::default();
+        cfg.events.insert("post_tool_use".into(), 2);
+        cfg.events.insert("post_tool_use.Read".into(), 0);
+        cfg.streak_tiers.push(StreakTier {
+            days: 30,
+            multiplier: 3.0,
+        });
+        let state = State {
+            commit_streak_days: 40,
+            ..Default::default()
+        };
+        let mini = CelebrationLevel::Mini;
+        let bash = event(EventKind::PostToolUse, Some("Bash"));
+        assert_eq!(award(&bash, &mini, &state, &cfg, now(), day()).xp, 6);
+        let read = event(EventKind::PostToolUse, Some("Read"));
+        assert_eq!(award(&read, &mini, &state, &cfg, now(), day()).xp, 0);
+    }
+
+    #[test]
+    fn test_daily_caps() {
+        let mut cfg = XpConfig::default();
+        cfg.daily_caps.insert("post_tool_use".into(), 12);
+        cfg.daily_cap = 100;
+        let mut state = State::default();
+        let mut earned = vec![];
+        for file in ["a", "b", "c", "d"] {
+            let edit = tool_call("Edit", "file_path", file);
+            earned.push(earn(&mut state, &cfg, &edit, now()));
+        }
+        assert_eq!(earned, vec![5, 5, 2, 0]);
+
+        let push = event(EventKind::GitPush, None);
+        let a = award(&push, &CelebrationLevel::Epic, &state, &cfg, now(), day());
+        assert_eq!(a.xp, 88);
+        // A new day starts from zero
+        let tomorrow = day().succ_opt().unwrap();
+        let edit = tool_call("Edit", "file_path", "e");
+        let mini = CelebrationLevel::Mini;
+        assert_eq!(award(&edit, &mini, &state, &cfg, now(), tomorrow).xp, 5);
+    }
+
+    #[test]
+    fn test_ledger_saturates() {
+        let mut ledger = XpLedger::default();
+        let push = event(EventKind::GitPush, None);
+        let huge = Award {
+            xp: u32::MAX,
+            steps: vec![],
+            call: None,
+        };
+        ledger.record(now(), day(), &push, &huge);
+        ledger.record(now(), day(), &push, &huge);
+        assert_eq!(ledger.total, u32::MAX);
+        assert!(ledger.by_key.values().all(|&xp| xp == u32::MAX));
+    }
+
+    #[test]
+    fn test_identical_calls_earn_less() {
+        let cfg = XpConfig::default();
+        let mut state = State::default();
+        let test = tool_call("Bash", "command", "cargo  build");
+        let earned: Vec<u32> = (0..4)
+            .map(|i| earn(&mut state, &cfg, &test, now() + Duration::seconds(i)))
+            .collect();
+        assert_eq!(earned, vec![5, 2, 1, 0]);
+        // Something else still earns in full
+        let other = tool_call("Bash", "command", "cargo fmt");
+        assert_eq!(earn(&mut state, &cfg, &other, now()), 5);
+        // Once the window has passed the command earns in full again
+        let later = now() + Duration::minutes(11);
+        assert_eq!(earn(&mut state, &cfg, &test, later), 5);
+    }
+
+    #[test]
+    fn test_calls_differ_by_whole_input() {
+        let cfg = XpConfig::default();
+        let mut state = State::default();
+        // Editing one file twice, differently, is two pieces of work
+        for content in ["fn a() {}", "fn b() {}"] {
+            let mut edit = event(EventKind::PostToolUse, Some("Edit"));
+            let input = serde_json::json!({ "file_path": "src/lib.rs", "new_string": content });
+            edit.metadata.insert(
+                "tool_input_hash".into(),
+                fingerprint(&input.to_string()).into(),
+            );
+            assert_eq!(earn(&mut state, &cfg, &edit, now()), 5);
+        }
+        for query in ["rust lifetimes", "tokio select"] {
+            let search = tool_call("WebSearch", "query", query);
+            assert_eq!(earn(&mut state, &cfg, &search, now()), 5);
+        }
+        let search = tool_call("WebSearch", "query", "tokio select");
+        assert_eq!(earn(&mut state, &cfg, &search, now()), 2);
+        // Only fingerprints are kept
+        let ledger = serde_json::to_string(&state.xp_ledger).unwrap();
+        assert!(!ledger.contains("tokio"));
+        assert!(
+            state
+                .xp_ledger
+                .recent
+                .iter()
+                .all(|r| r.call.what.len() == 16)
+        );
+    }
+
+    #[test]
+    fn test_noop_reads_earn_nothing() {
+        let cfg = XpConfig::default();
+        let mut state = State::default();
+        let read = tool_call("Read", "file_path", "src/main.rs");
+        let ls = tool_call("Bash", "command", "ls -la");
+        assert_eq!(earn(&mut state, &cfg, &read, now()), 5);
+        assert_eq!(earn(&mut state, &cfg, &ls, now()), 5);
+        // Reading again with only reads in between is a no-op...
+        let later = now() + Duration::minutes(30);
+        assert_eq!(earn(&mut state, &cfg, &read, later), 0);
+        assert_eq!(earn(&mut state, &cfg, &ls, later), 0);
+        // ...but not after an edit
+        let later = later + Duration::minutes(11);
+        let edit = tool_call("Edit", "file_path", "src/main.rs");
+        assert_eq!(earn(&mut state, &cfg, &edit, later), 5);
+        assert_eq!(earn(&mut state, &cfg, &read, later), 5);
+
+        let cfg = XpConfig {
+            routine: RoutineXp {
+                ignore_noop_reads: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let after_window = later + Duration::minutes(11);
+        assert_eq!(earn(&mut state, &cfg, &ls, after_window), 5);
+    }
+
+    #[test]
+    fn test_is_read_only() {
+        assert!(is_read_only("ls -la && git status | head"));
+        assert!(!is_read_only("ls && rm -rf target"));
+        assert!(!is_read_only("git commit -m x"));
+        assert!(!is_read_only("cargo test"));
+        assert!(!is_read_only("echo x > f"));
+        assert!(!is_read_only("cat a >> b"));
+        assert!(!is_read_only("find . -name '*.o' -delete"));
+        assert!(!is_read_only("find . -exec rm {} \\;"));
+        assert!(!is_read_only("git diff > p.patch"));
+        assert!(!is_read_only("git branch -D foo"));
+        assert!(!is_read_only("ls | tee listing"));
+        assert!(is_read_only("git branch -a"));
+    }
+
+    #[test]
+    fn test_hourly_routine_cap() {
+        let cfg = XpConfig {
+            routine: RoutineXp {
+                hourly_cap: 12,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut state = State::default();
+        let earned: Vec<u32> = (0..4)
+            .map(|i| {
+                let edit = tool_call("Edit", "file_path", &i.to_string());
+                earn(&mut state, &cfg, &edit, now())
+            })
+            .collect();
+        assert_eq!(earned, vec![5, 5, 2, 0]);
+        // Not routine: a commit is not held back
+        let commit = event(EventKind::GitCommit, None);
+        let medium = CelebrationLevel::Medium;
+        assert_eq!(award(&commit, &medium, &state, &cfg, now(), day()).xp, 25);
+        // An hour on, the cap has room again and old calls are dropped
+        let edit = tool_call("Edit", "file_path", "x");
+        let later = now() + Duration::minutes(61);
+        assert_eq!(earn(&mut state, &cfg, &edit, later), 5);
+        assert_eq!(state.xp_ledger.recent.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let e = parse_event(
+            r#"{"event":"PostToolUse","tool":"Bash","metadata":{"command":"git push"}}"#,
+        )
+        .unwrap();
+        assert_eq!(event_key(&e), "git_push");
+        assert!(e.session_id.is_empty());
+        assert!(parse_event("[1]").is_err());
+        assert!(parse_event(r#"{"event":"Nope"}"#).is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let state = State {
+            commit_streak_days: 6,
+            ..Default::default()
+        };
+        let mut cfg = XpConfig::default();
+        cfg.events.insert("git_commit".into(), 40);
+        cfg.daily_cap = 50;
+        let commit = event(EventKind::GitCommit, None);
+        let medium = CelebrationLevel::Medium;
+        let a = award(&commit, &medium, &state, &cfg, now(), day());
+        let text = a.explain();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4, "{text}");
+        assert!(lines[0].starts_with("  xp.events.git_commit"));
+        assert!(lines[0].ends_with("40"));
+        assert!(lines[1].contains("× 2 for a 5+ day streak (6 days)"));
+        assert!(lines[2].contains("xp.daily_cap: 50 of 50 left today"));
+        assert_eq!(lines[3], "= 50 XP");
+    }
+}